@@ -0,0 +1,15 @@
+//! `jws::decode_bytes` must reject any input without panicking, run with `cargo fuzz run decode_bytes`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use serde_json::Value;
+
+use jwts::Claims;
+use jwts::jws::{self, DangerousNoVerify, VerifyWith};
+use jwts::jws::alg::HS256;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = jws::decode_bytes::<Value>(data, DangerousNoVerify);
+    let _ = jws::decode_bytes::<Claims>(data, VerifyWith::<HS256>(b"secret"));
+});
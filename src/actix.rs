@@ -0,0 +1,146 @@
+//! Middleware of actix-web, requires the feature `actix-web`
+//!
+//! `JwtAuth` verifies the bearer token of every request with a `Verifier` and inserts the claims
+//! into the request extensions, where handlers take them with `web::ReqData`. Requests without a
+//! valid token are answered with 401, or 400, 403, 500 and 503 according to `ErrorKind`.
+//!
+//! ```rust,no_run
+//! use actix_web::{web, App, HttpServer};
+//! use jwts::{Claims, Verifier};
+//! use jwts::actix::JwtAuth;
+//! use jwts::jws::VerifyWith;
+//! use jwts::jws::alg::HS256;
+//! use jwts::validate::ExpiredTime;
+//!
+//! async fn whoami(claims: web::ReqData<Claims>) -> String {
+//!     claims.sub.clone().unwrap_or_default()
+//! }
+//!
+//! # async fn run() -> std::io::Result<()> {
+//! let verifier = Verifier::new(VerifyWith::<HS256>(b"secret")).with_validation(ExpiredTime);
+//! let auth = JwtAuth::<_, Claims>::new(verifier).skip("/health");
+//! HttpServer::new(move || {
+//!     App::new()
+//!         .wrap(auth.clone())
+//!         .route("/health", web::get().to(|| async { "ok" }))
+//!         .route("/whoami", web::get().to(whoami))
+//! })
+//! .bind("127.0.0.1:8080")?
+//! .run()
+//! .await
+//! # }
+//! ```
+
+use std::future::{ready, Future, Ready};
+use std::pin::Pin;
+
+use actix_web::{Error, HttpMessage, HttpResponse};
+use actix_web::body::EitherBody;
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::StatusCode;
+use actix_web::http::header;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::auth::Authenticator;
+use crate::extract::TokenExtractor;
+use crate::jws::Verify;
+use crate::verifier::Verifier;
+
+/// Middleware factory verifying the bearer token and inserting the claims `C` into the request extensions.
+pub struct JwtAuth<V, C = Value> {
+    auth: Authenticator<V, C>,
+}
+
+impl<V: Verify<Value>, C: DeserializeOwned> JwtAuth<V, C> {
+    /// Create a `JwtAuth` verifying with the `Verifier`, no route skipped.
+    #[inline]
+    pub fn new(verifier: Verifier<V>) -> Self {
+        JwtAuth { auth: Authenticator::new(verifier) }
+    }
+
+    /// Let requests to the path, or below it, pass without a token; `/` lets the root alone pass.
+    pub fn skip(mut self, path: impl Into<String>) -> Self {
+        self.auth.skip(path.into());
+        self
+    }
+
+    /// Look for the token with the `TokenExtractor`, the bearer `Authorization` header by default.
+    pub fn with_extractor(mut self, extractor: TokenExtractor) -> Self {
+        self.auth.extractor(extractor);
+        self
+    }
+}
+
+impl<V, C> Clone for JwtAuth<V, C> {
+    #[inline]
+    fn clone(&self) -> Self {
+        JwtAuth { auth: self.auth.clone() }
+    }
+}
+
+impl<S, B: 'static, V, C> Transform<S, ServiceRequest> for JwtAuth<V, C>
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error>,
+    S::Future: 'static,
+    V: Verify<Value>,
+    C: DeserializeOwned + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = JwtAuthMiddleware<S, V, C>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    #[inline]
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(JwtAuthMiddleware {
+            service,
+            auth: self.auth.clone(),
+        }))
+    }
+}
+
+/// Middleware created by `JwtAuth`.
+pub struct JwtAuthMiddleware<S, V, C> {
+    service: S,
+    auth: Authenticator<V, C>,
+}
+
+impl<S, B: 'static, V, C> Service<ServiceRequest> for JwtAuthMiddleware<S, V, C>
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error>,
+    S::Future: 'static,
+    V: Verify<Value>,
+    C: DeserializeOwned + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output=Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        if !self.auth.skipped(req.path()) {
+            let headers = req.headers();
+            let authorization = headers.get(header::AUTHORIZATION).map(|x| x.as_bytes());
+            let cookies = headers.get_all(header::COOKIE).map(|x| x.as_bytes());
+            match self.auth.authenticate(authorization, cookies, Some(req.query_string())) {
+                Ok(claims) => {
+                    req.extensions_mut().insert(claims);
+                }
+                Err(status) => {
+                    let status = StatusCode::from_u16(status).unwrap_or(StatusCode::UNAUTHORIZED);
+                    let mut res = HttpResponse::build(status);
+                    if status == StatusCode::UNAUTHORIZED {
+                        res.insert_header((header::WWW_AUTHENTICATE, "Bearer"));
+                    }
+                    let res = req.into_response(res.finish()).map_into_right_body();
+                    return Box::pin(ready(Ok(res)));
+                }
+            }
+        }
+        let res = self.service.call(req);
+        Box::pin(async move { res.await.map(ServiceResponse::map_into_left_body) })
+    }
+}
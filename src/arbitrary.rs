@@ -0,0 +1,99 @@
+//! Implementations of `arbitrary::Arbitrary`, requires the feature `arbitrary`
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+use serde_json::{Map, Value};
+
+use crate::claims::Claims;
+use crate::jwk::Jwk;
+use crate::jws::{Alg, Header, Signature, Token};
+
+/// Registered header parameters, not generated as `extra`.
+const REGISTERED: [&str; 11] = ["typ", "alg", "cty", "jku", "jwk", "kid", "x5u", "x5t", "x5t#S256", "x5c", "crit"];
+
+/// A registered algorithm, or else an unregistered name.
+impl<'a> Arbitrary<'a> for Alg {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(match u.int_in_range(0..=15)? {
+            0 => Alg::HS256,
+            1 => Alg::HS384,
+            2 => Alg::HS512,
+            3 => Alg::RS256,
+            4 => Alg::RS384,
+            5 => Alg::RS512,
+            6 => Alg::ES256,
+            7 => Alg::ES384,
+            8 => Alg::ES512,
+            9 => Alg::PS256,
+            10 => Alg::PS384,
+            11 => Alg::PS512,
+            12 => Alg::EdDSA,
+            13 => Alg::Ed25519,
+            14 => Alg::None,
+            _ => Alg::from(<&str>::arbitrary(u)?),
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Claims {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Claims {
+            iss: u.arbitrary()?,
+            sub: u.arbitrary()?,
+            aud: u.arbitrary()?,
+            exp: u.arbitrary()?,
+            nbf: u.arbitrary()?,
+            iat: u.arbitrary()?,
+            jti: u.arbitrary()?,
+        })
+    }
+}
+
+/// Headers with an optional symmetric or OKP `jwk` and string `extra` parameters.
+impl<'a> Arbitrary<'a> for Header {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let jwk = match u.int_in_range(0..=2)? {
+            0 => None,
+            1 => Some(Jwk::oct(<&[u8]>::arbitrary(u)?)),
+            _ => Some(Jwk::okp("Ed25519", <&[u8]>::arbitrary(u)?)),
+        };
+        let mut extra = Map::new();
+        for _ in 0..u.int_in_range(0..=3)? {
+            let name = String::arbitrary(u)?;
+            if !REGISTERED.contains(&name.as_str()) {
+                extra.insert(name, Value::String(u.arbitrary()?));
+            }
+        }
+        Ok(Header {
+            typ: u.arbitrary()?,
+            alg: u.arbitrary()?,
+            cty: u.arbitrary()?,
+            jku: u.arbitrary()?,
+            jwk,
+            kid: u.arbitrary()?,
+            x5u: u.arbitrary()?,
+            x5t: u.arbitrary()?,
+            x5t_s256: u.arbitrary()?,
+            x5c: u.arbitrary()?,
+            crit: u.arbitrary()?,
+            extra,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Signature {
+    #[inline]
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Signature::from(<&[u8]>::arbitrary(u)?))
+    }
+}
+
+/// Tokens of any header, payload and signature, see `Token::to_compact` for their encoding.
+impl<'a, P: Arbitrary<'a>> Arbitrary<'a> for Token<P> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Token {
+            header: u.arbitrary()?,
+            payload: u.arbitrary()?,
+            signature: u.arbitrary()?,
+        })
+    }
+}
@@ -0,0 +1,55 @@
+//! Configuration audit against the JWT best current practices
+//!
+//! `Verifier::audit` reports the weaknesses of a verifier configuration found by the checks of
+//! RFC 8725 (https://tools.ietf.org/html/rfc8725), for startup checks and tests. The
+//! verifications report their own findings by `Verify::audit`, the symmetric keys by
+//! `Algorithm::audit_key`, and the validations tell the claims they check by
+//! `Validation::validates_claim`.
+//!
+//! ```rust
+//! use jwts::Verifier;
+//! use jwts::audit::Finding;
+//! use jwts::jws::VerifyWith;
+//! use jwts::jws::alg::HS256;
+//! use jwts::validate::ExpiredTime;
+//!
+//! let verifier = Verifier::new(VerifyWith::<HS256>(b"secret"));
+//! assert_eq!(verifier.audit(), [
+//!     Finding::ShortHmacKey { alg: "HS256", len: 6, min: 32 },
+//!     Finding::NoExpirationRequired,
+//! ]);
+//!
+//! let verifier = Verifier::new(VerifyWith::<HS256>(&[7; 32])).with_validation(ExpiredTime);
+//! assert!(verifier.audit().is_empty());
+//! ```
+
+use std::fmt::{Display, Formatter};
+
+/// A weakness of a verifier configuration.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Finding {
+    /// The signature is not verified, see `DangerousNoVerify`
+    NoSignatureVerification,
+    /// The algorithm is selected by the `alg` header among symmetric and asymmetric algorithms,
+    /// so a public key may be used as an HMAC secret, see RFC 8725 section 3.1
+    AlgorithmNotPinned,
+    /// No validation rejects tokens without an `exp` in the future
+    NoExpirationRequired,
+    /// The HMAC key is shorter than the hash output, see RFC 7518 section 3.2
+    ShortHmacKey { alg: &'static str, len: usize, min: usize },
+    /// RSA keys with a modulus shorter than 2048 bits are accepted, see RFC 7518 section 3.3
+    ShortRsaKey { min: usize },
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Finding::NoSignatureVerification => f.write_str("Signature not verified"),
+            Finding::AlgorithmNotPinned => f.write_str("Algorithm not pinned, symmetric and asymmetric algorithms allowed"),
+            Finding::NoExpirationRequired => f.write_str("Expiration not required"),
+            Finding::ShortHmacKey { alg, len, min } => write!(f, "{} key of {} bytes, shorter than {}", alg, len, min),
+            Finding::ShortRsaKey { min } => write!(f, "RSA keys of {} bits accepted, shorter than 2048", min),
+        }
+    }
+}
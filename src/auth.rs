@@ -0,0 +1,84 @@
+//! Request authentication shared by the middleware integrations
+
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::error::ErrorKind;
+use crate::extract::TokenExtractor;
+use crate::jws::Verify;
+use crate::verifier::Verifier;
+
+/// Verifies the bearer token of a request, except for the skipped paths.
+pub(crate) struct Authenticator<V, C> {
+    verifier: Arc<Verifier<V>>,
+    skip: Arc<Vec<String>>,
+    extractor: Arc<TokenExtractor>,
+    _claims: PhantomData<fn() -> C>,
+}
+
+impl<V: Verify<Value>, C: DeserializeOwned> Authenticator<V, C> {
+    #[inline]
+    pub fn new(verifier: Verifier<V>) -> Self {
+        Authenticator {
+            verifier: Arc::new(verifier),
+            skip: Arc::new(Vec::new()),
+            extractor: Arc::new(TokenExtractor::new()),
+            _claims: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn skip(&mut self, path: String) {
+        Arc::make_mut(&mut self.skip).push(path);
+    }
+
+    #[inline]
+    pub fn extractor(&mut self, extractor: TokenExtractor) {
+        self.extractor = Arc::new(extractor);
+    }
+
+    /// Whether the path is one of the skipped paths or below it; the root `/` is skipped alone,
+    /// never the paths below it.
+    pub fn skipped(&self, path: &str) -> bool {
+        self.skip.iter().any(|skip| match skip.trim_end_matches('/') {
+            "" => path == "/",
+            skip => match path.strip_prefix(skip) {
+                Some(rest) => rest.is_empty() || rest.starts_with('/'),
+                None => false,
+            },
+        })
+    }
+
+    /// The claims of the token in the `Authorization` header, the `Cookie` headers or the query string,
+    /// or else the HTTP status to respond with.
+    pub fn authenticate<'a>(&self, authorization: Option<&'a [u8]>, cookies: impl Iterator<Item=&'a [u8]>, query: Option<&'a str>) -> Result<C, u16> {
+        let authorization = authorization.and_then(|x| std::str::from_utf8(x).ok());
+        let cookies = cookies.filter_map(|x| std::str::from_utf8(x).ok());
+        let token = self.extractor.extract(authorization, cookies, query).ok_or(401u16)?;
+        match self.verifier.verify::<C>(token) {
+            Ok(token) => Ok(token.payload),
+            Err(err) => match err.kind() {
+                ErrorKind::Malformed => Err(400),
+                ErrorKind::PolicyViolation => Err(403),
+                ErrorKind::Unavailable => Err(503),
+                ErrorKind::Internal => Err(500),
+                _ => Err(401),
+            },
+        }
+    }
+}
+
+impl<V, C> Clone for Authenticator<V, C> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Authenticator {
+            verifier: self.verifier.clone(),
+            skip: self.skip.clone(),
+            extractor: self.extractor.clone(),
+            _claims: PhantomData,
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! Helpers of the `Authorization: Bearer <token>` header, RFC 6750
+//!
+//! The `http::HeaderValue` conversions require the feature `http`.
+//!
+//! ```rust
+//! use jwts::bearer;
+//!
+//! assert_eq!(bearer::parse("  bearer   xxx.yyy.zzz "), Some("xxx.yyy.zzz"));
+//! assert_eq!(bearer::parse("Basic dXNlcjpwYXNz"), None);
+//! assert_eq!(bearer::strip("xxx.yyy.zzz\n"), "xxx.yyy.zzz");
+//! assert_eq!(bearer::format("xxx.yyy.zzz"), "Bearer xxx.yyy.zzz");
+//! ```
+
+#[cfg(feature = "http")]
+use http::HeaderValue;
+#[cfg(feature = "http")]
+use http::header::InvalidHeaderValue;
+
+/// The token of an `Authorization` header value of the `Bearer` scheme.
+///
+/// The scheme is case-insensitive, whitespace around the scheme and the token is ignored.
+pub fn parse(authorization: &str) -> Option<&str> {
+    let (scheme, token) = authorization.trim().split_once(|c: char| c.is_ascii_whitespace())?;
+    let token = token.trim_start();
+    if scheme.eq_ignore_ascii_case("Bearer") && !token.is_empty() {
+        Some(token)
+    } else {
+        None
+    }
+}
+
+/// The token without surrounding whitespace and the `Bearer` scheme if any, e.g. of a token
+/// copied from a header, a file or an environment variable.
+#[inline]
+pub fn strip(token: &str) -> &str {
+    parse(token).unwrap_or(token.trim())
+}
+
+/// The `Authorization` header value of the token.
+#[inline]
+pub fn format(token: &str) -> String {
+    format!("Bearer {}", token)
+}
+
+/// The token of an `Authorization` header value, see `parse`.
+#[cfg(feature = "http")]
+#[inline]
+pub fn from_header(value: &HeaderValue) -> Option<&str> {
+    parse(value.to_str().ok()?)
+}
+
+/// The `Authorization` header value of the token, marked sensitive.
+#[cfg(feature = "http")]
+pub fn to_header(token: &str) -> Result<HeaderValue, InvalidHeaderValue> {
+    let mut value = HeaderValue::try_from(format(token))?;
+    value.set_sensitive(true);
+    Ok(value)
+}
@@ -0,0 +1,318 @@
+//! Command line tool to decode, sign and verify tokens, requires the feature `cli`
+//!
+//! ```text
+//! jwts decode <TOKEN|->
+//! jwts sign --alg <ALG> --key <FILE> [--kid <KID>] [--exp <SECS>] <CLAIMS.json|->
+//! jwts verify [--alg <ALG>] (--key <FILE> | --jwks <URL|FILE>) <TOKEN|->
+//! ```
+//!
+//! With `--jwks`, the algorithm is the `--alg` given or else the `alg` of the selected JWK, never
+//! the one of the token.
+//!
+//! Keys are read as DER or PEM: HS secrets as raw bytes, RSA private keys as PKCS#1, EC and
+//! Ed25519 private keys as PKCS#8, and public keys as SubjectPublicKeyInfo or in their raw form.
+
+use std::fmt::Display;
+use std::io::{self, IsTerminal, Read};
+use std::process::ExitCode;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::{env, fs};
+
+use serde_json as json;
+use serde_json::{Map, Value};
+
+use jwts::crypto::{Ed25519KeyPair, RsaKeyPair};
+use jwts::file::FileJwks;
+use jwts::jws::alg::{Ed25519, ES256, ES384, HS256, HS384, HS512, PS256, PS384, PS512, RS256, RS384, RS512};
+use jwts::jws::{self, Alg, DangerousNoVerify, Header, Token, Verify};
+use jwts::key::{EcPublicKey, Ed25519PublicKey, RsaPublicKey};
+use jwts::keystore::KeyStore;
+use jwts::secret::SecretKey;
+use jwts::{remote, Error};
+
+const USAGE: &str = "\
+Usage:
+    jwts decode <TOKEN|->
+    jwts sign --alg <ALG> --key <FILE> [--kid <KID>] [--exp <SECS>] <CLAIMS.json|->
+    jwts verify [--alg <ALG>] (--key <FILE> | --jwks <URL|FILE>) <TOKEN|->";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("decode") => Args::parse(&args[1..], &[]).and_then(|x| decode(&x)),
+        Some("sign") => Args::parse(&args[1..], &["--alg", "--key", "--kid", "--exp"]).and_then(|x| sign(&x)),
+        Some("verify") => Args::parse(&args[1..], &["--alg", "--key", "--jwks"]).and_then(|x| verify(&x)),
+        Some("-h" | "--help" | "help") => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        _ => Err(Failure::Usage("expected a command".to_owned())),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(Failure::Usage(msg)) => {
+            eprintln!("error: {msg}\n\n{USAGE}");
+            ExitCode::from(2)
+        }
+        Err(Failure::Failed(msg)) => {
+            eprintln!("error: {msg}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Failure of a command, exiting with status 2 for usage errors and 1 otherwise.
+enum Failure {
+    Usage(String),
+    Failed(String),
+}
+
+impl From<Error> for Failure {
+    #[inline]
+    fn from(e: Error) -> Self {
+        Failure::Failed(e.to_string())
+    }
+}
+
+impl From<io::Error> for Failure {
+    #[inline]
+    fn from(e: io::Error) -> Self {
+        Failure::Failed(e.to_string())
+    }
+}
+
+/// The key pair types are of the backend, as well as their errors.
+fn invalid_key(e: impl Display) -> Failure {
+    Failure::Failed(format!("invalid key: {e}"))
+}
+
+/// Options of a command and its single positional argument.
+struct Args {
+    options: Vec<(String, String)>,
+    input: String,
+}
+
+impl Args {
+    fn parse(args: &[String], names: &[&str]) -> Result<Self, Failure> {
+        let mut options = Vec::new();
+        let mut input = None;
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if arg.starts_with("--") {
+                if !names.contains(&arg.as_str()) {
+                    return Err(Failure::Usage(format!("unknown option {arg}")));
+                }
+                let value = iter.next().ok_or_else(|| Failure::Usage(format!("missing value of {arg}")))?;
+                options.push((arg.clone(), value.clone()));
+            } else if input.replace(arg.clone()).is_some() {
+                return Err(Failure::Usage(format!("unexpected argument {arg}")));
+            }
+        }
+        let input = input.ok_or_else(|| Failure::Usage("missing input".to_owned()))?;
+        Ok(Args { options, input })
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.options.iter().rev().find(|(x, _)| x == name).map(|(_, v)| v.as_str())
+    }
+
+    fn require(&self, name: &str) -> Result<&str, Failure> {
+        self.get(name).ok_or_else(|| Failure::Usage(format!("missing option {name}")))
+    }
+
+    /// The input, read from stdin if it is `-`.
+    fn read_input(&self) -> Result<String, Failure> {
+        if self.input != "-" {
+            return Ok(self.input.clone());
+        }
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        Ok(input)
+    }
+
+    /// The content of the input file, read from stdin if it is `-`.
+    fn read_file(&self) -> Result<String, Failure> {
+        if self.input == "-" {
+            return self.read_input();
+        }
+        Ok(fs::read_to_string(&self.input)?)
+    }
+}
+
+fn decode(args: &Args) -> Result<(), Failure> {
+    let input = args.read_input()?;
+    let token: Token<Map<String, Value>> = jws::decode(input.trim(), DangerousNoVerify)?;
+    println!("{}", pretty(&token.header));
+    println!("{}", pretty(&token.payload));
+
+    let color = io::stdout().is_terminal();
+    let now = now_secs();
+    for name in ["iat", "nbf", "exp"] {
+        let Some(time) = token.payload.get(name).and_then(Value::as_u64) else { continue };
+        let note = match name {
+            "exp" if time <= now => paint(color, "31", &format!("expired {} ago", human(now - time))),
+            "exp" => paint(color, "32", &format!("valid for {}", human(time - now))),
+            "nbf" if time > now => paint(color, "33", &format!("not valid for {}", human(time - now))),
+            _ => String::new(),
+        };
+        println!("{name}: {} {note}", utc(time));
+    }
+    Ok(())
+}
+
+fn sign(args: &Args) -> Result<(), Failure> {
+    let alg = Alg::from(args.require("--alg")?);
+    let key = SecretKey::new(fs::read(args.require("--key")?)?);
+    let mut claims: Map<String, Value> = json::from_str(&args.read_file()?)
+        .map_err(|e| Failure::Failed(format!("invalid claims: {e}")))?;
+    if let Some(exp) = args.get("--exp") {
+        let secs: u64 = exp.parse().map_err(|_| Failure::Usage(format!("invalid --exp {exp}")))?;
+        claims.insert("exp".to_owned(), (now_secs() + secs).into());
+    }
+    let mut header = Header::default();
+    if let Some(kid) = args.get("--kid") {
+        header = header.with_kid(kid);
+    }
+
+    let token = match alg {
+        Alg::HS256 => jws::encode::<HS256>(header, &claims, &key)?,
+        Alg::HS384 => jws::encode::<HS384>(header, &claims, &key)?,
+        Alg::HS512 => jws::encode::<HS512>(header, &claims, &key)?,
+        Alg::RS256 => jws::encode::<RS256>(header, &claims, &RsaKeyPair::from_der(&der(&key)).map_err(invalid_key)?)?,
+        Alg::RS384 => jws::encode::<RS384>(header, &claims, &RsaKeyPair::from_der(&der(&key)).map_err(invalid_key)?)?,
+        Alg::RS512 => jws::encode::<RS512>(header, &claims, &RsaKeyPair::from_der(&der(&key)).map_err(invalid_key)?)?,
+        Alg::PS256 => jws::encode::<PS256>(header, &claims, &RsaKeyPair::from_der(&der(&key)).map_err(invalid_key)?)?,
+        Alg::PS384 => jws::encode::<PS384>(header, &claims, &RsaKeyPair::from_der(&der(&key)).map_err(invalid_key)?)?,
+        Alg::PS512 => jws::encode::<PS512>(header, &claims, &RsaKeyPair::from_der(&der(&key)).map_err(invalid_key)?)?,
+        Alg::ES256 => jws::encode::<ES256>(header, &claims, &der(&key))?,
+        Alg::ES384 => jws::encode::<ES384>(header, &claims, &der(&key))?,
+        Alg::EdDSA | Alg::Ed25519 => jws::encode::<Ed25519>(header, &claims, &Ed25519KeyPair::from_pkcs8(&der(&key)).map_err(invalid_key)?)?,
+        _ => return Err(Failure::Usage(format!("unsupported algorithm {alg}"))),
+    };
+    println!("{token}");
+    Ok(())
+}
+
+fn verify(args: &Args) -> Result<(), Failure> {
+    let input = args.read_input()?;
+    let token = input.trim();
+    let verify = match (args.get("--key"), args.get("--jwks")) {
+        (Some(key), None) => {
+            let alg = Alg::from(args.require("--alg")?);
+            let key = public_key(&alg, &fs::read(key)?)?;
+            VerifyWithKey { alg: Some(alg), key }
+        }
+        (None, Some(jwks)) => {
+            let jwks = if jwks.starts_with("https://") || jwks.starts_with("http://") {
+                fetch_jwks(jwks)?
+            } else {
+                FileJwks::open(jwks)?.jwks().as_ref().clone()
+            };
+            let Token { header, payload, .. } = jws::decode::<Value>(token, DangerousNoVerify)?;
+            let jwk = match &header.kid {
+                Some(kid) => jwks.find(kid),
+                None if jwks.keys.len() == 1 => jwks.keys.first(),
+                None => None,
+            };
+            let jwk = jwk.ok_or(Error::InvalidKey("no matching key in the JWK Set"))?;
+            // never trust the alg of the token, e.g. HS256 with a public RSA key as the secret
+            let alg = match args.get("--alg") {
+                Some(alg) => Alg::from(alg),
+                None => jwk.alg.clone().ok_or_else(|| Failure::Usage("--alg is required unless the JWK has alg".to_owned()))?,
+            };
+            let header = Header { alg: Some(alg.clone()), ..header };
+            let key = jwks.key_for(&header, &payload).ok_or(Error::InvalidKey("no matching key in the JWK Set"))?;
+            VerifyWithKey { alg: Some(alg), key }
+        }
+        _ => return Err(Failure::Usage("expected exactly one of --key and --jwks".to_owned())),
+    };
+    let token: Token<Map<String, Value>> = jws::decode(token, verify)?;
+    if let Some(exp) = token.payload.get("exp").and_then(Value::as_u64) {
+        if exp <= now_secs() {
+            return Err(Failure::Failed(format!("signature valid, but expired at {}", utc(exp))));
+        }
+    }
+    println!("signature valid");
+    println!("{}", pretty(&token.payload));
+    Ok(())
+}
+
+/// Verify with the key, for the expected algorithm if any.
+struct VerifyWithKey {
+    alg: Option<Alg>,
+    key: Vec<u8>,
+}
+
+impl<P> Verify<P> for VerifyWithKey {
+    fn verify(&self, f2s: &str, signature: &[u8], header: &Header, _payload: &P) -> Result<(), Error> {
+        let alg = header.alg.as_ref().ok_or(Error::Rejected("missing alg"))?;
+        let same = |x: &Alg| x == alg || matches!((x, alg), (Alg::EdDSA | Alg::Ed25519, Alg::EdDSA | Alg::Ed25519));
+        if self.alg.as_ref().is_some_and(|x| !same(x)) {
+            return Err(Error::Rejected("unexpected alg"));
+        }
+        alg.verify(f2s, signature, &self.key)
+    }
+}
+
+fn fetch_jwks(url: &str) -> Result<jwts::jwk::JwkSet, Failure> {
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+    let http = reqwest::Client::builder().timeout(Duration::from_secs(30)).build().map_err(Error::fetch)?;
+    Ok(runtime.block_on(remote::fetch_jwks(&http, url))?)
+}
+
+/// The verification key of the algorithm, from a SubjectPublicKeyInfo or the raw key.
+fn public_key(alg: &Alg, key: &[u8]) -> Result<Vec<u8>, Error> {
+    let der = der(key);
+    Ok(match alg {
+        Alg::HS256 | Alg::HS384 | Alg::HS512 => key.to_owned(),
+        Alg::RS256 | Alg::RS384 | Alg::RS512 | Alg::PS256 | Alg::PS384 | Alg::PS512 => {
+            RsaPublicKey::from_spki_der(&der).or_else(|_| RsaPublicKey::from_pkcs1_der(&der))?.to_vec()
+        }
+        Alg::ES256 | Alg::ES384 => EcPublicKey::from_spki_der(&der).or_else(|_| EcPublicKey::from_sec1(&der))?.to_vec(),
+        Alg::EdDSA | Alg::Ed25519 => Ed25519PublicKey::from_spki_der(&der).or_else(|_| Ed25519PublicKey::from_bytes(&der))?.to_vec(),
+        _ => return Err(Error::InvalidKey("unsupported algorithm")),
+    })
+}
+
+/// The DER of the first PEM block, or else the bytes as they are.
+fn der(key: &[u8]) -> SecretKey {
+    let Ok(text) = std::str::from_utf8(key) else { return SecretKey::new(key.to_owned()) };
+    let label = text.split_once("-----BEGIN ").and_then(|(_, x)| x.split_once("-----"));
+    label.and_then(|(label, _)| SecretKey::from_pem(text, label).ok())
+        .unwrap_or_else(|| SecretKey::new(key.to_owned()))
+}
+
+fn pretty(value: &impl serde::Serialize) -> String {
+    json::to_string_pretty(value).unwrap_or_default()
+}
+
+fn paint(color: bool, code: &str, text: &str) -> String {
+    if color { format!("\x1b[{code}m({text})\x1b[0m") } else { format!("({text})") }
+}
+
+/// The duration in its two largest units, as `1d 2h`.
+fn human(secs: u64) -> String {
+    let parts = [(secs / 86400, "d"), (secs / 3600 % 24, "h"), (secs / 60 % 60, "m"), (secs % 60, "s")];
+    let start = parts.iter().position(|(n, _)| *n > 0).unwrap_or(3);
+    parts[start..].iter().take(2).map(|(n, unit)| format!("{n}{unit}")).collect::<Vec<_>>().join(" ")
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |x| x.as_secs())
+}
+
+/// The time as `YYYY-MM-DD hh:mm:ss UTC`.
+fn utc(secs: u64) -> String {
+    let (days, rem) = (secs / 86400, secs % 86400);
+    // civil from days, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    format!("{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC", rem / 3600, rem / 60 % 60, rem % 60)
+}
@@ -1,6 +1,13 @@
-//! Wrapper of `base64`
+//! Wrapper of `base64`, in the unpadded base64url alphabet of JWS unless noted
 
-use base64::{DecodeError, Engine};
+use base64::{alphabet, DecodeError, Engine};
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+
+/// Standard alphabet, padding optional.
+const STANDARD_INDIFFERENT: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
 
 /// Encodes the specific bytes to a base64 string.
 #[inline]
@@ -8,8 +15,52 @@ pub fn from_bytes(bytes: impl AsRef<[u8]>) -> String {
     base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(&bytes)
 }
 
+/// Encodes the specific bytes and appends to the string, reusing its capacity.
+#[inline]
+pub fn encode_into(bytes: impl AsRef<[u8]>, out: &mut String) {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode_string(bytes, out)
+}
+
+/// The length of the base64 string encoded from `len` bytes.
+#[inline]
+pub fn encoded_len(len: usize) -> usize {
+    base64::encoded_len(len, false).expect("base64 length overflow")
+}
+
 /// Decodes the base64 string to bytes as `Vec<u8>`.
 #[inline]
 pub fn to_bytes(s: &str) -> Result<Vec<u8>, DecodeError> {
     base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(s)
 }
+
+/// Decodes the base64 string and appends the bytes to the buffer, reusing its capacity.
+#[inline]
+pub fn decode_into(s: &str, out: &mut Vec<u8>) -> Result<(), DecodeError> {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.decode_vec(s, out)
+}
+
+/// Encodes the specific bytes to a standard base64 string with padding, as used by `x5c`.
+#[inline]
+pub fn std_from_bytes(bytes: impl AsRef<[u8]>) -> String {
+    base64::engine::general_purpose::STANDARD.encode(&bytes)
+}
+
+/// Decodes the standard base64 string with padding to bytes as `Vec<u8>`.
+#[inline]
+pub fn std_to_bytes(s: &str) -> Result<Vec<u8>, DecodeError> {
+    base64::engine::general_purpose::STANDARD.decode(s)
+}
+
+/// Decodes a base64 string of the standard or the URL-safe alphabet, with or without padding.
+pub fn any_to_bytes(s: &str) -> Result<Vec<u8>, DecodeError> {
+    let standard: String = s.chars()
+        .map(|x| match x {
+            '-' => '+',
+            '_' => '/',
+            x => x,
+        })
+        .collect();
+    let bytes = STANDARD_INDIFFERENT.decode(&standard);
+    crate::crypto::wipe(&mut standard.into_bytes());
+    bytes
+}
@@ -0,0 +1,112 @@
+//! Verification cache for repeated tokens
+//!
+//! A bearer token is usually presented many times before it expires. `CachedVerify` remembers
+//! the tokens whose signature verified, so repeated ones skip the signature check until their
+//! `exp`. Claims validation is not cached, a `Verifier` still runs it on every token.
+//!
+//! A cached token stays accepted even if its key is revoked in the meantime, until it expires.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use serde_json as json;
+
+use crate::audit::Finding;
+use crate::crypto;
+use crate::error::Error;
+use crate::jws::{Header, Verify};
+use crate::time;
+
+type Digest = [u8; 32];
+
+/// Wrap a verification with a LRU cache of successfully verified tokens, keyed by their SHA-256.
+///
+/// Tokens without a numeric `exp` claim are never cached. Clones share the cache.
+#[derive(Clone)]
+pub struct CachedVerify<V> {
+    verify: V,
+    capacity: usize,
+    lru: Arc<Mutex<Lru>>,
+}
+
+#[derive(Default)]
+struct Lru {
+    tick: u64,
+    /// digest -> (exp, last used tick)
+    entries: HashMap<Digest, (u64, u64)>,
+    /// last used tick -> digest
+    recency: BTreeMap<u64, Digest>,
+}
+
+impl<V> CachedVerify<V> {
+    /// Create a `CachedVerify` holding at most `capacity` tokens.
+    #[inline]
+    pub fn new(verify: V, capacity: usize) -> Self {
+        CachedVerify {
+            verify,
+            capacity,
+            lru: Arc::default(),
+        }
+    }
+
+    /// Remove all cached tokens.
+    pub fn clear(&self) {
+        *self.lru.lock().unwrap_or_else(|e| e.into_inner()) = Lru::default();
+    }
+}
+
+impl Lru {
+    /// Whether the digest is cached and unexpired, marking it as recently used.
+    fn hit(&mut self, digest: &Digest, now: u64) -> bool {
+        let Some(&(exp, used)) = self.entries.get(digest) else { return false };
+        self.recency.remove(&used);
+        if exp <= now {
+            self.entries.remove(digest);
+            return false;
+        }
+        self.tick += 1;
+        self.recency.insert(self.tick, *digest);
+        self.entries.insert(*digest, (exp, self.tick));
+        true
+    }
+
+    fn insert(&mut self, digest: Digest, exp: u64, capacity: usize) {
+        if let Some((_, used)) = self.entries.remove(&digest) {
+            self.recency.remove(&used);
+        }
+        while self.entries.len() >= capacity {
+            match self.recency.pop_first() {
+                Some((_, oldest)) => self.entries.remove(&oldest),
+                None => return,
+            };
+        }
+        self.tick += 1;
+        self.recency.insert(self.tick, digest);
+        self.entries.insert(digest, (exp, self.tick));
+    }
+}
+
+impl<P: Serialize, V: Verify<P>> Verify<P> for CachedVerify<V> {
+    fn verify(&self, f2s: &str, signature: &[u8], header: &Header, payload: &P) -> Result<(), Error> {
+        let digest = crypto::sha256(&[f2s.as_bytes(), b".", signature].concat());
+        let digest: Digest = digest.try_into().expect("SHA-256 digest is 32 bytes");
+
+        let now = time::now_secs();
+        if self.lru.lock().unwrap_or_else(|e| e.into_inner()).hit(&digest, now) {
+            return Ok(());
+        }
+        self.verify.verify(f2s, signature, header, payload)?;
+
+        let exp = json::to_value(payload).ok().and_then(|x| x["exp"].as_u64());
+        if let Some(exp) = exp.filter(|&x| x > now && self.capacity > 0) {
+            self.lru.lock().unwrap_or_else(|e| e.into_inner()).insert(digest, exp, self.capacity);
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn audit(&self, findings: &mut Vec<Finding>) {
+        self.verify.audit(findings)
+    }
+}
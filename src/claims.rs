@@ -1,34 +1,48 @@
 //! Standard Claims
 
+use std::fmt::{Debug, Formatter};
 use std::time::{Duration, SystemTime};
 
 use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
 
+use crate::redact::mask;
 use crate::time;
 
 /// Registered Claim Names, see https://tools.ietf.org/html/rfc7519#section-4.1
-#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+///
+/// With the feature `lenient-claims`, the spelled-out names of non-standard issuers, such as
+/// `issuer` or `expires_at`, are also accepted on deserialization. Relative times such as
+/// `expires_in` are never taken as `exp`.
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Claims {
     /// Issuer
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "lenient-claims", serde(alias = "issuer"))]
     pub iss: Option<String>,
     /// Subject
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "lenient-claims", serde(alias = "subject"))]
     pub sub: Option<String>,
     /// Audience
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "lenient-claims", serde(alias = "audience"))]
     pub aud: Option<String>,
     /// Expiration Time
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "lenient-claims", serde(alias = "expires_at"))]
     pub exp: Option<u64>,
     /// Not Before
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "lenient-claims", serde(alias = "not_before"))]
     pub nbf: Option<u64>,
     /// Issued At
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "lenient-claims", serde(alias = "issued_at"))]
     pub iat: Option<u64>,
     /// JWT ID
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "lenient-claims", serde(alias = "jwt_id"))]
     pub jti: Option<String>,
 }
 
@@ -55,6 +69,14 @@ impl Claims {
         }
     }
 
+    #[inline]
+    pub fn issued_at(self, time: SystemTime) -> Self {
+        Claims {
+            iat: Some(time::since_unix_epoch_secs(time)),
+            ..self
+        }
+    }
+
     #[inline]
     pub fn expired_in(self, duration: Duration) -> Self {
         Claims {
@@ -71,6 +93,15 @@ impl Claims {
         }
     }
 
+    /// Remove `exp`, for tokens valid until revoked.
+    #[inline]
+    pub fn expires_never(self) -> Self {
+        Claims {
+            exp: None,
+            ..self
+        }
+    }
+
     #[inline]
     pub fn not_before(self, time: SystemTime) -> Self {
         Claims {
@@ -78,6 +109,68 @@ impl Claims {
             ..self
         }
     }
+
+    #[inline]
+    pub fn not_before_in(self, duration: Duration) -> Self {
+        Claims {
+            nbf: Some(time::now_secs() + duration.as_secs()),
+            ..self
+        }
+    }
+
+    /// Set `jti` to a random UUIDv4, requires the feature `uuid`.
+    #[cfg(feature = "uuid")]
+    #[inline]
+    pub fn with_random_jti(self) -> Self {
+        self.with_jti_uuid(JtiUuid::V4)
+    }
+
+    /// Set `jti` to a fresh UUID of the version, requires the feature `uuid`.
+    #[cfg(feature = "uuid")]
+    #[inline]
+    pub fn with_jti_uuid(self, version: JtiUuid) -> Self {
+        Claims {
+            jti: Some(version.generate()),
+            ..self
+        }
+    }
+}
+
+/// Version of the UUIDs generated as `jti`, requires the feature `uuid`.
+#[cfg(feature = "uuid")]
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum JtiUuid {
+    /// Random
+    #[default]
+    V4,
+    /// Random with a millisecond timestamp prefix, sorted by creation time
+    V7,
+}
+
+#[cfg(feature = "uuid")]
+impl JtiUuid {
+    /// A fresh UUID of the version in the hyphenated form.
+    pub fn generate(self) -> String {
+        match self {
+            JtiUuid::V4 => uuid::Uuid::new_v4().to_string(),
+            JtiUuid::V7 => uuid::Uuid::now_v7().to_string(),
+        }
+    }
+}
+
+/// Masks `sub`, see `redact::Reveal` to print it.
+impl Debug for Claims {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Claims")
+            .field("iss", &self.iss)
+            .field("sub", &mask(&self.sub))
+            .field("aud", &self.aud)
+            .field("exp", &self.exp)
+            .field("nbf", &self.nbf)
+            .field("iat", &self.iat)
+            .field("jti", &self.jti)
+            .finish()
+    }
 }
 
 impl Default for Claims {
@@ -86,3 +179,51 @@ impl Default for Claims {
         Self::new()
     }
 }
+
+/// Normalize the time claims `exp`, `nbf` and `iat` of a payload given as a float, such as
+/// `1699999999.0`, or as a numeric string to whole seconds, truncating any fraction. Values
+/// that are negative, out of range or not numeric are left as they are.
+pub(crate) fn normalize_numeric_dates(payload: &mut Value) {
+    for name in ["exp", "nbf", "iat"] {
+        let Some(value) = payload.get_mut(name) else { continue };
+        let secs = match value {
+            Value::Number(x) if x.is_u64() => continue,
+            Value::Number(x) => x.as_f64().and_then(whole_secs),
+            Value::String(x) => x.trim().parse::<u64>().ok().or_else(|| x.trim().parse().ok().and_then(whole_secs)),
+            _ => None,
+        };
+        if let Some(secs) = secs {
+            *value = secs.into();
+        }
+    }
+}
+
+/// Normalize the string claims `iss`, `sub`, `aud` and `jti` of a payload given as a number,
+/// such as `"sub": 12345`, to the string of the number. Other values are left as they are.
+pub(crate) fn normalize_string_claims(payload: &mut Value) {
+    for name in ["iss", "sub", "aud", "jti"] {
+        let Some(value) = payload.get_mut(name) else { continue };
+        if let Value::Number(x) = value {
+            *value = x.to_string().into();
+        }
+    }
+}
+
+/// Rename the claims of a payload by the mappings of `(from, to)`, applied in order. A claim is
+/// not renamed onto a present one, which is kept as it is.
+pub(crate) fn remap_claims(payload: &mut Value, mappings: &[(String, String)]) {
+    let Some(claims) = payload.as_object_mut() else { return };
+    for (from, to) in mappings {
+        if claims.contains_key(to) {
+            continue;
+        }
+        if let Some(value) = claims.remove(from) {
+            claims.insert(to.clone(), value);
+        }
+    }
+}
+
+#[inline]
+fn whole_secs(x: f64) -> Option<u64> {
+    (x.is_finite() && x >= 0.0 && x < u64::MAX as f64).then_some(x as u64)
+}
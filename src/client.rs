@@ -0,0 +1,135 @@
+//! Middleware of reqwest-middleware, requires the feature `reqwest-middleware`
+//!
+//! `BearerAuth` sets the bearer `Authorization` header of every outgoing request, minting a new
+//! token from its `TokenSource` when the current one is about to expire.
+//!
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use jwts::{Claims, Issuer};
+//! use jwts::client::BearerAuth;
+//! use jwts::jws::alg::HS256;
+//! use reqwest_middleware::ClientBuilder;
+//! use reqwest_middleware::reqwest::Client;
+//!
+//! let issuer = Issuer::<HS256>::new(b"secret", Duration::from_secs(300));
+//! let claims = Claims {
+//!     sub: Some("service-a".to_owned()),
+//!     ..Default::default()
+//! };
+//! let client = ClientBuilder::new(Client::new())
+//!     .with(BearerAuth::from_issuer(issuer, claims))
+//!     .build();
+//! ```
+
+use std::future::{ready, Future};
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use http::Extensions;
+use http::header::AUTHORIZATION;
+use reqwest_middleware::{Middleware, Next};
+use reqwest_middleware::reqwest::{Request, Response};
+use serde_derive::Deserialize;
+use serde_json as json;
+
+use crate::{bearer, bs64, time};
+use crate::claims::Claims;
+use crate::error::Error;
+use crate::issuer::Issuer;
+use crate::jws::Algorithm;
+
+/// Margin before `exp` within which a new token is minted by default.
+const DEFAULT_REFRESH_BEFORE: Duration = Duration::from_secs(60);
+
+/// Future of a minted token.
+pub type TokenFuture<'a> = Pin<Box<dyn Future<Output=Result<String, Error>> + Send + 'a>>;
+
+/// Mints tokens, such as an `Issuer` or a call of a token endpoint.
+pub trait TokenSource: Send + Sync + 'static {
+    fn token(&self) -> TokenFuture<'_>;
+}
+
+impl<F, Fut> TokenSource for F
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output=Result<String, Error>> + Send + 'static,
+{
+    #[inline]
+    fn token(&self) -> TokenFuture<'_> {
+        Box::pin(self())
+    }
+}
+
+/// Middleware setting the bearer token minted by the `TokenSource`.
+///
+/// A token is reused until it is within the refresh margin of its `exp`, tokens without a
+/// readable `exp` are reused for good.
+pub struct BearerAuth<T> {
+    source: T,
+    refresh_before: Duration,
+    current: Mutex<Option<(String, Option<u64>)>>,
+}
+
+impl<T: TokenSource> BearerAuth<T> {
+    /// Create a `BearerAuth` minting with the `TokenSource`.
+    #[inline]
+    pub fn new(source: T) -> Self {
+        BearerAuth {
+            source,
+            refresh_before: DEFAULT_REFRESH_BEFORE,
+            current: Mutex::new(None),
+        }
+    }
+
+    /// Mint a new token when the current one expires within the duration, 60 seconds by default.
+    #[inline]
+    pub fn refresh_before(self, duration: Duration) -> Self {
+        BearerAuth { refresh_before: duration, ..self }
+    }
+
+    /// The current token, or a newly minted one.
+    pub async fn token(&self) -> Result<String, Error> {
+        let deadline = time::now_secs() + self.refresh_before.as_secs();
+        if let Some((token, exp)) = &*self.current.lock().unwrap() {
+            if exp.is_none_or(|exp| exp > deadline) {
+                return Ok(token.clone());
+            }
+        }
+        let token = self.source.token().await?;
+        *self.current.lock().unwrap() = Some((token.clone(), expiration(&token)));
+        Ok(token)
+    }
+}
+
+impl BearerAuth<()> {
+    /// Create a `BearerAuth` issuing tokens of the claims with the `Issuer`.
+    pub fn from_issuer<A>(issuer: Issuer<'static, A>, claims: Claims) -> BearerAuth<impl TokenSource>
+    where
+        A: Algorithm + Send + Sync + 'static,
+        A::SignKey: Sync,
+    {
+        BearerAuth::new(move || ready(issuer.issue(claims.clone())))
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: TokenSource> Middleware for BearerAuth<T> {
+    async fn handle(&self, mut req: Request, extensions: &mut Extensions, next: Next<'_>) -> reqwest_middleware::Result<Response> {
+        let token = self.token().await.map_err(reqwest_middleware::Error::middleware)?;
+        let value = bearer::to_header(&token).map_err(reqwest_middleware::Error::middleware)?;
+        req.headers_mut().insert(AUTHORIZATION, value);
+        next.run(req, extensions).await
+    }
+}
+
+/// The `exp` claim of the token, read without verification.
+fn expiration(token: &str) -> Option<u64> {
+    #[derive(Deserialize)]
+    struct Exp {
+        exp: Option<u64>,
+    }
+
+    let payload = bs64::to_bytes(token.split('.').nth(1)?).ok()?;
+    json::from_slice::<Exp>(&payload).ok()?.exp
+}
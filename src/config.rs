@@ -0,0 +1,155 @@
+//! Verifier built from configuration
+//!
+//! A `VerifierConfig` describes the JWT policy of a service, so it can live in a YAML or TOML
+//! file next to the rest of its configuration and be turned into a `Verifier` in one call.
+//!
+//! ```rust
+//! use jwts::{Claims, jws, Verifier, VerifierConfig};
+//! use jwts::crypto::RsaKeyPair;
+//! use jwts::jws::Header;
+//! use jwts::jws::alg::RS256;
+//!
+//! let config: VerifierConfig = serde_json::from_str(r#"{
+//!     "algorithms": ["RS256"],
+//!     "issuer": "https://idp.example.com",
+//!     "audience": "api",
+//!     "leeway": 30,
+//!     "key_file": "tests/rsa-pub.pem",
+//!     "required_claims": ["sub"]
+//! }"#).unwrap();
+//! let verifier = Verifier::from_config(&config).unwrap();
+//!
+//! let claims = Claims {
+//!     iss: Some("https://idp.example.com".to_owned()),
+//!     aud: Some("api".to_owned()),
+//!     sub: Some("sea".to_owned()),
+//!     ..Default::default()
+//! }.expired_in(std::time::Duration::from_secs(60));
+//! let key = RsaKeyPair::from_der(include_bytes!("../tests/rsa-pri.der")).unwrap();
+//! let token = jws::encode::<RS256>(Header::default(), &claims, &key).unwrap();
+//! verifier.verify::<Claims>(&token).unwrap();
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde_derive::{Deserialize, Serialize};
+use serde_json as json;
+
+use crate::error::Error;
+use crate::file::FileJwks;
+use crate::jwk::JwkSet;
+use crate::jws::{Alg, Header, Verify};
+use crate::key::{AnyVerifyKey, ParsedKey};
+use crate::keystore::KeyStore;
+use crate::remote::{self, HttpGet};
+use crate::validate::ClaimsPolicy;
+use crate::verifier::Verifier;
+
+/// The JWT policy of a service, deserialized from its configuration.
+///
+/// The keys come from exactly one of `jwks_url` and `key_file`. `exp` is always required,
+/// `nbf` and `iat` are validated if present.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VerifierConfig {
+    /// Accepted algorithms, either all HS or all asymmetric
+    pub algorithms: Vec<Alg>,
+    /// Expected `iss` value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<String>,
+    /// Expected `aud` value, a string or in an array
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audience: Option<String>,
+    /// Clock skew tolerated for `exp`, `nbf` and `iat`, in seconds
+    pub leeway: u64,
+    /// URL of the JSON Web Key Set, fetched by `Verifier::from_config_with_http`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jwks_url: Option<String>,
+    /// A key file of any format of `key::load_verify_key`, or a JWK Set file reloaded when modified
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_file: Option<PathBuf>,
+    /// Claims every token must contain
+    pub required_claims: Vec<String>,
+}
+
+/// The signature verification of a `VerifierConfig`.
+#[derive(Debug, Clone)]
+pub struct ConfigVerify {
+    algorithms: Vec<Alg>,
+    keys: ConfigKeys,
+}
+
+#[derive(Debug, Clone)]
+enum ConfigKeys {
+    Key(Arc<AnyVerifyKey>),
+    Jwks(Arc<JwkSet>),
+    File(FileJwks),
+}
+
+impl Verifier<ConfigVerify> {
+    /// Create a `Verifier` of the configuration with the keys of `key_file`.
+    ///
+    /// A configured `jwks_url` is rejected, the keys are fetched by `from_config_with_http`.
+    pub fn from_config(config: &VerifierConfig) -> Result<Self, Error> {
+        let keys = match (&config.jwks_url, &config.key_file) {
+            (None, Some(path)) => load_keys(path)?,
+            (Some(_), None) => return Err(Error::InvalidKey("jwks_url requires Verifier::from_config_with_http")),
+            _ => return Err(Error::InvalidKey("expected exactly one of jwks_url and key_file")),
+        };
+        Self::with_keys(config, keys)
+    }
+
+    /// Create a `Verifier` of the configuration, fetching the keys of `jwks_url` once.
+    pub async fn from_config_with_http(config: &VerifierConfig, http: &impl HttpGet) -> Result<Self, Error> {
+        let keys = match (&config.jwks_url, &config.key_file) {
+            (Some(url), None) => ConfigKeys::Jwks(Arc::new(remote::fetch_jwks(http, url).await?)),
+            (None, Some(path)) => load_keys(path)?,
+            _ => return Err(Error::InvalidKey("expected exactly one of jwks_url and key_file")),
+        };
+        Self::with_keys(config, keys)
+    }
+
+    fn with_keys(config: &VerifierConfig, keys: ConfigKeys) -> Result<Self, Error> {
+        let symmetric = |x: &Alg| matches!(x, Alg::HS256 | Alg::HS384 | Alg::HS512);
+        if config.algorithms.is_empty() {
+            return Err(Error::Rejected("no algorithm allowed"));
+        }
+        if config.algorithms.iter().any(symmetric) && !config.algorithms.iter().all(symmetric) {
+            return Err(Error::Rejected("HS algorithms mixed with asymmetric algorithms"));
+        }
+        let verify = ConfigVerify { algorithms: config.algorithms.clone(), keys };
+        Ok(Verifier::new(verify).with_claims_policy(ClaimsPolicy {
+            issuer: config.issuer.clone(),
+            audience: config.audience.clone(),
+            leeway: config.leeway,
+            required_claims: config.required_claims.clone(),
+        }))
+    }
+}
+
+impl<P> Verify<P> for ConfigVerify {
+    fn verify(&self, f2s: &str, signature: &[u8], header: &Header, payload: &P) -> Result<(), Error> {
+        // `EdDSA` and `Ed25519` name the same algorithm
+        let same = |x: &Alg, y: &Alg| x == y || matches!((x, y), (Alg::EdDSA | Alg::Ed25519, Alg::EdDSA | Alg::Ed25519));
+        let alg = header.alg.as_ref()
+            .filter(|x| self.algorithms.iter().any(|y| same(x, y)))
+            .ok_or(Error::Rejected("algorithm not allowed"))?;
+        let key = match &self.keys {
+            ConfigKeys::Key(key) => return key.verify(alg, f2s.as_bytes(), signature),
+            ConfigKeys::Jwks(jwks) => jwks.key_for(header, payload),
+            ConfigKeys::File(file) => file.key_for(header, payload),
+        };
+        alg.verify(f2s, signature, &key.ok_or(Error::InvalidKey("no matching key"))?)
+    }
+}
+
+/// The keys of a JWK Set file, reloaded when modified, or else of a single key file.
+fn load_keys(path: &Path) -> Result<ConfigKeys, Error> {
+    let bytes = fs::read(path).map_err(Error::fetch)?;
+    if json::from_slice::<JwkSet>(&bytes).is_ok() {
+        return FileJwks::open(path).map(ConfigKeys::File);
+    }
+    AnyVerifyKey::parse(&bytes).map(|x| ConfigKeys::Key(Arc::new(x)))
+}
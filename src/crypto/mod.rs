@@ -0,0 +1,144 @@
+//! Crypto backends
+//!
+//! The algorithms are implemented by exactly one backend, selected by cargo features:
+//!
+//! - `ring` (default) - [ring](https://crates.io/crates/ring)
+//! - `aws-lc-rs` - [aws-lc-rs](https://crates.io/crates/aws-lc-rs), API-compatible with ring,
+//!   enable its `fips` feature in the dependent crate for FIPS-validated builds
+//! - `openssl` - the system [OpenSSL](https://crates.io/crates/openssl), including its FIPS provider
+//!   when configured
+//! - `rust-crypto` - the pure Rust crates of [RustCrypto](https://github.com/RustCrypto),
+//!   for targets where ring does not build, such as `wasm32-unknown-unknown`
+//!
+//! Disable the default features to select another backend. The signing key types of the
+//! algorithms are those of the backend, re-exported here.
+
+#[cfg(any(
+    all(feature = "ring", feature = "aws-lc-rs"),
+    all(feature = "ring", feature = "openssl"),
+    all(feature = "ring", feature = "rust-crypto"),
+    all(feature = "aws-lc-rs", feature = "openssl"),
+    all(feature = "aws-lc-rs", feature = "rust-crypto"),
+    all(feature = "openssl", feature = "rust-crypto"),
+))]
+compile_error!("the crypto backend features are mutually exclusive, disable the default features to select another backend");
+
+#[cfg(not(any(feature = "ring", feature = "aws-lc-rs", feature = "openssl", feature = "rust-crypto")))]
+compile_error!("a crypto backend is required, enable one of the features `ring`, `aws-lc-rs`, `openssl` or `rust-crypto`");
+
+#[cfg(feature = "ring")]
+use self::ring as backend;
+#[cfg(feature = "aws-lc-rs")]
+use self::aws_lc_rs as backend;
+#[cfg(feature = "openssl")]
+use self::openssl as backend;
+#[cfg(feature = "rust-crypto")]
+use self::rust_crypto as backend;
+
+pub use self::backend::HmacContext;
+#[cfg(feature = "alg-eddsa")]
+pub use self::backend::Ed25519KeyPair;
+#[cfg(feature = "alg-rs")]
+pub use self::backend::RsaKeyPair;
+pub(crate) use self::backend::*;
+
+#[cfg(any(feature = "alg-rs", feature = "alg-eddsa"))]
+use crate::der;
+#[cfg(any(feature = "alg-rs", feature = "alg-eddsa"))]
+use crate::error::Error;
+#[cfg(feature = "alg-rs")]
+use crate::key::RsaKeySize;
+
+#[cfg(feature = "ring")]
+mod ring;
+#[cfg(feature = "aws-lc-rs")]
+#[path = "ring.rs"]
+mod aws_lc_rs;
+#[cfg(feature = "openssl")]
+mod openssl;
+#[cfg(feature = "rust-crypto")]
+mod rust_crypto;
+
+/// Hash function of an algorithm.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Hash {
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+/// Curve of an ECDSA algorithm.
+#[cfg(feature = "alg-es")]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum Curve {
+    P256,
+    P384,
+}
+
+/// Padding of an RSA algorithm.
+#[cfg(feature = "alg-rs")]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum RsaPadding {
+    Pkcs1,
+    Pss,
+}
+
+/// HMAC of the data with the key.
+#[cfg(feature = "alg-hs")]
+#[inline]
+pub(crate) fn hmac(hash: Hash, key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut context = HmacContext::new(hash, key);
+    context.update(data);
+    context.sign()
+}
+
+/// Verify an RSA signature by a DER-encoded RSAPublicKey (PKCS#1) with a modulus of the accepted
+/// size, checked before the backend so that the error names the size.
+#[cfg(feature = "alg-rs")]
+pub(crate) fn rsa_verify_sized(
+    padding: RsaPadding,
+    hash: Hash,
+    key: &[u8],
+    data: &[u8],
+    sig: &[u8],
+    size: RsaKeySize,
+) -> Result<(), Error> {
+    let (n, _) = der::rsa_public_key(key).map_err(|_| Error::InvalidSignature)?;
+    let bits = der::uint_bits(n);
+    size.check(bits)?;
+    rsa_verify(padding, hash, key, bits, data, sig)
+}
+
+/// Zero the buffer of a secret with the feature `zeroize`, a no-op without it.
+#[inline]
+pub(crate) fn wipe(_buf: &mut [u8]) {
+    #[cfg(feature = "zeroize")]
+    zeroize::Zeroize::zeroize(_buf);
+}
+
+/// The private key seed and the public key if present of a PKCS#8 v1 or v2 Ed25519 private key.
+///
+/// Accepts the explicitly tagged public key written by ring 0.16 as well as the implicit one of RFC 5958.
+#[cfg(feature = "alg-eddsa")]
+pub(crate) fn ed25519_pkcs8(pkcs8: &[u8]) -> Result<([u8; 32], Option<&[u8]>), Error> {
+    let (info, _) = der::read(pkcs8, der::SEQUENCE)?;
+    let (_version, rest) = der::read(info, der::INTEGER)?;
+    let (algorithm, rest) = der::read(rest, der::SEQUENCE)?;
+    if algorithm != der::ED25519_OID {
+        return Err(Error::InvalidKey("not an Ed25519 key"));
+    }
+    let (private_key, rest) = der::read(rest, der::OCTET_STRING)?;
+    let (seed, _) = der::read(private_key, der::OCTET_STRING)?;
+    let seed = seed.try_into().map_err(|_| Error::InvalidKey("invalid Ed25519 private key"))?;
+
+    let public_key = match der::read_any(rest) {
+        Ok((der::CONTEXT_1, contents, _)) => Some(der::read(contents, der::BIT_STRING)?.0),
+        Ok((der::IMPLICIT_1, contents, _)) => Some(contents),
+        _ => None,
+    };
+    match public_key {
+        Some([0, public_key @ ..]) => Ok((seed, Some(public_key))),
+        Some(_) => Err(Error::InvalidKey("invalid Ed25519 public key")),
+        None => Ok((seed, None)),
+    }
+}
@@ -0,0 +1,401 @@
+//! Backend of OpenSSL
+
+#[cfg(any(feature = "alg-rs", feature = "alg-eddsa"))]
+use std::fmt;
+#[cfg(any(feature = "alg-rs", feature = "alg-es", feature = "alg-eddsa"))]
+use std::sync::Arc;
+
+#[cfg(any(feature = "alg-rs", feature = "alg-es"))]
+use openssl::bn::BigNum;
+#[cfg(feature = "alg-es")]
+use openssl::bn::BigNumContext;
+#[cfg(feature = "alg-es")]
+use openssl::ec::{EcGroup, EcKey, EcPoint, PointConversionForm};
+#[cfg(feature = "alg-es")]
+use openssl::ecdsa::EcdsaSig;
+#[cfg(any(feature = "alg-rs", feature = "alg-es", feature = "alg-eddsa"))]
+use openssl::error::ErrorStack;
+use openssl::hash::{self, MessageDigest};
+use openssl::md::Md;
+use openssl::md_ctx::MdCtx;
+#[cfg(feature = "alg-es")]
+use openssl::nid::Nid;
+use openssl::pkey::PKey;
+#[cfg(any(feature = "alg-rs", feature = "alg-es", feature = "alg-eddsa"))]
+use openssl::pkey::{Private, Public};
+#[cfg(feature = "alg-eddsa")]
+use openssl::pkey::Id;
+#[cfg(feature = "alg-rs")]
+use openssl::rsa::{Padding, Rsa};
+#[cfg(feature = "alg-rs")]
+use openssl::sign::RsaPssSaltlen;
+#[cfg(any(feature = "alg-rs", feature = "alg-eddsa"))]
+use openssl::sign::{Signer, Verifier};
+
+#[cfg(feature = "alg-eddsa")]
+use crate::crypto;
+#[cfg(feature = "alg-es")]
+use crate::crypto::Curve;
+use crate::crypto::Hash;
+#[cfg(feature = "alg-rs")]
+use crate::crypto::RsaPadding;
+use crate::error::Error;
+
+/// RSA private key, the counterpart of ring's `RsaKeyPair`.
+#[cfg(feature = "alg-rs")]
+#[derive(Clone)]
+pub struct RsaKeyPair(PKey<Private>);
+
+#[cfg(feature = "alg-rs")]
+impl RsaKeyPair {
+    /// Parse a PKCS#1 `RSAPrivateKey` in DER.
+    pub fn from_der(der: &[u8]) -> Result<Self, Error> {
+        let rsa = Rsa::private_key_from_der(der).map_err(rejected)?;
+        Self::checked(rsa)
+    }
+
+    /// Parse a PKCS#8 `PrivateKeyInfo` in DER.
+    pub fn from_pkcs8(pkcs8: &[u8]) -> Result<Self, Error> {
+        let rsa = PKey::private_key_from_pkcs8(pkcs8).and_then(|x| x.rsa()).map_err(rejected)?;
+        Self::checked(rsa)
+    }
+
+    fn checked(rsa: Rsa<Private>) -> Result<Self, Error> {
+        if !rsa.check_key().map_err(rejected)? {
+            return Err(Error::InvalidKey("inconsistent RSA private key"));
+        }
+        match rsa.n().num_bits() {
+            2048..=8192 => Ok(RsaKeyPair(PKey::from_rsa(rsa).map_err(rejected)?)),
+            _ => Err(Error::InvalidKey("RSA modulus must be 2048 to 8192 bits")),
+        }
+    }
+}
+
+#[cfg(feature = "alg-rs")]
+impl fmt::Debug for RsaKeyPair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RsaKeyPair").field("bits", &self.0.bits()).finish_non_exhaustive()
+    }
+}
+
+/// Ed25519 private key, the counterpart of ring's `Ed25519KeyPair`.
+#[cfg(feature = "alg-eddsa")]
+#[derive(Clone)]
+pub struct Ed25519KeyPair(PKey<Private>);
+
+#[cfg(feature = "alg-eddsa")]
+impl Ed25519KeyPair {
+    /// Parse a PKCS#8 v1 or v2 `PrivateKeyInfo` in DER, checking the public key if present.
+    pub fn from_pkcs8(pkcs8: &[u8]) -> Result<Self, Error> {
+        let (mut seed, public_key) = crypto::ed25519_pkcs8(pkcs8)?;
+        let key = PKey::private_key_from_raw_bytes(&seed, Id::ED25519);
+        crypto::wipe(&mut seed);
+        let key = key.map_err(rejected)?;
+        match public_key {
+            Some(public_key) if public_key != key.raw_public_key().map_err(rejected)? => Err(Error::InvalidKey("Ed25519 public key mismatch")),
+            _ => Ok(Ed25519KeyPair(key)),
+        }
+    }
+}
+
+#[cfg(feature = "alg-eddsa")]
+impl fmt::Debug for Ed25519KeyPair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Ed25519KeyPair").field(&self.0.raw_public_key().ok()).finish()
+    }
+}
+
+/// Incremental HMAC computation.
+pub struct HmacContext(MdCtx);
+
+impl HmacContext {
+    /// Start with the key.
+    ///
+    /// Panics only when OpenSSL fails to allocate the context.
+    #[inline]
+    pub fn new(hash: Hash, key: &[u8]) -> Self {
+        let md = match hash {
+            Hash::Sha256 => Md::sha256(),
+            Hash::Sha384 => Md::sha384(),
+            Hash::Sha512 => Md::sha512(),
+        };
+        let mut ctx = MdCtx::new().expect("HMAC context");
+        PKey::hmac(key)
+            .and_then(|key| ctx.digest_sign_init(Some(md), &key).map(|_| ()))
+            .expect("HMAC context");
+        HmacContext(ctx)
+    }
+
+    /// Feed more data.
+    #[inline]
+    pub fn update(&mut self, data: &[u8]) {
+        self.0.digest_sign_update(data).expect("HMAC update")
+    }
+
+    /// Finish and return the tag.
+    #[inline]
+    pub fn sign(mut self) -> Vec<u8> {
+        let mut tag = Vec::new();
+        self.0.digest_sign_final_to_vec(&mut tag).expect("HMAC finish");
+        tag
+    }
+}
+
+#[inline]
+pub fn sha1(data: &[u8]) -> Vec<u8> {
+    hash::hash(MessageDigest::sha1(), data).map(|x| x.to_vec()).unwrap_or_default()
+}
+
+#[inline]
+pub fn sha256(data: &[u8]) -> Vec<u8> {
+    hash::hash(MessageDigest::sha256(), data).map(|x| x.to_vec()).unwrap_or_default()
+}
+
+/// Fill the buffer with cryptographically secure random bytes.
+#[inline]
+pub fn random(buf: &mut [u8]) -> Result<(), Error> {
+    openssl::rand::rand_bytes(buf).map_err(|_| Error::Crypto)
+}
+
+#[cfg(feature = "alg-rs")]
+pub fn rsa_sign(key: &RsaKeyPair, padding: RsaPadding, hash: Hash, data: &[u8]) -> Result<Vec<u8>, Error> {
+    let sign = || {
+        let mut signer = Signer::new(digest(hash), &key.0)?;
+        if padding == RsaPadding::Pss {
+            signer.set_rsa_padding(Padding::PKCS1_PSS)?;
+            signer.set_rsa_pss_saltlen(RsaPssSaltlen::DIGEST_LENGTH)?;
+            signer.set_rsa_mgf1_md(digest(hash))?;
+        }
+        signer.sign_oneshot_to_vec(data)
+    };
+    sign().map_err(|_| Error::Crypto)
+}
+
+/// An RSA public key for repeated verifications, parsed by OpenSSL.
+#[cfg(feature = "alg-rs")]
+#[derive(Clone)]
+pub struct ParsedRsaKey(PKey<Public>);
+
+#[cfg(feature = "alg-rs")]
+impl ParsedRsaKey {
+    /// From the big-endian modulus and public exponent without leading zeros.
+    pub fn new(n: &[u8], e: &[u8], _bits: usize) -> Result<Self, Error> {
+        let rsa = Rsa::from_public_components(BigNum::from_slice(n).map_err(rejected)?, BigNum::from_slice(e).map_err(rejected)?);
+        Ok(ParsedRsaKey(rsa.and_then(PKey::from_rsa).map_err(rejected)?))
+    }
+
+    #[inline]
+    pub fn verify(&self, padding: RsaPadding, hash: Hash, data: &[u8], sig: &[u8]) -> Result<(), Error> {
+        rsa_verify_with(&self.0, padding, hash, data, sig)
+    }
+}
+
+#[cfg(feature = "alg-rs")]
+pub fn rsa_verify(padding: RsaPadding, hash: Hash, key: &[u8], _bits: usize, data: &[u8], sig: &[u8]) -> Result<(), Error> {
+    let key = Rsa::public_key_from_der_pkcs1(key)
+        .and_then(PKey::from_rsa)
+        .map_err(|_| Error::InvalidSignature)?;
+    rsa_verify_with(&key, padding, hash, data, sig)
+}
+
+#[cfg(feature = "alg-rs")]
+fn rsa_verify_with(key: &PKey<Public>, padding: RsaPadding, hash: Hash, data: &[u8], sig: &[u8]) -> Result<(), Error> {
+    let verify = || {
+        let mut verifier = Verifier::new(digest(hash), key)?;
+        if padding == RsaPadding::Pss {
+            verifier.set_rsa_padding(Padding::PKCS1_PSS)?;
+            verifier.set_rsa_pss_saltlen(RsaPssSaltlen::DIGEST_LENGTH)?;
+            verifier.set_rsa_mgf1_md(digest(hash))?;
+        }
+        verifier.verify_oneshot(sig, data)
+    };
+    verified(verify())
+}
+
+/// The big-endian modulus and public exponent.
+#[cfg(feature = "alg-rs")]
+#[inline]
+pub fn rsa_public_components(key: &RsaKeyPair) -> (Vec<u8>, Vec<u8>) {
+    match key.0.rsa() {
+        Ok(rsa) => (rsa.n().to_vec(), rsa.e().to_vec()),
+        Err(_) => Default::default(),
+    }
+}
+
+#[cfg(feature = "alg-es")]
+pub fn ecdsa_sign(curve: Curve, pkcs8: &[u8], data: &[u8]) -> Result<Vec<u8>, Error> {
+    let key = ec_private_key(curve, pkcs8)?;
+    let (hash, len) = ecdsa_params(curve);
+    let sign = || {
+        let sig = EcdsaSig::sign(&hash::hash(hash, data)?, &key)?;
+        Ok::<_, ErrorStack>([sig.r().to_vec_padded(len)?, sig.s().to_vec_padded(len)?].concat())
+    };
+    sign().map_err(|_| Error::Crypto)
+}
+
+/// An EC public key for repeated verifications, parsed by OpenSSL.
+#[cfg(feature = "alg-es")]
+#[derive(Clone)]
+pub struct ParsedEcKey {
+    curve: Curve,
+    key: EcKey<Public>,
+}
+
+#[cfg(feature = "alg-es")]
+impl ParsedEcKey {
+    pub fn new(curve: Curve, point: &[u8]) -> Result<Self, Error> {
+        let key = ec_public_key(curve, point).map_err(rejected)?;
+        key.check_key().map_err(rejected)?;
+        Ok(ParsedEcKey { curve, key })
+    }
+
+    #[inline]
+    pub fn verify(&self, data: &[u8], sig: &[u8]) -> Result<(), Error> {
+        ecdsa_verify_with(self.curve, &self.key, data, sig)
+    }
+}
+
+#[cfg(feature = "alg-es")]
+pub fn ecdsa_verify(curve: Curve, point: &[u8], data: &[u8], sig: &[u8]) -> Result<(), Error> {
+    // ring accepts the uncompressed form only
+    if point.first() != Some(&4) {
+        return Err(Error::InvalidSignature);
+    }
+    let key = ec_public_key(curve, point).map_err(|_| Error::InvalidSignature)?;
+    ecdsa_verify_with(curve, &key, data, sig)
+}
+
+#[cfg(feature = "alg-es")]
+fn ecdsa_verify_with(curve: Curve, key: &EcKey<Public>, data: &[u8], sig: &[u8]) -> Result<(), Error> {
+    let (hash, len) = ecdsa_params(curve);
+    if sig.len() != 2 * len as usize {
+        return Err(Error::InvalidSignature);
+    }
+    let verify = || {
+        let (r, s) = sig.split_at(len as usize);
+        let sig = EcdsaSig::from_private_components(BigNum::from_slice(r)?, BigNum::from_slice(s)?)?;
+        sig.verify(&hash::hash(hash, data)?, key)
+    };
+    verified(verify())
+}
+
+#[cfg(feature = "alg-es")]
+fn ec_public_key(curve: Curve, point: &[u8]) -> Result<EcKey<Public>, ErrorStack> {
+    let group = EcGroup::from_curve_name(curve_nid(curve))?;
+    let mut ctx = BigNumContext::new()?;
+    let point = EcPoint::from_bytes(&group, point, &mut ctx)?;
+    EcKey::from_public_key(&group, &point)
+}
+
+/// The uncompressed public point of the PKCS#8 private key.
+#[cfg(feature = "alg-es")]
+pub fn ecdsa_public_point(curve: Curve, pkcs8: &[u8]) -> Result<Vec<u8>, Error> {
+    let key = ec_private_key(curve, pkcs8)?;
+    let mut ctx = BigNumContext::new().map_err(|_| Error::Crypto)?;
+    key.public_key()
+        .to_bytes(key.group(), PointConversionForm::UNCOMPRESSED, &mut ctx)
+        .map_err(|_| Error::Crypto)
+}
+
+#[cfg(feature = "alg-eddsa")]
+#[inline]
+pub fn ed25519_from_pkcs8(pkcs8: &[u8]) -> Result<Ed25519KeyPair, Error> {
+    Ed25519KeyPair::from_pkcs8(pkcs8)
+}
+
+#[cfg(feature = "alg-eddsa")]
+#[inline]
+pub fn ed25519_sign(key: &Ed25519KeyPair, data: &[u8]) -> Vec<u8> {
+    Signer::new_without_digest(&key.0)
+        .and_then(|mut signer| signer.sign_oneshot_to_vec(data))
+        .unwrap_or_default()
+}
+
+/// An Ed25519 public key for repeated verifications, parsed by OpenSSL.
+#[cfg(feature = "alg-eddsa")]
+#[derive(Clone)]
+pub struct ParsedEd25519Key(PKey<Public>);
+
+#[cfg(feature = "alg-eddsa")]
+impl ParsedEd25519Key {
+    #[inline]
+    pub fn new(key: &[u8; 32]) -> Result<Self, Error> {
+        Ok(ParsedEd25519Key(PKey::public_key_from_raw_bytes(key, Id::ED25519).map_err(rejected)?))
+    }
+
+    #[inline]
+    pub fn verify(&self, data: &[u8], sig: &[u8]) -> Result<(), Error> {
+        ed25519_verify_with(&self.0, data, sig)
+    }
+}
+
+#[cfg(feature = "alg-eddsa")]
+pub fn ed25519_verify(key: &[u8], data: &[u8], sig: &[u8]) -> Result<(), Error> {
+    let key = PKey::public_key_from_raw_bytes(key, Id::ED25519).map_err(|_| Error::InvalidSignature)?;
+    ed25519_verify_with(&key, data, sig)
+}
+
+#[cfg(feature = "alg-eddsa")]
+fn ed25519_verify_with(key: &PKey<Public>, data: &[u8], sig: &[u8]) -> Result<(), Error> {
+    let verify = || Verifier::new_without_digest(key)?.verify_oneshot(sig, data);
+    verified(verify())
+}
+
+#[cfg(feature = "alg-eddsa")]
+#[inline]
+pub fn ed25519_public_key(key: &Ed25519KeyPair) -> Vec<u8> {
+    key.0.raw_public_key().unwrap_or_default()
+}
+
+#[cfg(feature = "alg-es")]
+fn ec_private_key(curve: Curve, pkcs8: &[u8]) -> Result<EcKey<Private>, Error> {
+    let key = PKey::private_key_from_pkcs8(pkcs8).and_then(|x| x.ec_key()).map_err(rejected)?;
+    key.check_key().map_err(rejected)?;
+    match key.group().curve_name() {
+        Some(nid) if nid == curve_nid(curve) => Ok(key),
+        _ => Err(Error::InvalidKey("wrong curve")),
+    }
+}
+
+#[cfg(feature = "alg-es")]
+#[inline]
+fn ecdsa_params(curve: Curve) -> (MessageDigest, i32) {
+    match curve {
+        Curve::P256 => (MessageDigest::sha256(), 32),
+        Curve::P384 => (MessageDigest::sha384(), 48),
+    }
+}
+
+#[cfg(feature = "alg-es")]
+#[inline]
+fn curve_nid(curve: Curve) -> Nid {
+    match curve {
+        Curve::P256 => Nid::X9_62_PRIME256V1,
+        Curve::P384 => Nid::SECP384R1,
+    }
+}
+
+#[cfg(feature = "alg-rs")]
+#[inline]
+fn digest(hash: Hash) -> MessageDigest {
+    match hash {
+        Hash::Sha256 => MessageDigest::sha256(),
+        Hash::Sha384 => MessageDigest::sha384(),
+        Hash::Sha512 => MessageDigest::sha512(),
+    }
+}
+
+#[cfg(any(feature = "alg-rs", feature = "alg-es", feature = "alg-eddsa"))]
+#[inline]
+fn verified(result: Result<bool, ErrorStack>) -> Result<(), Error> {
+    match result {
+        Ok(true) => Ok(()),
+        _ => Err(Error::InvalidSignature),
+    }
+}
+
+#[cfg(any(feature = "alg-rs", feature = "alg-es", feature = "alg-eddsa"))]
+#[inline]
+fn rejected(err: ErrorStack) -> Error {
+    Error::KeyRejected(Arc::new(err))
+}
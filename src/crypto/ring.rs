@@ -0,0 +1,337 @@
+//! Backend of ring, also compiled against the API-compatible aws-lc-rs
+
+use std::sync::Arc;
+
+#[cfg(feature = "aws-lc-rs")]
+use aws_lc_rs as ring;
+
+use ring::{digest, hmac};
+use ring::rand::{SecureRandom, SystemRandom};
+#[cfg(any(feature = "alg-rs", feature = "alg-es", feature = "alg-eddsa"))]
+use ring::signature::{self, KeyPair, UnparsedPublicKey, VerificationAlgorithm};
+#[cfg(feature = "alg-es")]
+use ring::signature::EcdsaKeyPair;
+#[cfg(all(feature = "alg-es", feature = "ring"))]
+use ring::agreement;
+#[cfg(feature = "alg-rs")]
+use ring::signature::{RsaParameters, RsaPublicKeyComponents};
+#[cfg(all(any(feature = "alg-es", feature = "alg-eddsa"), feature = "aws-lc-rs"))]
+use ring::signature::ParsedPublicKey;
+
+#[cfg(feature = "alg-eddsa")]
+pub use ring::signature::Ed25519KeyPair;
+#[cfg(feature = "alg-rs")]
+pub use ring::signature::RsaKeyPair;
+
+#[cfg(feature = "alg-eddsa")]
+use crate::crypto;
+#[cfg(feature = "alg-es")]
+use crate::crypto::Curve;
+use crate::crypto::Hash;
+#[cfg(feature = "alg-rs")]
+use crate::crypto::RsaPadding;
+use crate::error::Error;
+
+/// Incremental HMAC computation.
+pub struct HmacContext(hmac::Context);
+
+impl HmacContext {
+    /// Start with the key.
+    #[inline]
+    pub fn new(hash: Hash, key: &[u8]) -> Self {
+        let alg = match hash {
+            Hash::Sha256 => hmac::HMAC_SHA256,
+            Hash::Sha384 => hmac::HMAC_SHA384,
+            Hash::Sha512 => hmac::HMAC_SHA512,
+        };
+        HmacContext(hmac::Context::with_key(&hmac::Key::new(alg, key)))
+    }
+
+    /// Feed more data.
+    #[inline]
+    pub fn update(&mut self, data: &[u8]) {
+        self.0.update(data)
+    }
+
+    /// Finish and return the tag.
+    #[inline]
+    pub fn sign(self) -> Vec<u8> {
+        self.0.sign().as_ref().to_owned()
+    }
+}
+
+#[inline]
+pub fn sha1(data: &[u8]) -> Vec<u8> {
+    digest::digest(&digest::SHA1_FOR_LEGACY_USE_ONLY, data).as_ref().to_owned()
+}
+
+#[inline]
+pub fn sha256(data: &[u8]) -> Vec<u8> {
+    digest::digest(&digest::SHA256, data).as_ref().to_owned()
+}
+
+/// Fill the buffer with cryptographically secure random bytes.
+#[inline]
+pub fn random(buf: &mut [u8]) -> Result<(), Error> {
+    Ok(SystemRandom::new().fill(buf)?)
+}
+
+#[cfg(feature = "alg-rs")]
+pub fn rsa_sign(key: &RsaKeyPair, padding: RsaPadding, hash: Hash, data: &[u8]) -> Result<Vec<u8>, Error> {
+    let encoding: &'static dyn signature::RsaEncoding = match (padding, hash) {
+        (RsaPadding::Pkcs1, Hash::Sha256) => &signature::RSA_PKCS1_SHA256,
+        (RsaPadding::Pkcs1, Hash::Sha384) => &signature::RSA_PKCS1_SHA384,
+        (RsaPadding::Pkcs1, Hash::Sha512) => &signature::RSA_PKCS1_SHA512,
+        (RsaPadding::Pss, Hash::Sha256) => &signature::RSA_PSS_SHA256,
+        (RsaPadding::Pss, Hash::Sha384) => &signature::RSA_PSS_SHA384,
+        (RsaPadding::Pss, Hash::Sha512) => &signature::RSA_PSS_SHA512,
+    };
+    let mut sig = vec![0; key.public_key().modulus_len()];
+    key.sign(encoding, &SystemRandom::new(), data, &mut sig)?;
+    Ok(sig)
+}
+
+/// An RSA public key for repeated verifications, as its components.
+#[cfg(feature = "alg-rs")]
+#[derive(Clone)]
+pub struct ParsedRsaKey {
+    components: RsaPublicKeyComponents<Vec<u8>>,
+    bits: usize,
+}
+
+#[cfg(feature = "alg-rs")]
+impl ParsedRsaKey {
+    /// From the big-endian modulus and public exponent without leading zeros.
+    #[inline]
+    pub fn new(n: &[u8], e: &[u8], bits: usize) -> Result<Self, Error> {
+        rsa_parameters(RsaPadding::Pkcs1, Hash::Sha256, bits)?;
+        Ok(ParsedRsaKey { components: RsaPublicKeyComponents { n: n.to_owned(), e: e.to_owned() }, bits })
+    }
+
+    #[inline]
+    pub fn verify(&self, padding: RsaPadding, hash: Hash, data: &[u8], sig: &[u8]) -> Result<(), Error> {
+        self.components
+            .verify(rsa_parameters(padding, hash, self.bits)?, data, sig)
+            .map_err(|_| Error::InvalidSignature)
+    }
+}
+
+#[cfg(feature = "alg-rs")]
+#[inline]
+pub fn rsa_verify(padding: RsaPadding, hash: Hash, key: &[u8], bits: usize, data: &[u8], sig: &[u8]) -> Result<(), Error> {
+    verify(rsa_parameters(padding, hash, bits)?, key, data, sig)
+}
+
+#[cfg(feature = "alg-rs")]
+fn rsa_parameters(padding: RsaPadding, hash: Hash, bits: usize) -> Result<&'static RsaParameters, Error> {
+    Ok(match (padding, hash, bits) {
+        (_, _, 8193..) => return Err(Error::InvalidKey("RSA modulus over 8192 bits not supported by the backend")),
+        (RsaPadding::Pkcs1, Hash::Sha256, 1024..=2047) => &signature::RSA_PKCS1_1024_8192_SHA256_FOR_LEGACY_USE_ONLY,
+        (RsaPadding::Pkcs1, Hash::Sha512, 1024..=2047) => &signature::RSA_PKCS1_1024_8192_SHA512_FOR_LEGACY_USE_ONLY,
+        (_, _, ..=2047) => return Err(Error::InvalidKey("RSA modulus under 2048 bits not supported by the backend")),
+        (RsaPadding::Pkcs1, Hash::Sha256, _) => &signature::RSA_PKCS1_2048_8192_SHA256,
+        (RsaPadding::Pkcs1, Hash::Sha384, _) => &signature::RSA_PKCS1_2048_8192_SHA384,
+        (RsaPadding::Pkcs1, Hash::Sha512, _) => &signature::RSA_PKCS1_2048_8192_SHA512,
+        (RsaPadding::Pss, Hash::Sha256, _) => &signature::RSA_PSS_2048_8192_SHA256,
+        (RsaPadding::Pss, Hash::Sha384, _) => &signature::RSA_PSS_2048_8192_SHA384,
+        (RsaPadding::Pss, Hash::Sha512, _) => &signature::RSA_PSS_2048_8192_SHA512,
+    })
+}
+
+/// The big-endian modulus and public exponent.
+#[cfg(feature = "alg-rs")]
+#[inline]
+pub fn rsa_public_components(key: &RsaKeyPair) -> (Vec<u8>, Vec<u8>) {
+    let RsaPublicKeyComponents { n, e } = RsaPublicKeyComponents::<Vec<u8>>::from(key.public_key());
+    (n, e)
+}
+
+#[cfg(feature = "alg-es")]
+pub fn ecdsa_sign(curve: Curve, pkcs8: &[u8], data: &[u8]) -> Result<Vec<u8>, Error> {
+    let key_pair = ecdsa_key_pair(curve, pkcs8)?;
+    Ok(key_pair.sign(&SystemRandom::new(), data)?.as_ref().to_owned())
+}
+
+/// An EC public key for repeated verifications, the point checked to be on the curve once.
+#[cfg(all(feature = "alg-es", feature = "ring"))]
+#[derive(Clone)]
+pub struct ParsedEcKey {
+    curve: Curve,
+    point: Vec<u8>,
+}
+
+#[cfg(all(feature = "alg-es", feature = "ring"))]
+impl ParsedEcKey {
+    pub fn new(curve: Curve, point: &[u8]) -> Result<Self, Error> {
+        // ring checks the public points of key agreement only
+        let alg = match curve {
+            Curve::P256 => &agreement::ECDH_P256,
+            Curve::P384 => &agreement::ECDH_P384,
+        };
+        let private = agreement::EphemeralPrivateKey::generate(alg, &SystemRandom::new())?;
+        agreement::agree_ephemeral(private, &agreement::UnparsedPublicKey::new(alg, point), |_| ())
+            .map_err(|_| Error::InvalidKey("EC point is not on the curve"))?;
+        Ok(ParsedEcKey { curve, point: point.to_owned() })
+    }
+
+    #[inline]
+    pub fn verify(&self, data: &[u8], sig: &[u8]) -> Result<(), Error> {
+        ecdsa_verify(self.curve, &self.point, data, sig)
+    }
+}
+
+/// An EC public key for repeated verifications, parsed by aws-lc-rs.
+#[cfg(all(feature = "alg-es", feature = "aws-lc-rs"))]
+#[derive(Clone)]
+pub struct ParsedEcKey(ParsedPublicKey);
+
+#[cfg(all(feature = "alg-es", feature = "aws-lc-rs"))]
+impl ParsedEcKey {
+    #[inline]
+    pub fn new(curve: Curve, point: &[u8]) -> Result<Self, Error> {
+        Ok(ParsedEcKey(ParsedPublicKey::new(ecdsa_verification(curve), point)?))
+    }
+
+    #[inline]
+    pub fn verify(&self, data: &[u8], sig: &[u8]) -> Result<(), Error> {
+        self.0.verify_sig(data, sig).map_err(|_| Error::InvalidSignature)
+    }
+}
+
+#[cfg(feature = "alg-es")]
+#[inline]
+pub fn ecdsa_verify(curve: Curve, point: &[u8], data: &[u8], sig: &[u8]) -> Result<(), Error> {
+    verify(ecdsa_verification(curve), point, data, sig)
+}
+
+#[cfg(feature = "alg-es")]
+#[inline]
+fn ecdsa_verification(curve: Curve) -> &'static signature::EcdsaVerificationAlgorithm {
+    match curve {
+        Curve::P256 => &signature::ECDSA_P256_SHA256_FIXED,
+        Curve::P384 => &signature::ECDSA_P384_SHA384_FIXED,
+    }
+}
+
+/// The uncompressed public point of the PKCS#8 private key.
+#[cfg(feature = "alg-es")]
+#[inline]
+pub fn ecdsa_public_point(curve: Curve, pkcs8: &[u8]) -> Result<Vec<u8>, Error> {
+    let key_pair = ecdsa_key_pair(curve, pkcs8)?;
+    Ok(key_pair.public_key().as_ref().to_owned())
+}
+
+/// Parse a PKCS#8 Ed25519 private key, also of the explicitly tagged public key written by
+/// ring 0.16 that ring 0.17 and aws-lc-rs reject.
+#[cfg(feature = "alg-eddsa")]
+pub fn ed25519_from_pkcs8(pkcs8: &[u8]) -> Result<Ed25519KeyPair, Error> {
+    let rejected = match Ed25519KeyPair::from_pkcs8(pkcs8) {
+        Ok(key) => return Ok(key),
+        Err(err) => err,
+    };
+    // keep the diagnostics of ring if the legacy encoding does not parse either
+    let (mut seed, public_key) = crypto::ed25519_pkcs8(pkcs8).map_err(|_| Error::from(rejected))?;
+    let key = match public_key {
+        Some(public_key) => Ed25519KeyPair::from_seed_and_public_key(&seed, public_key),
+        None => Ed25519KeyPair::from_seed_unchecked(&seed),
+    };
+    crypto::wipe(&mut seed);
+    Ok(key?)
+}
+
+#[cfg(feature = "alg-eddsa")]
+#[inline]
+pub fn ed25519_sign(key: &Ed25519KeyPair, data: &[u8]) -> Vec<u8> {
+    key.sign(data).as_ref().to_owned()
+}
+
+/// An Ed25519 public key for repeated verifications, ring decodes the point on each one.
+#[cfg(all(feature = "alg-eddsa", feature = "ring"))]
+#[derive(Clone)]
+pub struct ParsedEd25519Key([u8; 32]);
+
+#[cfg(all(feature = "alg-eddsa", feature = "ring"))]
+impl ParsedEd25519Key {
+    #[inline]
+    pub fn new(key: &[u8; 32]) -> Result<Self, Error> {
+        Ok(ParsedEd25519Key(*key))
+    }
+
+    #[inline]
+    pub fn verify(&self, data: &[u8], sig: &[u8]) -> Result<(), Error> {
+        ed25519_verify(&self.0, data, sig)
+    }
+}
+
+/// An Ed25519 public key for repeated verifications, parsed by aws-lc-rs.
+#[cfg(all(feature = "alg-eddsa", feature = "aws-lc-rs"))]
+#[derive(Clone)]
+pub struct ParsedEd25519Key(ParsedPublicKey);
+
+#[cfg(all(feature = "alg-eddsa", feature = "aws-lc-rs"))]
+impl ParsedEd25519Key {
+    #[inline]
+    pub fn new(key: &[u8; 32]) -> Result<Self, Error> {
+        Ok(ParsedEd25519Key(ParsedPublicKey::new(&signature::ED25519, key)?))
+    }
+
+    #[inline]
+    pub fn verify(&self, data: &[u8], sig: &[u8]) -> Result<(), Error> {
+        self.0.verify_sig(data, sig).map_err(|_| Error::InvalidSignature)
+    }
+}
+
+#[cfg(feature = "alg-eddsa")]
+#[inline]
+pub fn ed25519_verify(key: &[u8], data: &[u8], sig: &[u8]) -> Result<(), Error> {
+    verify(&signature::ED25519, key, data, sig)
+}
+
+#[cfg(feature = "alg-eddsa")]
+#[inline]
+pub fn ed25519_public_key(key: &Ed25519KeyPair) -> Vec<u8> {
+    key.public_key().as_ref().to_owned()
+}
+
+#[cfg(all(feature = "alg-es", feature = "ring"))]
+#[inline]
+fn ecdsa_key_pair(curve: Curve, pkcs8: &[u8]) -> Result<EcdsaKeyPair, Error> {
+    Ok(EcdsaKeyPair::from_pkcs8(ecdsa_signing(curve), pkcs8, &SystemRandom::new())?)
+}
+
+#[cfg(all(feature = "alg-es", feature = "aws-lc-rs"))]
+#[inline]
+fn ecdsa_key_pair(curve: Curve, pkcs8: &[u8]) -> Result<EcdsaKeyPair, Error> {
+    Ok(EcdsaKeyPair::from_pkcs8(ecdsa_signing(curve), pkcs8)?)
+}
+
+#[cfg(feature = "alg-es")]
+#[inline]
+fn ecdsa_signing(curve: Curve) -> &'static signature::EcdsaSigningAlgorithm {
+    match curve {
+        Curve::P256 => &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+        Curve::P384 => &signature::ECDSA_P384_SHA384_FIXED_SIGNING,
+    }
+}
+
+#[cfg(any(feature = "alg-rs", feature = "alg-es", feature = "alg-eddsa"))]
+#[inline]
+fn verify(alg: &'static dyn VerificationAlgorithm, key: &[u8], data: &[u8], sig: &[u8]) -> Result<(), Error> {
+    UnparsedPublicKey::new(alg, key)
+        .verify(data, sig)
+        .map_err(|_| Error::InvalidSignature)
+}
+
+impl From<ring::error::KeyRejected> for Error {
+    #[inline]
+    fn from(err: ring::error::KeyRejected) -> Self {
+        Error::KeyRejected(Arc::new(err))
+    }
+}
+
+impl From<ring::error::Unspecified> for Error {
+    #[inline]
+    fn from(_: ring::error::Unspecified) -> Self {
+        Error::Crypto
+    }
+}
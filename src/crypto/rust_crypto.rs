@@ -0,0 +1,343 @@
+//! Backend of RustCrypto
+
+#[cfg(any(feature = "alg-rs", feature = "alg-eddsa"))]
+use std::fmt;
+#[cfg(any(feature = "alg-rs", feature = "alg-es", feature = "alg-eddsa"))]
+use std::sync::Arc;
+
+#[cfg(any(feature = "alg-es", feature = "alg-eddsa"))]
+use ed25519_dalek::{Signer, Verifier};
+use hmac::{Hmac, Mac};
+#[cfg(feature = "alg-es")]
+use p256::pkcs8::DecodePrivateKey;
+use rand_core::{OsRng, RngCore};
+#[cfg(feature = "alg-rs")]
+use rsa::{BigUint, Pkcs1v15Sign, Pss, RsaPrivateKey, RsaPublicKey};
+#[cfg(feature = "alg-rs")]
+use rsa::pkcs1::DecodeRsaPrivateKey;
+#[cfg(feature = "alg-rs")]
+use rsa::traits::PublicKeyParts;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+#[cfg(feature = "alg-eddsa")]
+use crate::crypto;
+#[cfg(feature = "alg-es")]
+use crate::crypto::Curve;
+use crate::crypto::Hash;
+#[cfg(feature = "alg-rs")]
+use crate::crypto::RsaPadding;
+#[cfg(feature = "alg-rs")]
+use crate::der;
+use crate::error::Error;
+
+/// RSA private key, the counterpart of ring's `RsaKeyPair`.
+#[cfg(feature = "alg-rs")]
+#[derive(Clone)]
+pub struct RsaKeyPair(RsaPrivateKey);
+
+#[cfg(feature = "alg-rs")]
+impl RsaKeyPair {
+    /// Parse a PKCS#1 `RSAPrivateKey` in DER.
+    pub fn from_der(der: &[u8]) -> Result<Self, Error> {
+        let key = RsaPrivateKey::from_pkcs1_der(der).map_err(|e| Error::KeyRejected(Arc::new(e)))?;
+        Self::checked(key)
+    }
+
+    /// Parse a PKCS#8 `PrivateKeyInfo` in DER.
+    pub fn from_pkcs8(pkcs8: &[u8]) -> Result<Self, Error> {
+        let key = <RsaPrivateKey as rsa::pkcs8::DecodePrivateKey>::from_pkcs8_der(pkcs8).map_err(|e| Error::KeyRejected(Arc::new(e)))?;
+        Self::checked(key)
+    }
+
+    fn checked(key: RsaPrivateKey) -> Result<Self, Error> {
+        key.validate().map_err(|e| Error::KeyRejected(Arc::new(e)))?;
+        match key.n().bits() {
+            2048..=8192 => Ok(RsaKeyPair(key)),
+            _ => Err(Error::InvalidKey("RSA modulus must be 2048 to 8192 bits")),
+        }
+    }
+}
+
+#[cfg(feature = "alg-rs")]
+impl fmt::Debug for RsaKeyPair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RsaKeyPair").field("bits", &self.0.n().bits()).finish_non_exhaustive()
+    }
+}
+
+/// Ed25519 private key, the counterpart of ring's `Ed25519KeyPair`.
+#[cfg(feature = "alg-eddsa")]
+#[derive(Clone)]
+pub struct Ed25519KeyPair(ed25519_dalek::SigningKey);
+
+#[cfg(feature = "alg-eddsa")]
+impl Ed25519KeyPair {
+    /// Parse a PKCS#8 v1 or v2 `PrivateKeyInfo` in DER, checking the public key if present.
+    pub fn from_pkcs8(pkcs8: &[u8]) -> Result<Self, Error> {
+        let (mut seed, public_key) = crypto::ed25519_pkcs8(pkcs8)?;
+        let key = ed25519_dalek::SigningKey::from_bytes(&seed);
+        crypto::wipe(&mut seed);
+        match public_key {
+            Some(public_key) if public_key != key.verifying_key().as_bytes() => Err(Error::InvalidKey("Ed25519 public key mismatch")),
+            _ => Ok(Ed25519KeyPair(key)),
+        }
+    }
+}
+
+#[cfg(feature = "alg-eddsa")]
+impl fmt::Debug for Ed25519KeyPair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Ed25519KeyPair").field(&self.0.verifying_key()).finish()
+    }
+}
+
+/// Incremental HMAC computation.
+pub struct HmacContext(HmacInner);
+
+enum HmacInner {
+    Sha256(Hmac<Sha256>),
+    Sha384(Hmac<Sha384>),
+    Sha512(Hmac<Sha512>),
+}
+
+impl HmacContext {
+    /// Start with the key.
+    #[inline]
+    pub fn new(hash: Hash, key: &[u8]) -> Self {
+        // HMAC accepts keys of any length
+        let inner = match hash {
+            Hash::Sha256 => HmacInner::Sha256(Mac::new_from_slice(key).unwrap()),
+            Hash::Sha384 => HmacInner::Sha384(Mac::new_from_slice(key).unwrap()),
+            Hash::Sha512 => HmacInner::Sha512(Mac::new_from_slice(key).unwrap()),
+        };
+        HmacContext(inner)
+    }
+
+    /// Feed more data.
+    #[inline]
+    pub fn update(&mut self, data: &[u8]) {
+        match &mut self.0 {
+            HmacInner::Sha256(mac) => mac.update(data),
+            HmacInner::Sha384(mac) => mac.update(data),
+            HmacInner::Sha512(mac) => mac.update(data),
+        }
+    }
+
+    /// Finish and return the tag.
+    #[inline]
+    pub fn sign(self) -> Vec<u8> {
+        match self.0 {
+            HmacInner::Sha256(mac) => mac.finalize().into_bytes().to_vec(),
+            HmacInner::Sha384(mac) => mac.finalize().into_bytes().to_vec(),
+            HmacInner::Sha512(mac) => mac.finalize().into_bytes().to_vec(),
+        }
+    }
+}
+
+#[inline]
+pub fn sha1(data: &[u8]) -> Vec<u8> {
+    Sha1::digest(data).to_vec()
+}
+
+#[inline]
+pub fn sha256(data: &[u8]) -> Vec<u8> {
+    Sha256::digest(data).to_vec()
+}
+
+/// Fill the buffer with cryptographically secure random bytes.
+#[inline]
+pub fn random(buf: &mut [u8]) -> Result<(), Error> {
+    OsRng.try_fill_bytes(buf).map_err(|_| Error::Crypto)
+}
+
+#[cfg(feature = "alg-rs")]
+pub fn rsa_sign(key: &RsaKeyPair, padding: RsaPadding, hash: Hash, data: &[u8]) -> Result<Vec<u8>, Error> {
+    let hashed = digest(hash, data);
+    let sig = match (padding, hash) {
+        (RsaPadding::Pkcs1, Hash::Sha256) => key.0.sign_with_rng(&mut OsRng, Pkcs1v15Sign::new::<Sha256>(), &hashed),
+        (RsaPadding::Pkcs1, Hash::Sha384) => key.0.sign_with_rng(&mut OsRng, Pkcs1v15Sign::new::<Sha384>(), &hashed),
+        (RsaPadding::Pkcs1, Hash::Sha512) => key.0.sign_with_rng(&mut OsRng, Pkcs1v15Sign::new::<Sha512>(), &hashed),
+        (RsaPadding::Pss, Hash::Sha256) => key.0.sign_with_rng(&mut OsRng, Pss::new::<Sha256>(), &hashed),
+        (RsaPadding::Pss, Hash::Sha384) => key.0.sign_with_rng(&mut OsRng, Pss::new::<Sha384>(), &hashed),
+        (RsaPadding::Pss, Hash::Sha512) => key.0.sign_with_rng(&mut OsRng, Pss::new::<Sha512>(), &hashed),
+    };
+    sig.map_err(|_| Error::Crypto)
+}
+
+/// An RSA public key for repeated verifications, parsed by the rsa crate.
+#[cfg(feature = "alg-rs")]
+#[derive(Clone)]
+pub struct ParsedRsaKey(RsaPublicKey);
+
+#[cfg(feature = "alg-rs")]
+impl ParsedRsaKey {
+    /// From the big-endian modulus and public exponent without leading zeros.
+    pub fn new(n: &[u8], e: &[u8], bits: usize) -> Result<Self, Error> {
+        RsaPublicKey::new_with_max_size(BigUint::from_bytes_be(n), BigUint::from_bytes_be(e), bits)
+            .map(ParsedRsaKey)
+            .map_err(|e| Error::KeyRejected(Arc::new(e)))
+    }
+
+    #[inline]
+    pub fn verify(&self, padding: RsaPadding, hash: Hash, data: &[u8], sig: &[u8]) -> Result<(), Error> {
+        rsa_verify_with(&self.0, padding, hash, data, sig)
+    }
+}
+
+#[cfg(feature = "alg-rs")]
+pub fn rsa_verify(padding: RsaPadding, hash: Hash, key: &[u8], bits: usize, data: &[u8], sig: &[u8]) -> Result<(), Error> {
+    let (n, e) = der::rsa_public_key(key).map_err(|_| Error::InvalidSignature)?;
+    let key = RsaPublicKey::new_with_max_size(BigUint::from_bytes_be(n), BigUint::from_bytes_be(e), bits)
+        .map_err(|_| Error::InvalidSignature)?;
+    rsa_verify_with(&key, padding, hash, data, sig)
+}
+
+#[cfg(feature = "alg-rs")]
+fn rsa_verify_with(key: &RsaPublicKey, padding: RsaPadding, hash: Hash, data: &[u8], sig: &[u8]) -> Result<(), Error> {
+    let hashed = digest(hash, data);
+    let verified = match (padding, hash) {
+        (RsaPadding::Pkcs1, Hash::Sha256) => key.verify(Pkcs1v15Sign::new::<Sha256>(), &hashed, sig),
+        (RsaPadding::Pkcs1, Hash::Sha384) => key.verify(Pkcs1v15Sign::new::<Sha384>(), &hashed, sig),
+        (RsaPadding::Pkcs1, Hash::Sha512) => key.verify(Pkcs1v15Sign::new::<Sha512>(), &hashed, sig),
+        (RsaPadding::Pss, Hash::Sha256) => key.verify(Pss::new::<Sha256>(), &hashed, sig),
+        (RsaPadding::Pss, Hash::Sha384) => key.verify(Pss::new::<Sha384>(), &hashed, sig),
+        (RsaPadding::Pss, Hash::Sha512) => key.verify(Pss::new::<Sha512>(), &hashed, sig),
+    };
+    verified.map_err(|_| Error::InvalidSignature)
+}
+
+/// The big-endian modulus and public exponent.
+#[cfg(feature = "alg-rs")]
+#[inline]
+pub fn rsa_public_components(key: &RsaKeyPair) -> (Vec<u8>, Vec<u8>) {
+    let to_bytes = BigUint::to_bytes_be;
+    (to_bytes(key.0.n()), to_bytes(key.0.e()))
+}
+
+#[cfg(feature = "alg-es")]
+pub fn ecdsa_sign(curve: Curve, pkcs8: &[u8], data: &[u8]) -> Result<Vec<u8>, Error> {
+    let rejected = |e| Error::KeyRejected(Arc::new(e));
+    match curve {
+        Curve::P256 => {
+            let key = p256::ecdsa::SigningKey::from_pkcs8_der(pkcs8).map_err(rejected)?;
+            let sig: p256::ecdsa::Signature = key.sign(data);
+            Ok(sig.to_vec())
+        }
+        Curve::P384 => {
+            let key = p384::ecdsa::SigningKey::from_pkcs8_der(pkcs8).map_err(rejected)?;
+            let sig: p384::ecdsa::Signature = key.sign(data);
+            Ok(sig.to_vec())
+        }
+    }
+}
+
+/// An EC public key for repeated verifications, parsed by the p256 and p384 crates.
+#[cfg(feature = "alg-es")]
+#[derive(Clone)]
+pub enum ParsedEcKey {
+    P256(p256::ecdsa::VerifyingKey),
+    P384(p384::ecdsa::VerifyingKey),
+}
+
+#[cfg(feature = "alg-es")]
+impl ParsedEcKey {
+    pub fn new(curve: Curve, point: &[u8]) -> Result<Self, Error> {
+        let key = match curve {
+            Curve::P256 => p256::ecdsa::VerifyingKey::from_sec1_bytes(point).map(ParsedEcKey::P256),
+            Curve::P384 => p384::ecdsa::VerifyingKey::from_sec1_bytes(point).map(ParsedEcKey::P384),
+        };
+        key.map_err(|e| Error::KeyRejected(Arc::new(e)))
+    }
+
+    pub fn verify(&self, data: &[u8], sig: &[u8]) -> Result<(), Error> {
+        let verified = match self {
+            ParsedEcKey::P256(key) => p256::ecdsa::Signature::from_slice(sig).and_then(|sig| key.verify(data, &sig)),
+            ParsedEcKey::P384(key) => p384::ecdsa::Signature::from_slice(sig).and_then(|sig| key.verify(data, &sig)),
+        };
+        verified.map_err(|_| Error::InvalidSignature)
+    }
+}
+
+#[cfg(feature = "alg-es")]
+pub fn ecdsa_verify(curve: Curve, point: &[u8], data: &[u8], sig: &[u8]) -> Result<(), Error> {
+    // ring accepts the uncompressed form only
+    if point.first() != Some(&4) {
+        return Err(Error::InvalidSignature);
+    }
+    ParsedEcKey::new(curve, point)
+        .map_err(|_| Error::InvalidSignature)?
+        .verify(data, sig)
+}
+
+/// The uncompressed public point of the PKCS#8 private key.
+#[cfg(feature = "alg-es")]
+pub fn ecdsa_public_point(curve: Curve, pkcs8: &[u8]) -> Result<Vec<u8>, Error> {
+    let rejected = |e| Error::KeyRejected(Arc::new(e));
+    match curve {
+        Curve::P256 => {
+            let key = p256::ecdsa::SigningKey::from_pkcs8_der(pkcs8).map_err(rejected)?;
+            Ok(key.verifying_key().to_encoded_point(false).as_bytes().to_owned())
+        }
+        Curve::P384 => {
+            let key = p384::ecdsa::SigningKey::from_pkcs8_der(pkcs8).map_err(rejected)?;
+            Ok(key.verifying_key().to_encoded_point(false).as_bytes().to_owned())
+        }
+    }
+}
+
+#[cfg(feature = "alg-eddsa")]
+#[inline]
+pub fn ed25519_from_pkcs8(pkcs8: &[u8]) -> Result<Ed25519KeyPair, Error> {
+    Ed25519KeyPair::from_pkcs8(pkcs8)
+}
+
+#[cfg(feature = "alg-eddsa")]
+#[inline]
+pub fn ed25519_sign(key: &Ed25519KeyPair, data: &[u8]) -> Vec<u8> {
+    key.0.sign(data).to_vec()
+}
+
+/// An Ed25519 public key for repeated verifications, the point decompressed once.
+#[cfg(feature = "alg-eddsa")]
+#[derive(Clone)]
+pub struct ParsedEd25519Key(ed25519_dalek::VerifyingKey);
+
+#[cfg(feature = "alg-eddsa")]
+impl ParsedEd25519Key {
+    #[inline]
+    pub fn new(key: &[u8; 32]) -> Result<Self, Error> {
+        ed25519_dalek::VerifyingKey::from_bytes(key)
+            .map(ParsedEd25519Key)
+            .map_err(|e| Error::KeyRejected(Arc::new(e)))
+    }
+
+    pub fn verify(&self, data: &[u8], sig: &[u8]) -> Result<(), Error> {
+        let sig = ed25519_dalek::Signature::from_slice(sig).map_err(|_| Error::InvalidSignature)?;
+        self.0.verify(data, &sig).map_err(|_| Error::InvalidSignature)
+    }
+}
+
+#[cfg(feature = "alg-eddsa")]
+pub fn ed25519_verify(key: &[u8], data: &[u8], sig: &[u8]) -> Result<(), Error> {
+    let key: &[u8; 32] = key.try_into().map_err(|_| Error::InvalidSignature)?;
+    ParsedEd25519Key::new(key)
+        .map_err(|_| Error::InvalidSignature)?
+        .verify(data, sig)
+}
+
+#[cfg(feature = "alg-eddsa")]
+#[inline]
+pub fn ed25519_public_key(key: &Ed25519KeyPair) -> Vec<u8> {
+    key.0.verifying_key().as_bytes().to_vec()
+}
+
+#[cfg(feature = "alg-rs")]
+#[inline]
+fn digest(hash: Hash, data: &[u8]) -> Vec<u8> {
+    match hash {
+        Hash::Sha256 => Sha256::digest(data).to_vec(),
+        Hash::Sha384 => Sha384::digest(data).to_vec(),
+        Hash::Sha512 => Sha512::digest(data).to_vec(),
+    }
+}
@@ -0,0 +1,206 @@
+//! CBOR Web Tokens (CWT) signed with COSE_Sign1, requires the feature `cwt`
+//!
+//! See https://tools.ietf.org/html/rfc8392 and https://tools.ietf.org/html/rfc9052#section-4.2.
+//! The `Claims` are mapped to the integer keys of the CWT claims, with `jti` holding the `cti`
+//! base64url encoded, so the validations of `jwts::validate` apply to decoded CWTs as well.
+//! The signature algorithms are those of JWS under their COSE identifiers, HMAC, which takes
+//! a COSE_Mac0 structure, is not supported.
+//!
+//! ```rust
+//! use jwts::Claims;
+//! use jwts::cwt;
+//! use jwts::jws::alg::ES256;
+//! use jwts::validate::{ExpiredTime, Validate};
+//!
+//! let claims = Claims::new().expired_in(std::time::Duration::from_secs(60));
+//! let token = cwt::encode::<ES256>(Some(b"key-1"), &claims, include_bytes!("../tests/ecdsa-pri.pk8")).unwrap();
+//!
+//! let key = jwts::key::EcPublicKey::from_spki_der(include_bytes!("../tests/ecdsa-pub.der")).unwrap();
+//! let cwt = cwt::decode::<ES256>(&token, &key).unwrap();
+//! assert_eq!(cwt.kid.as_deref(), Some(&b"key-1"[..]));
+//! cwt.claims.validate(ExpiredTime).unwrap();
+//! ```
+
+use ciborium::Value;
+
+use crate::bs64;
+use crate::claims::Claims;
+use crate::error::Error;
+use crate::jws::Algorithm;
+
+/// Tag of a CWT, see https://tools.ietf.org/html/rfc8392#section-6
+const CWT_TAG: u64 = 61;
+/// Tag of a COSE_Sign1 message.
+const COSE_SIGN1_TAG: u64 = 18;
+/// Header label of the algorithm.
+const ALG: i64 = 1;
+/// Header label of the key identifier.
+const KID: i64 = 4;
+
+/// Claim keys, see https://tools.ietf.org/html/rfc8392#section-3.1
+const ISS: i64 = 1;
+const SUB: i64 = 2;
+const AUD: i64 = 3;
+const EXP: i64 = 4;
+const NBF: i64 = 5;
+const IAT: i64 = 6;
+const CTI: i64 = 7;
+
+/// A decoded CWT.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Cwt {
+    /// COSE algorithm identifier of the protected header
+    pub alg: i64,
+    /// Key identifier of the unprotected header
+    pub kid: Option<Vec<u8>>,
+    /// Claims of the payload
+    pub claims: Claims,
+}
+
+/// The COSE algorithm identifier of the builtin algorithm named `alg`, see
+/// https://www.iana.org/assignments/cose/cose.xhtml#algorithms
+pub fn cose_alg(alg: &str) -> Option<i64> {
+    Some(match alg {
+        "ES256" => -7,
+        "EdDSA" | "Ed25519" => -8,
+        "ES384" => -35,
+        "ES512" => -36,
+        "PS256" => -37,
+        "PS384" => -38,
+        "PS512" => -39,
+        "RS256" => -257,
+        "RS384" => -258,
+        "RS512" => -259,
+        _ => return None,
+    })
+}
+
+/// Encode the claims as a CWT claims set, a CBOR map of integer keys.
+pub fn encode_claims(claims: &Claims) -> Result<Vec<u8>, Error> {
+    let text = |key, x: &Option<String>| x.clone().map(|x| (Value::from(key), Value::Text(x)));
+    let time = |key, x: Option<u64>| x.map(|x| (Value::from(key), Value::from(x)));
+    let cti = match &claims.jti {
+        Some(jti) => Some((Value::from(CTI), Value::Bytes(bs64::to_bytes(jti).map_err(|_| Error::Malformed)?))),
+        None => None,
+    };
+    let map = [
+        text(ISS, &claims.iss),
+        text(SUB, &claims.sub),
+        text(AUD, &claims.aud),
+        time(EXP, claims.exp),
+        time(NBF, claims.nbf),
+        time(IAT, claims.iat),
+        cti,
+    ];
+    to_vec(&Value::Map(map.into_iter().flatten().collect()))
+}
+
+/// Decode a CWT claims set, the claims of other keys are ignored.
+pub fn decode_claims(bytes: &[u8]) -> Result<Claims, Error> {
+    let Value::Map(map) = from_slice(bytes)? else { return Err(Error::Malformed) };
+    let mut claims = Claims::new();
+    for (key, value) in map {
+        let Some(key) = key.as_integer().and_then(|x| i64::try_from(x).ok()) else { continue };
+        let text = || value.as_text().map(str::to_owned).ok_or(Error::Malformed).map(Some);
+        let time = || value.as_integer().and_then(|x| u64::try_from(x).ok()).ok_or(Error::Malformed).map(Some);
+        match key {
+            ISS => claims.iss = text()?,
+            SUB => claims.sub = text()?,
+            AUD => claims.aud = text()?,
+            EXP => claims.exp = time()?,
+            NBF => claims.nbf = time()?,
+            IAT => claims.iat = time()?,
+            CTI => claims.jti = Some(bs64::from_bytes(value.as_bytes().ok_or(Error::Malformed)?)),
+            _ => {}
+        }
+    }
+    Ok(claims)
+}
+
+/// Encode the claims as a tagged COSE_Sign1 message signed with the algorithm.
+pub fn encode<A: Algorithm>(kid: Option<&[u8]>, claims: &Claims, key: &A::SignKey) -> Result<Vec<u8>, Error> {
+    let alg = cose_alg(A::name()).ok_or(Error::Rejected("algorithm not supported by COSE_Sign1"))?;
+    let protected = to_vec(&Value::Map(vec![(Value::from(ALG), Value::from(alg))]))?;
+    let unprotected = kid.map(|x| (Value::from(KID), Value::Bytes(x.to_owned()))).into_iter().collect();
+    let payload = encode_claims(claims)?;
+    let signature = A::sign(sig_structure(&protected, &payload)?, key)?;
+    let message = Value::Array(vec![Value::Bytes(protected), Value::Map(unprotected), Value::Bytes(payload), Value::Bytes(signature)]);
+    to_vec(&Value::Tag(COSE_SIGN1_TAG, Box::new(message)))
+}
+
+/// Decode a COSE_Sign1 message, optionally tagged, and verify it with the algorithm and key.
+pub fn decode<A: Algorithm>(bytes: &[u8], key: &A::VerifyKey) -> Result<Cwt, Error> {
+    let message = Message::parse(bytes)?;
+    if cose_alg(A::name()) != Some(message.alg) {
+        return Err(Error::Rejected("unexpected alg"));
+    }
+    A::verify(sig_structure(&message.protected, &message.payload)?, &message.signature, key)?;
+    Ok(Cwt { alg: message.alg, kid: message.kid, claims: decode_claims(&message.payload)? })
+}
+
+/// Decode a COSE_Sign1 message without verification, e.g. to select the key by `kid`.
+pub fn decode_unverified(bytes: &[u8]) -> Result<Cwt, Error> {
+    let message = Message::parse(bytes)?;
+    Ok(Cwt { alg: message.alg, kid: message.kid, claims: decode_claims(&message.payload)? })
+}
+
+/// The parts of a COSE_Sign1 message.
+struct Message {
+    protected: Vec<u8>,
+    alg: i64,
+    kid: Option<Vec<u8>>,
+    payload: Vec<u8>,
+    signature: Vec<u8>,
+}
+
+impl Message {
+    fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        let mut value = from_slice(bytes)?;
+        if let Value::Tag(CWT_TAG, inner) = value {
+            value = *inner;
+        }
+        if let Value::Tag(COSE_SIGN1_TAG, inner) = value {
+            value = *inner;
+        }
+        let Value::Array(items) = value else { return Err(Error::Malformed) };
+        let [Value::Bytes(protected), Value::Map(unprotected), Value::Bytes(payload), Value::Bytes(signature)] = <[Value; 4]>::try_from(items).map_err(|_| Error::Malformed)? else {
+            return Err(Error::Malformed);
+        };
+        let Value::Map(headers) = from_slice(&protected)? else { return Err(Error::Malformed) };
+        let alg = label(&headers, ALG)
+            .and_then(|x| x.as_integer())
+            .and_then(|x| i64::try_from(x).ok())
+            .ok_or(Error::Rejected("missing alg"))?;
+        let kid = label(&headers, KID).or_else(|| label(&unprotected, KID))
+            .map(|x| x.as_bytes().cloned().ok_or(Error::Malformed))
+            .transpose()?;
+        Ok(Message { protected, alg, kid, payload, signature })
+    }
+}
+
+/// The value of the integer label in a header map.
+fn label(map: &[(Value, Value)], label: i64) -> Option<&Value> {
+    map.iter().find(|(k, _)| k.as_integer() == Some(label.into())).map(|(_, v)| v)
+}
+
+/// The Sig_structure signed for a COSE_Sign1 message without external AAD.
+fn sig_structure(protected: &[u8], payload: &[u8]) -> Result<Vec<u8>, Error> {
+    to_vec(&Value::Array(vec![
+        Value::Text("Signature1".to_owned()),
+        Value::Bytes(protected.to_owned()),
+        Value::Bytes(Vec::new()),
+        Value::Bytes(payload.to_owned()),
+    ]))
+}
+
+#[inline]
+fn to_vec(value: &Value) -> Result<Vec<u8>, Error> {
+    let mut buf = Vec::new();
+    ciborium::into_writer(value, &mut buf).map_err(|_| Error::Malformed)?;
+    Ok(buf)
+}
+
+#[inline]
+fn from_slice(bytes: &[u8]) -> Result<Value, Error> {
+    ciborium::from_reader(bytes).map_err(|_| Error::Malformed)
+}
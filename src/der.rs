@@ -0,0 +1,156 @@
+//! Minimal DER reader and writer
+
+use crate::error::Error;
+
+const MALFORMED: Error = Error::InvalidKey("malformed DER");
+
+pub const INTEGER: u8 = 0x02;
+pub const BIT_STRING: u8 = 0x03;
+#[cfg_attr(not(all(feature = "alg-eddsa", any(feature = "openssl", feature = "rust-crypto"))), allow(dead_code))]
+pub const OCTET_STRING: u8 = 0x04;
+pub const SEQUENCE: u8 = 0x30;
+pub const CONTEXT_0: u8 = 0xa0;
+pub const CONTEXT_1: u8 = 0xa1;
+pub const IMPLICIT_1: u8 = 0x81;
+
+/// The object identifiers of the key algorithms and curves, including the tag and length.
+pub const RSA_OID: &[u8] = &[0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01];
+pub const EC_OID: &[u8] = &[0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+pub const P256_OID: &[u8] = &[0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+pub const P384_OID: &[u8] = &[0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x22];
+/// Ed25519, RFC 8410, also the complete AlgorithmIdentifier contents.
+pub const ED25519_OID: &[u8] = &[0x06, 0x03, 0x2b, 0x65, 0x70];
+
+/// Read a TLV of any tag, return the tag, the contents and the remaining input.
+pub fn read_any(input: &[u8]) -> Result<(u8, &[u8], &[u8]), Error> {
+    let (&tag, input) = input.split_first().ok_or(MALFORMED)?;
+    let (&first, input) = input.split_first().ok_or(MALFORMED)?;
+    let (len, input) = match first {
+        0..=0x7f => (first as usize, input),
+        0x81..=0x84 => {
+            let n = (first & 0x7f) as usize;
+            if input.len() < n {
+                return Err(MALFORMED);
+            }
+            let (len, input) = input.split_at(n);
+            (len.iter().fold(0, |acc, &x| acc << 8 | x as usize), input)
+        }
+        _ => return Err(MALFORMED),
+    };
+    if input.len() < len {
+        return Err(MALFORMED);
+    }
+    let (contents, rest) = input.split_at(len);
+    Ok((tag, contents, rest))
+}
+
+/// Read a TLV with the expected tag, return the contents and the remaining input.
+pub fn read(input: &[u8], tag: u8) -> Result<(&[u8], &[u8]), Error> {
+    match read_any(input)? {
+        (actual, contents, rest) if actual == tag => Ok((contents, rest)),
+        _ => Err(MALFORMED),
+    }
+}
+
+/// The subjectPublicKey bits of a SubjectPublicKeyInfo.
+pub fn spki_public_key(spki: &[u8]) -> Result<&[u8], Error> {
+    let (spki, _) = read(spki, SEQUENCE)?;
+    let (_algorithm, rest) = read(spki, SEQUENCE)?;
+    let (bits, _) = read(rest, BIT_STRING)?;
+    match bits.split_first() {
+        Some((0, key)) => Ok(key),
+        _ => Err(MALFORMED),
+    }
+}
+
+/// The AlgorithmIdentifier contents of a SubjectPublicKeyInfo, the OID with its parameters.
+#[cfg_attr(not(feature = "x5c"), allow(dead_code))]
+pub fn spki_algorithm(spki: &[u8]) -> Result<&[u8], Error> {
+    let (spki, _) = read(spki, SEQUENCE)?;
+    let (algorithm, _) = read(spki, SEQUENCE)?;
+    Ok(algorithm)
+}
+
+/// The modulus and public exponent of a DER-encoded RSAPublicKey (PKCS#1).
+#[cfg_attr(not(feature = "alg-rs"), allow(dead_code))]
+pub fn rsa_public_key(der: &[u8]) -> Result<(&[u8], &[u8]), Error> {
+    let (key, rest) = read(der, SEQUENCE)?;
+    let (n, key) = read(key, INTEGER)?;
+    let (e, key) = read(key, INTEGER)?;
+    if !rest.is_empty() || !key.is_empty() {
+        return Err(MALFORMED);
+    }
+    Ok((n, e))
+}
+
+/// The bit length of an unsigned big-endian integer.
+pub fn uint_bits(bytes: &[u8]) -> usize {
+    let skip = bytes.iter().take_while(|&&x| x == 0).count();
+    match bytes[skip..].first() {
+        Some(&x) => (bytes.len() - skip) * 8 - x.leading_zeros() as usize,
+        None => 0,
+    }
+}
+
+/// The fixed `r || s` form of a DER-encoded ECDSA-Sig-Value, each integer padded to `len` bytes.
+pub fn ecdsa_signature(der: &[u8], len: usize) -> Result<Vec<u8>, Error> {
+    let (sig, rest) = read(der, SEQUENCE)?;
+    let (r, sig) = read(sig, INTEGER)?;
+    let (s, sig) = read(sig, INTEGER)?;
+    if !rest.is_empty() || !sig.is_empty() {
+        return Err(MALFORMED);
+    }
+    let mut fixed = vec![0; len * 2];
+    for (int, out) in [r, s].into_iter().zip(fixed.chunks_mut(len)) {
+        let skip = int.iter().take_while(|&&x| x == 0).count();
+        let int = &int[skip..];
+        if int.is_empty() || int.len() > len || (skip == 0 && int[0] & 0x80 != 0) {
+            return Err(MALFORMED);
+        }
+        out[len - int.len()..].copy_from_slice(int);
+    }
+    Ok(fixed)
+}
+
+/// The SubjectPublicKeyInfo of a certificate, including the outer tag and length.
+pub fn certificate_spki(cert: &[u8]) -> Result<&[u8], Error> {
+    let (cert, _) = read(cert, SEQUENCE)?;
+    let (tbs, _) = read(cert, SEQUENCE)?;
+    let mut rest = match read_any(tbs)? {
+        (CONTEXT_0, _version, rest) => rest,
+        _ => tbs,
+    };
+    // serialNumber, signature, issuer, validity, subject
+    for _ in 0..5 {
+        rest = read_any(rest)?.2;
+    }
+    let (_, _, after) = read_any(rest)?;
+    Ok(&rest[..rest.len() - after.len()])
+}
+
+/// Write a TLV.
+pub fn write(tag: u8, contents: &[u8]) -> Vec<u8> {
+    let len = contents.len();
+    let mut out = Vec::with_capacity(len + 6);
+    out.push(tag);
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let bytes = len.to_be_bytes();
+        let skip = bytes.iter().take_while(|&&x| x == 0).count();
+        out.push(0x80 | (bytes.len() - skip) as u8);
+        out.extend_from_slice(&bytes[skip..]);
+    }
+    out.extend_from_slice(contents);
+    out
+}
+
+/// Write an unsigned big-endian integer as INTEGER.
+pub fn write_uint(bytes: &[u8]) -> Vec<u8> {
+    let skip = bytes.iter().take_while(|&&x| x == 0).count();
+    let bytes = &bytes[skip..];
+    match bytes.first() {
+        Some(&x) if x & 0x80 == 0 => write(INTEGER, bytes),
+        _ => write(INTEGER, &[&[0], bytes].concat()),
+    }
+}
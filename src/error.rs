@@ -1,57 +1,254 @@
 //! Errors
 
 use std::fmt::{Display, Formatter};
+use std::sync::Arc;
 
-/// An error that might occur when signing and decode a token
+use crate::validate::ValidateError;
+
+/// Result with the crate `Error` by default, or e.g. `VerifyError` when claims are validated too.
+///
+/// ```rust
+/// use jwts::{Claims, jws, VerifyError};
+/// use jwts::jws::{Header, VerifyWith};
+/// use jwts::jws::alg::HS256;
+/// use jwts::validate::{ExpectIss, Validate};
+///
+/// fn issuer_checked(token: &str) -> jwts::Result<Claims, VerifyError> {
+///     let token = jws::decode::<Claims>(token, VerifyWith::<HS256>(b"secret"))?;
+///     token.payload.validate(ExpectIss("sea"))?;
+///     Ok(token.payload)
+/// }
+///
+/// let claims = Claims { iss: Some("sea".to_owned()), ..Default::default() };
+/// let token = jws::encode::<HS256>(Header::default(), &claims, b"secret").unwrap();
+/// assert_eq!(issuer_checked(&token).unwrap(), claims);
+/// ```
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// A segment of a token in compact serialization.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Segment {
+    Header,
+    Payload,
+    Signature,
+}
+
+impl Display for Segment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Segment::Header => f.write_str("header"),
+            Segment::Payload => f.write_str("payload"),
+            Segment::Signature => f.write_str("signature"),
+        }
+    }
+}
+
+/// An error that might occur when signing and decode a token
+#[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum Error {
-    /// Token malformed
+    /// Token is not made of dot-separated segments
     Malformed,
+    /// Token bytes are not valid UTF-8
+    InvalidUtf8(std::str::Utf8Error),
+    /// A segment is not valid base64url
+    InvalidBase64 { segment: Segment, source: base64::DecodeError },
+    /// A segment is not valid JSON or does not fit the expected type
+    InvalidJson { segment: Segment, source: Arc<serde_json::Error> },
+    /// Payload could not be encoded or decoded by a non-JSON codec
+    InvalidPayload(Arc<dyn std::error::Error + Send + Sync>),
     /// Signature does not match
     InvalidSignature,
     /// An invalid key provided
     InvalidKey(&'static str),
+    /// RSA modulus of `bits` outside the accepted sizes, see `RsaKeySize`
+    RsaKeySize { bits: usize, min: usize, max: usize },
+    /// Key rejected by the crypto backend
+    KeyRejected(Arc<dyn std::error::Error + Send + Sync>),
+    /// Token or key not allowed by the configured policy
+    Rejected(&'static str),
+    /// Payload could not be read
+    Io(Arc<std::io::Error>),
+    /// Remote resource, such as a JWK Set, could not be fetched
+    Fetch(Arc<dyn std::error::Error + Send + Sync>),
     /// Unspecific crypto error
     Crypto,
 }
 
+impl Error {
+    #[inline]
+    pub(crate) fn base64(segment: Segment, source: base64::DecodeError) -> Self {
+        Error::InvalidBase64 { segment, source }
+    }
+
+    #[inline]
+    pub(crate) fn io(source: std::io::Error) -> Self {
+        Error::Io(Arc::new(source))
+    }
+
+    /// An error of fetching a remote resource, e.g. in an `HttpGet` implementation.
+    #[inline]
+    pub fn fetch(source: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        Error::Fetch(Arc::from(source.into()))
+    }
+
+    /// An error of encoding or decoding a payload, e.g. in a `PayloadCodec` implementation.
+    #[inline]
+    pub fn payload(source: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        Error::InvalidPayload(Arc::from(source.into()))
+    }
+
+    #[inline]
+    pub(crate) fn json(segment: Segment, source: serde_json::Error) -> Self {
+        Error::InvalidJson { segment, source: Arc::new(source) }
+    }
+}
+
+impl Error {
+    /// The category of the error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Malformed | Error::InvalidUtf8(_) | Error::InvalidBase64 { .. } | Error::InvalidJson { .. } | Error::InvalidPayload(_) => ErrorKind::Malformed,
+            Error::InvalidSignature | Error::Crypto => ErrorKind::Crypto,
+            Error::InvalidKey(_) | Error::RsaKeySize { .. } | Error::KeyRejected(_) => ErrorKind::KeyProblem,
+            Error::Rejected(_) => ErrorKind::PolicyViolation,
+            Error::Fetch(_) => ErrorKind::Unavailable,
+            Error::Io(_) => ErrorKind::Internal,
+        }
+    }
+}
+
+impl PartialEq for Error {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Error::Malformed, Error::Malformed) => true,
+            (Error::InvalidUtf8(x), Error::InvalidUtf8(y)) => x == y,
+            (Error::InvalidBase64 { segment: a, source: x }, Error::InvalidBase64 { segment: b, source: y }) => a == b && x == y,
+            (Error::InvalidJson { segment: a, source: x }, Error::InvalidJson { segment: b, source: y }) => {
+                a == b && x.classify() == y.classify() && x.line() == y.line() && x.column() == y.column()
+            }
+            (Error::InvalidPayload(x), Error::InvalidPayload(y)) => x.to_string() == y.to_string(),
+            (Error::InvalidSignature, Error::InvalidSignature) => true,
+            (Error::InvalidKey(x), Error::InvalidKey(y)) => x == y,
+            (Error::RsaKeySize { bits: a, min: b, max: c }, Error::RsaKeySize { bits: x, min: y, max: z }) => (a, b, c) == (x, y, z),
+            (Error::KeyRejected(x), Error::KeyRejected(y)) => x.to_string() == y.to_string(),
+            (Error::Rejected(x), Error::Rejected(y)) => x == y,
+            (Error::Io(x), Error::Io(y)) => x.kind() == y.kind(),
+            (Error::Fetch(x), Error::Fetch(y)) => x.to_string() == y.to_string(),
+            (Error::Crypto, Error::Crypto) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Error {}
+
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Error::Malformed => f.write_str("Malformed"),
+            Error::InvalidUtf8(source) => write!(f, "Invalid UTF-8: {}", source),
+            Error::InvalidBase64 { segment, source } => write!(f, "Invalid base64 in {}: {}", segment, source),
+            Error::InvalidJson { segment, source } => write!(f, "Invalid JSON in {}: {}", segment, source),
+            Error::InvalidPayload(source) => write!(f, "Invalid payload: {}", source),
             Error::InvalidSignature => f.write_str("Invalid signature"),
             Error::InvalidKey(cause) => write!(f, "Invalid key: {}", cause),
+            Error::RsaKeySize { bits, min, max } => write!(f, "Invalid key: RSA modulus of {} bits, accepted {} to {}", bits, min, max),
+            Error::KeyRejected(cause) => write!(f, "Key rejected: {}", cause),
+            Error::Rejected(cause) => write!(f, "Rejected by policy: {}", cause),
+            Error::Io(source) => write!(f, "Read error: {}", source),
+            Error::Fetch(source) => write!(f, "Fetch error: {}", source),
             Error::Crypto => f.write_str("Unspecific crypto error"),
         }
     }
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::InvalidUtf8(source) => Some(source),
+            Error::InvalidBase64 { source, .. } => Some(source),
+            Error::InvalidJson { source, .. } => Some(source.as_ref()),
+            Error::InvalidPayload(source) => Some(source.as_ref()),
+            Error::KeyRejected(source) => Some(source.as_ref()),
+            Error::Io(source) => Some(source.as_ref()),
+            Error::Fetch(source) => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
 
-impl From<base64::DecodeError> for Error {
-    #[inline]
-    fn from(_: base64::DecodeError) -> Self {
-        Error::Malformed
+/// Category of an error, e.g. to choose an HTTP status: `Malformed` as 400, `PolicyViolation`
+/// as 403, `Unavailable` as 503, `Internal` as 500, and the others as 401.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// Token could not be decoded
+    Malformed,
+    /// Signature does not match or could not be checked
+    Crypto,
+    /// Key is invalid or could not be found
+    KeyProblem,
+    /// Token expired
+    Expired,
+    /// Claims do not match the expected values
+    ClaimMismatch,
+    /// Token or key not allowed by the configured policy
+    PolicyViolation,
+    /// Keys could not be fetched, a failure of the service rather than of the token
+    Unavailable,
+    /// Payload could not be read, a failure of the service rather than of the token
+    Internal,
+}
+
+/// An error that might occur when verifying a token and validating its claims.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum VerifyError {
+    /// Token decoding or signature verification failed
+    Token(Error),
+    /// Claims validation failed
+    Claims(ValidateError),
+}
+
+impl VerifyError {
+    /// The category of the error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            VerifyError::Token(err) => err.kind(),
+            VerifyError::Claims(err) => err.kind(),
+        }
     }
 }
 
-impl From<serde_json::Error> for Error {
-    #[inline]
-    fn from(_: serde_json::Error) -> Self {
-        Error::Malformed
+impl Display for VerifyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VerifyError::Token(_) => f.write_str("token rejected"),
+            VerifyError::Claims(_) => f.write_str("claims rejected"),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            VerifyError::Token(err) => Some(err),
+            VerifyError::Claims(err) => Some(err),
+        }
     }
 }
 
-impl From<ring::error::KeyRejected> for Error {
+impl From<Error> for VerifyError {
     #[inline]
-    fn from(err: ring::error::KeyRejected) -> Self {
-        Error::InvalidKey(err.description_())
+    fn from(err: Error) -> Self {
+        VerifyError::Token(err)
     }
 }
 
-impl From<ring::error::Unspecified> for Error {
+impl From<ValidateError> for VerifyError {
     #[inline]
-    fn from(_: ring::error::Unspecified) -> Self {
-        Error::Crypto
+    fn from(err: ValidateError) -> Self {
+        VerifyError::Claims(err)
     }
 }
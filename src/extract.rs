@@ -0,0 +1,98 @@
+//! Extraction of the token from a request, by the middleware integrations
+//!
+//! `TokenExtractor` looks for the token in the bearer `Authorization` header, then in a named
+//! cookie and a named query parameter if configured.
+//!
+//! ```rust
+//! use jwts::extract::TokenExtractor;
+//!
+//! let extractor = TokenExtractor::new().with_cookie("session");
+//! assert_eq!(extractor.extract(None, ["theme=dark; session=xxx.yyy.zzz"], Some("session=aaa.bbb.ccc")), Some("xxx.yyy.zzz"));
+//! assert_eq!(extractor.extract(Some("Bearer aaa.bbb.ccc"), ["session=xxx.yyy.zzz"], None), Some("aaa.bbb.ccc"));
+//! ```
+
+use crate::bearer;
+
+/// Where to look for the token of a request, the bearer `Authorization` header by default.
+#[derive(Debug, Clone)]
+pub struct TokenExtractor {
+    header: bool,
+    cookie: Option<String>,
+    query: Option<String>,
+}
+
+impl TokenExtractor {
+    /// Create a `TokenExtractor` of the bearer `Authorization` header only.
+    #[inline]
+    pub fn new() -> Self {
+        TokenExtractor {
+            header: true,
+            cookie: None,
+            query: None,
+        }
+    }
+
+    /// Ignore the `Authorization` header, for the cookie or the query parameter only.
+    #[inline]
+    pub fn without_header(self) -> Self {
+        TokenExtractor { header: false, ..self }
+    }
+
+    /// Also look for the token in the cookie of the name.
+    #[inline]
+    pub fn with_cookie(self, name: impl Into<String>) -> Self {
+        TokenExtractor { cookie: Some(name.into()), ..self }
+    }
+
+    /// Also look for the token in the query parameter of the name, after the header and the cookie.
+    ///
+    /// Tokens in URLs end up in access logs, browser history and `Referer` headers, enable it only
+    /// where a header can not be set, such as WebSocket handshakes from browsers.
+    #[inline]
+    pub fn with_query(self, name: impl Into<String>) -> Self {
+        TokenExtractor { query: Some(name.into()), ..self }
+    }
+
+    /// The token of the request with the `Authorization` header, the `Cookie` headers and the query string.
+    pub fn extract<'a>(&self, authorization: Option<&'a str>, cookies: impl IntoIterator<Item=&'a str>, query: Option<&'a str>) -> Option<&'a str> {
+        if let Some(token) = authorization.filter(|_| self.header).and_then(bearer::parse) {
+            return Some(token);
+        }
+        if let Some(name) = &self.cookie {
+            if let Some(token) = cookies.into_iter().find_map(|x| cookie(x, name)) {
+                return Some(token);
+            }
+        }
+        match (&self.query, query) {
+            (Some(name), Some(query)) => query_param(query, name),
+            _ => None,
+        }
+    }
+}
+
+impl Default for TokenExtractor {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The non-empty value of the cookie of the name in a `Cookie` header value.
+pub fn cookie<'a>(header: &'a str, name: &str) -> Option<&'a str> {
+    header.split(';')
+        .filter_map(|x| x.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.trim_matches('"'))
+        .filter(|x| !x.is_empty())
+}
+
+/// The non-empty value of the parameter of the name in a query string, not percent-decoded,
+/// as tokens are base64url.
+pub fn query_param<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    query.trim_start_matches('?')
+        .split('&')
+        .filter_map(|x| x.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+        .filter(|x| !x.is_empty())
+}
@@ -0,0 +1,181 @@
+//! Keys loaded from local files
+//!
+//! `FileJwks` is a `KeyStore` of a JWK Set file for deployments without access to the key
+//! endpoint of the issuer, reloaded when the file is modified. The modification time is polled
+//! at most once per interval when a key is looked up; with the feature `notify`, `watch`
+//! reloads on file system events instead.
+//!
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use jwts::{Claims, jws};
+//! use jwts::file::FileJwks;
+//! use jwts::jws::alg::RS256;
+//! use jwts::keystore::VerifyWithKeyStore;
+//!
+//! let jwks = FileJwks::open("/etc/jwt/jwks.json").unwrap().with_interval(Duration::from_secs(10));
+//! # let token = "";
+//! jws::decode::<Claims>(token, VerifyWithKeyStore::<RS256, _>::new(&jwks)).unwrap();
+//! ```
+
+use std::fmt::{Debug, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+#[cfg(feature = "notify")]
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime};
+
+use serde_json as json;
+
+use crate::error::Error;
+use crate::jwk::JwkSet;
+use crate::jws::Header;
+use crate::keystore::KeyStore;
+
+/// A JWK Set file, reloaded when modified.
+///
+/// A file that fails to load keeps the keys loaded before, until it is fixed. Clones share the
+/// loaded keys and the watcher.
+#[derive(Clone)]
+pub struct FileJwks {
+    path: PathBuf,
+    interval: Duration,
+    loaded: Arc<RwLock<Loaded>>,
+    #[cfg(feature = "notify")]
+    changed: Arc<AtomicBool>,
+    #[cfg(feature = "notify")]
+    watcher: Option<Arc<notify::RecommendedWatcher>>,
+}
+
+struct Loaded {
+    jwks: Arc<JwkSet>,
+    modified: Option<SystemTime>,
+    checked: Instant,
+}
+
+impl Debug for FileJwks {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileJwks")
+            .field("path", &self.path)
+            .field("interval", &self.interval)
+            .finish_non_exhaustive()
+    }
+}
+
+impl FileJwks {
+    /// Load the JWK Set file, checking it for modifications every second.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, Error> {
+        let path = path.into();
+        let (jwks, modified) = load(&path)?;
+        Ok(FileJwks {
+            path,
+            interval: Duration::from_secs(1),
+            loaded: Arc::new(RwLock::new(Loaded { jwks: Arc::new(jwks), modified, checked: Instant::now() })),
+            #[cfg(feature = "notify")]
+            changed: Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "notify")]
+            watcher: None,
+        })
+    }
+
+    /// Check the file for modifications at most once per interval, `Duration::ZERO` on every lookup.
+    #[inline]
+    pub fn with_interval(self, interval: Duration) -> Self {
+        FileJwks { interval, ..self }
+    }
+
+    /// Reload on file system events of the file, instead of polling its modification time.
+    ///
+    /// Requires the feature `notify`.
+    #[cfg(feature = "notify")]
+    pub fn watch(mut self) -> Result<Self, Error> {
+        use notify::Watcher;
+
+        let changed = self.changed.clone();
+        let path = self.path.clone();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            if event.is_ok_and(|x| x.paths.iter().any(|p| p.file_name() == path.file_name())) {
+                changed.store(true, Ordering::Release);
+            }
+        }).map_err(Error::fetch)?;
+        // watch the directory, editors and orchestrators replace the file instead of writing it
+        let dir = self.path.parent().filter(|x| !x.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        watcher.watch(dir, notify::RecursiveMode::NonRecursive).map_err(Error::fetch)?;
+        self.watcher = Some(Arc::new(watcher));
+        Ok(self)
+    }
+
+    /// The path of the file.
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The keys, reloaded first if the file was modified.
+    pub fn jwks(&self) -> Arc<JwkSet> {
+        #[cfg(feature = "notify")]
+        if self.watcher.is_some() {
+            if self.changed.swap(false, Ordering::AcqRel) {
+                let _ = self.reload();
+            }
+            return self.current();
+        }
+        let checked = self.loaded.read().unwrap_or_else(|e| e.into_inner()).checked;
+        if checked.elapsed() >= self.interval {
+            let _ = self.reload_if_modified();
+        }
+        self.current()
+    }
+
+    /// Reload the file now.
+    pub fn reload(&self) -> Result<(), Error> {
+        let (jwks, modified) = load(&self.path)?;
+        let mut loaded = self.loaded.write().unwrap_or_else(|e| e.into_inner());
+        *loaded = Loaded { jwks: Arc::new(jwks), modified, checked: Instant::now() };
+        Ok(())
+    }
+
+    #[inline]
+    fn current(&self) -> Arc<JwkSet> {
+        self.loaded.read().unwrap_or_else(|e| e.into_inner()).jwks.clone()
+    }
+
+    fn reload_if_modified(&self) -> Result<(), Error> {
+        let modified = fs::metadata(&self.path).and_then(|x| x.modified()).ok();
+        {
+            let mut loaded = self.loaded.write().unwrap_or_else(|e| e.into_inner());
+            loaded.checked = Instant::now();
+            if modified.is_some() && modified == loaded.modified {
+                return Ok(());
+            }
+        }
+        self.reload()
+    }
+}
+
+/// Keys by `kid`, or the only key for tokens without one, like a `JwkSet`.
+impl<P> KeyStore<P> for FileJwks {
+    type Key = Vec<u8>;
+
+    #[inline]
+    fn key_for(&self, header: &Header, payload: &P) -> Option<Vec<u8>> {
+        self.jwks().key_for(header, payload)
+    }
+}
+
+/// Keys by `kid`, or the only key for tokens without one, like a `JwkSet`.
+impl<P> KeyStore<P> for &FileJwks {
+    type Key = Vec<u8>;
+
+    #[inline]
+    fn key_for(&self, header: &Header, payload: &P) -> Option<Vec<u8>> {
+        (**self).key_for(header, payload)
+    }
+}
+
+fn load(path: &Path) -> Result<(JwkSet, Option<SystemTime>), Error> {
+    let modified = fs::metadata(path).and_then(|x| x.modified()).ok();
+    let json = fs::read(path).map_err(Error::fetch)?;
+    let jwks = json::from_slice(&json).map_err(|_| Error::InvalidKey("malformed jwk set"))?;
+    Ok((jwks, modified))
+}
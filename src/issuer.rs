@@ -0,0 +1,138 @@
+//! Issuer
+
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use crate::claims::Claims;
+#[cfg(feature = "uuid")]
+use crate::claims::JtiUuid;
+use crate::error::Error;
+use crate::jws::{Algorithm, Encoder, Header};
+use crate::redact::Redacted;
+
+/// Sign tokens with a fixed key, header and lifetime.
+///
+/// ```rust
+/// use std::time::Duration;
+/// use jwts::{Claims, Issuer};
+/// use jwts::jws::alg::HS256;
+///
+/// let issuer = Issuer::<HS256>::new(b"secret", Duration::from_secs(3600)).with_iss("https://auth.example.com");
+/// let claims = Claims {
+///     sub: Some("sea".to_owned()),
+///     ..Default::default()
+/// };
+/// let token = issuer.issue(claims).unwrap();
+/// ```
+pub struct Issuer<'a, A: Algorithm> {
+    key: &'a A::SignKey,
+    header: Header,
+    iss: Option<String>,
+    aud: Option<String>,
+    #[cfg(feature = "uuid")]
+    jti: Option<JtiUuid>,
+    lifetime: Duration,
+    encoder: Encoder,
+    _alg: PhantomData<A>,
+}
+
+/// Omits the key.
+impl<A: Algorithm> Debug for Issuer<'_, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Issuer")
+            .field("alg", &A::name())
+            .field("key", &Redacted)
+            .field("header", &self.header)
+            .field("iss", &self.iss)
+            .field("aud", &self.aud)
+            .field("lifetime", &self.lifetime)
+            .finish()
+    }
+}
+
+impl<'a, A: Algorithm> Issuer<'a, A> {
+    /// Create an `Issuer` signing with the key, the tokens expire after the lifetime.
+    #[inline]
+    pub fn new(key: &'a A::SignKey, lifetime: Duration) -> Self {
+        Issuer {
+            key,
+            header: Header::default(),
+            iss: None,
+            aud: None,
+            #[cfg(feature = "uuid")]
+            jti: None,
+            lifetime,
+            encoder: Encoder::new(),
+            _alg: PhantomData,
+        }
+    }
+
+    /// Sign with the header, `alg` is set by the algorithm.
+    #[inline]
+    pub fn with_header(self, header: Header) -> Self {
+        Issuer { header, ..self }
+    }
+
+    /// The `iss` claim of the tokens without one.
+    #[inline]
+    pub fn with_iss(self, iss: impl Into<String>) -> Self {
+        Issuer { iss: Some(iss.into()), ..self }
+    }
+
+    /// The `aud` claim of the tokens without one.
+    #[inline]
+    pub fn with_aud(self, aud: impl Into<String>) -> Self {
+        Issuer { aud: Some(aud.into()), ..self }
+    }
+
+    /// Stamp a fresh UUID of the version as the `jti` claim of the tokens without one, as
+    /// required for replay protection, requires the feature `uuid`.
+    #[cfg(feature = "uuid")]
+    #[inline]
+    pub fn with_random_jti(self, version: JtiUuid) -> Self {
+        Issuer { jti: Some(version), ..self }
+    }
+
+    /// Encode with the `Encoder`.
+    #[inline]
+    pub fn with_encoder(self, encoder: Encoder) -> Self {
+        Issuer { encoder, ..self }
+    }
+
+    /// The lifetime of the tokens.
+    #[inline]
+    pub fn lifetime(&self) -> Duration {
+        self.lifetime
+    }
+
+    /// Sign a token of the claims, `iat` and `exp` are set from the current time and the lifetime.
+    pub fn issue(&self, claims: Claims) -> Result<String, Error> {
+        let claims = Claims {
+            iss: claims.iss.or_else(|| self.iss.clone()),
+            aud: claims.aud.or_else(|| self.aud.clone()),
+            #[cfg(feature = "uuid")]
+            jti: claims.jti.or_else(|| self.jti.map(JtiUuid::generate)),
+            ..claims
+        };
+        let claims = claims.issued_now().expired_in(self.lifetime);
+        self.encoder.encode::<A>(self.header.clone(), &claims, self.key)
+    }
+}
+
+impl<A: Algorithm> Clone for Issuer<'_, A> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Issuer {
+            key: self.key,
+            header: self.header.clone(),
+            iss: self.iss.clone(),
+            aud: self.aud.clone(),
+            #[cfg(feature = "uuid")]
+            jti: self.jti,
+            lifetime: self.lifetime,
+            encoder: self.encoder.clone(),
+            _alg: PhantomData,
+        }
+    }
+}
@@ -0,0 +1,220 @@
+//! JSON Web Key, see https://tools.ietf.org/html/rfc7517
+
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Formatter};
+
+use serde_derive::{Deserialize, Serialize};
+use serde_json as json;
+use serde_json::{Map, Value};
+
+use crate::{bs64, crypto, der};
+use crate::error::Error;
+use crate::jws::Alg;
+use crate::redact::{mask, Redacted, SECRET_JWK_PARAMS};
+
+/// A JSON Web Key.
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Jwk {
+    /// Key type, e.g. "RSA", "EC", "OKP" or "oct"
+    pub kty: String,
+    /// Public key use
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#use: Option<String>,
+    /// Key operations
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_ops: Option<Vec<String>>,
+    /// Algorithm intended for use with the key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alg: Option<Alg>,
+    /// Key ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kid: Option<String>,
+    /// X.509 URL
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x5u: Option<String>,
+    /// X.509 certificate chain
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x5c: Option<Vec<String>>,
+    /// X.509 certificate SHA-1 thumbprint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x5t: Option<String>,
+    /// X.509 certificate SHA-256 thumbprint
+    #[serde(rename = "x5t#S256", skip_serializing_if = "Option::is_none")]
+    pub x5t_s256: Option<String>,
+    /// Curve of an "EC" or "OKP" key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crv: Option<String>,
+    /// X coordinate of an "EC" key, or public key of an "OKP" key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<String>,
+    /// Y coordinate of an "EC" key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<String>,
+    /// Modulus of an "RSA" key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<String>,
+    /// Exponent of an "RSA" key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub e: Option<String>,
+    /// Key value of an "oct" key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub k: Option<String>,
+    /// Additional parameters, including the private key parameters
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Jwk {
+    /// Create a new `Jwk` of the key type, without any other parameters.
+    #[inline]
+    pub fn new(kty: impl Into<String>) -> Self {
+        Jwk {
+            kty: kty.into(),
+            r#use: None,
+            key_ops: None,
+            alg: None,
+            kid: None,
+            x5u: None,
+            x5c: None,
+            x5t: None,
+            x5t_s256: None,
+            crv: None,
+            x: None,
+            y: None,
+            n: None,
+            e: None,
+            k: None,
+            extra: Map::new(),
+        }
+    }
+
+    /// An "RSA" public key from the big-endian modulus and exponent.
+    pub fn rsa(n: impl AsRef<[u8]>, e: impl AsRef<[u8]>) -> Self {
+        Jwk {
+            n: Some(bs64::from_bytes(n)),
+            e: Some(bs64::from_bytes(e)),
+            ..Jwk::new("RSA")
+        }
+    }
+
+    /// An "EC" public key from the curve name and the coordinates.
+    pub fn ec(crv: impl Into<String>, x: impl AsRef<[u8]>, y: impl AsRef<[u8]>) -> Self {
+        Jwk {
+            crv: Some(crv.into()),
+            x: Some(bs64::from_bytes(x)),
+            y: Some(bs64::from_bytes(y)),
+            ..Jwk::new("EC")
+        }
+    }
+
+    /// An "OKP" public key from the curve name and the public key bytes.
+    pub fn okp(crv: impl Into<String>, x: impl AsRef<[u8]>) -> Self {
+        Jwk {
+            crv: Some(crv.into()),
+            x: Some(bs64::from_bytes(x)),
+            ..Jwk::new("OKP")
+        }
+    }
+
+    /// An "oct" symmetric key.
+    pub fn oct(k: impl AsRef<[u8]>) -> Self {
+        Jwk {
+            k: Some(bs64::from_bytes(k)),
+            ..Jwk::new("oct")
+        }
+    }
+
+    /// Encode the public key in the form the builtin algorithms take as `VerifyKey`:
+    /// DER encoded RSAPublicKey for "RSA", uncompressed point for "EC",
+    /// public key bytes for "OKP" and the key value for "oct".
+    pub fn to_verify_key(&self) -> Result<Vec<u8>, Error> {
+        let param = |x: &Option<String>| {
+            x.as_deref()
+                .ok_or(Error::InvalidKey("missing key parameter"))
+                .and_then(|x| bs64::to_bytes(x).map_err(|_| Error::InvalidKey("invalid key parameter")))
+        };
+        match self.kty.as_str() {
+            "RSA" => {
+                let n = der::write_uint(&param(&self.n)?);
+                let e = der::write_uint(&param(&self.e)?);
+                Ok(der::write(der::SEQUENCE, &[n, e].concat()))
+            }
+            "EC" => Ok([&[0x04], &param(&self.x)?[..], &param(&self.y)?[..]].concat()),
+            "OKP" => param(&self.x),
+            "oct" => param(&self.k),
+            _ => Err(Error::InvalidKey("unsupported key type")),
+        }
+    }
+
+    /// JWK SHA-256 thumbprint, see https://tools.ietf.org/html/rfc7638
+    pub fn thumbprint(&self) -> Result<String, Error> {
+        let required: &[(&str, &Option<String>)] = match self.kty.as_str() {
+            "RSA" => &[("e", &self.e), ("n", &self.n)],
+            "EC" => &[("crv", &self.crv), ("x", &self.x), ("y", &self.y)],
+            "OKP" => &[("crv", &self.crv), ("x", &self.x)],
+            "oct" => &[("k", &self.k)],
+            _ => return Err(Error::InvalidKey("unsupported key type")),
+        };
+        let mut members = BTreeMap::from([("kty", self.kty.as_str())]);
+        for (name, value) in required {
+            let value = value.as_deref().ok_or(Error::InvalidKey("missing key parameter"))?;
+            members.insert(name, value);
+        }
+        let members = json::to_vec(&members).map_err(|_| Error::InvalidKey("invalid key parameter"))?;
+        Ok(bs64::from_bytes(crypto::sha256(&members)))
+    }
+}
+
+/// Masks the secret and private key parameters, see `redact::Reveal` to print them.
+impl Debug for Jwk {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Jwk")
+            .field("kty", &self.kty)
+            .field("use", &self.r#use)
+            .field("key_ops", &self.key_ops)
+            .field("alg", &self.alg)
+            .field("kid", &self.kid)
+            .field("x5u", &self.x5u)
+            .field("x5c", &self.x5c)
+            .field("x5t", &self.x5t)
+            .field("x5t_s256", &self.x5t_s256)
+            .field("crv", &self.crv)
+            .field("x", &self.x)
+            .field("y", &self.y)
+            .field("n", &self.n)
+            .field("e", &self.e)
+            .field("k", &mask(&self.k))
+            .field("extra", &ExtraParams(&self.extra))
+            .finish()
+    }
+}
+
+struct ExtraParams<'a>(&'a Map<String, Value>);
+
+impl Debug for ExtraParams<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut map = f.debug_map();
+        for (name, value) in self.0 {
+            if SECRET_JWK_PARAMS.contains(&name.as_str()) {
+                map.entry(name, &Redacted);
+            } else {
+                map.entry(name, value);
+            }
+        }
+        map.finish()
+    }
+}
+
+/// A JSON Web Key Set.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct JwkSet {
+    pub keys: Vec<Jwk>,
+}
+
+impl JwkSet {
+    /// Find the key with the specific key ID.
+    #[inline]
+    pub fn find(&self, kid: &str) -> Option<&Jwk> {
+        self.keys.iter().find(|x| x.kid.as_deref() == Some(kid))
+    }
+}
@@ -1,11 +1,24 @@
 //! Algorithm
 
-use ring::hmac;
-use ring::rand::SystemRandom;
-use ring::signature;
-use ring::signature::{EcdsaKeyPair, EcdsaSigningAlgorithm, Ed25519KeyPair, RsaEncoding, RsaKeyPair, UnparsedPublicKey, VerificationAlgorithm};
-
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[cfg(any(feature = "alg-hs", feature = "alg-rs", feature = "alg-es", feature = "alg-eddsa"))]
+use crate::crypto;
+#[cfg(feature = "alg-es")]
+use crate::crypto::Curve;
+#[cfg(feature = "alg-eddsa")]
+use crate::crypto::Ed25519KeyPair;
+#[cfg(any(feature = "alg-hs", feature = "alg-rs"))]
+use crate::crypto::Hash;
+#[cfg(feature = "alg-rs")]
+use crate::crypto::{RsaKeyPair, RsaPadding};
+use crate::audit::Finding;
 use crate::error::Error;
+use crate::jwk::Jwk;
+#[cfg(feature = "alg-rs")]
+use crate::key::RsaKeySize;
 
 pub trait Algorithm {
     type SignKey: ?Sized;
@@ -19,44 +32,247 @@ pub trait Algorithm {
 
     /// Verify the signature with the key.
     fn verify(data: impl AsRef<[u8]>, sig: impl AsRef<[u8]>, key: &Self::VerifyKey) -> Result<(), Error>;
+
+    /// Identifier of the signing key, used to populate the `kid` header on encode.
+    #[inline]
+    fn key_id(_key: &Self::SignKey) -> Option<String> {
+        None
+    }
+
+    /// The public JWK of the signing key, without `kid`, `None` for symmetric keys.
+    #[inline]
+    fn public_jwk(_key: &Self::SignKey) -> Option<Jwk> {
+        None
+    }
+
+    /// The weakness of the verification key, see `Verifier::audit`.
+    #[inline]
+    fn audit_key(_key: &Self::VerifyKey) -> Option<Finding> {
+        None
+    }
+}
+
+/// Value of the `alg` header parameter, see https://tools.ietf.org/html/rfc7518#section-3.1
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Alg {
+    /// HMAC using SHA-256
+    HS256,
+    /// HMAC using SHA-384
+    HS384,
+    /// HMAC using SHA-512
+    HS512,
+    /// RSASSA-PKCS1-v1_5 using SHA-256
+    RS256,
+    /// RSASSA-PKCS1-v1_5 using SHA-384
+    RS384,
+    /// RSASSA-PKCS1-v1_5 using SHA-512
+    RS512,
+    /// ECDSA using P-256 and SHA-256
+    ES256,
+    /// ECDSA using P-384 and SHA-384
+    ES384,
+    /// ECDSA using P-521 and SHA-512
+    ES512,
+    /// RSASSA-PSS using SHA-256 and MGF1 with SHA-256
+    PS256,
+    /// RSASSA-PSS using SHA-384 and MGF1 with SHA-384
+    PS384,
+    /// RSASSA-PSS using SHA-512 and MGF1 with SHA-512
+    PS512,
+    /// EdDSA signature algorithms, see https://tools.ietf.org/html/rfc8037
+    EdDSA,
+    /// EdDSA using the Ed25519 parameter set
+    Ed25519,
+    /// No digital signature or MAC performed
+    None,
+    /// Unregistered or private algorithm name
+    Other(String),
+}
+
+impl Alg {
+    /// Name of the algorithm as it appears in the header.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Alg::HS256 => "HS256",
+            Alg::HS384 => "HS384",
+            Alg::HS512 => "HS512",
+            Alg::RS256 => "RS256",
+            Alg::RS384 => "RS384",
+            Alg::RS512 => "RS512",
+            Alg::ES256 => "ES256",
+            Alg::ES384 => "ES384",
+            Alg::ES512 => "ES512",
+            Alg::PS256 => "PS256",
+            Alg::PS384 => "PS384",
+            Alg::PS512 => "PS512",
+            Alg::EdDSA => "EdDSA",
+            Alg::Ed25519 => "Ed25519",
+            Alg::None => "none",
+            Alg::Other(name) => name,
+        }
+    }
+
+    /// The JWK key type of the keys of the algorithm, `oct` for HS, `RSA` for RS and PS, `EC`
+    /// for ES and `OKP` for EdDSA, `None` for `none` and unregistered algorithms.
+    pub fn kty(&self) -> Option<&'static str> {
+        match self {
+            Alg::HS256 | Alg::HS384 | Alg::HS512 => Some("oct"),
+            Alg::RS256 | Alg::RS384 | Alg::RS512 | Alg::PS256 | Alg::PS384 | Alg::PS512 => Some("RSA"),
+            Alg::ES256 | Alg::ES384 | Alg::ES512 => Some("EC"),
+            Alg::EdDSA | Alg::Ed25519 => Some("OKP"),
+            Alg::None | Alg::Other(_) => None,
+        }
+    }
+
+    /// Verify the signature with the builtin algorithm, the key as its `VerifyKey`, for algorithms
+    /// chosen at runtime. `EdDSA` is verified as `Ed25519`.
+    pub fn verify(&self, data: impl AsRef<[u8]>, sig: impl AsRef<[u8]>, key: &[u8]) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "alg-hs")]
+            Alg::HS256 => HS256::verify(data, sig, key),
+            #[cfg(feature = "alg-hs")]
+            Alg::HS384 => HS384::verify(data, sig, key),
+            #[cfg(feature = "alg-hs")]
+            Alg::HS512 => HS512::verify(data, sig, key),
+            #[cfg(feature = "alg-rs")]
+            Alg::RS256 => RS256::verify(data, sig, key),
+            #[cfg(feature = "alg-rs")]
+            Alg::RS384 => RS384::verify(data, sig, key),
+            #[cfg(feature = "alg-rs")]
+            Alg::RS512 => RS512::verify(data, sig, key),
+            #[cfg(feature = "alg-es")]
+            Alg::ES256 => ES256::verify(data, sig, key),
+            #[cfg(feature = "alg-es")]
+            Alg::ES384 => ES384::verify(data, sig, key),
+            #[cfg(feature = "alg-rs")]
+            Alg::PS256 => PS256::verify(data, sig, key),
+            #[cfg(feature = "alg-rs")]
+            Alg::PS384 => PS384::verify(data, sig, key),
+            #[cfg(feature = "alg-rs")]
+            Alg::PS512 => PS512::verify(data, sig, key),
+            #[cfg(feature = "alg-eddsa")]
+            Alg::EdDSA | Alg::Ed25519 => Ed25519::verify(data, sig, key),
+            _ => {
+                let _ = (data, sig, key);
+                Err(Error::Rejected("unsupported algorithm"))
+            }
+        }
+    }
+
+    /// Verify the signature with the builtin RS or PS algorithm by an RSA key with a modulus of
+    /// the accepted size, see `RsaKeySize`.
+    #[cfg(feature = "alg-rs")]
+    pub fn verify_rsa(&self, data: impl AsRef<[u8]>, sig: impl AsRef<[u8]>, key: &[u8], size: RsaKeySize) -> Result<(), Error> {
+        let (padding, hash) = self.rsa_params()?;
+        crypto::rsa_verify_sized(padding, hash, key, data.as_ref(), sig.as_ref(), size)
+    }
+
+    /// The padding and hash of an RS or PS algorithm.
+    #[cfg(feature = "alg-rs")]
+    pub(crate) fn rsa_params(&self) -> Result<(RsaPadding, Hash), Error> {
+        match self {
+            Alg::RS256 => Ok((RsaPadding::Pkcs1, Hash::Sha256)),
+            Alg::RS384 => Ok((RsaPadding::Pkcs1, Hash::Sha384)),
+            Alg::RS512 => Ok((RsaPadding::Pkcs1, Hash::Sha512)),
+            Alg::PS256 => Ok((RsaPadding::Pss, Hash::Sha256)),
+            Alg::PS384 => Ok((RsaPadding::Pss, Hash::Sha384)),
+            Alg::PS512 => Ok((RsaPadding::Pss, Hash::Sha512)),
+            _ => Err(Error::Rejected("not an RSA algorithm")),
+        }
+    }
+}
+
+impl From<&str> for Alg {
+    fn from(name: &str) -> Self {
+        match name {
+            "HS256" => Alg::HS256,
+            "HS384" => Alg::HS384,
+            "HS512" => Alg::HS512,
+            "RS256" => Alg::RS256,
+            "RS384" => Alg::RS384,
+            "RS512" => Alg::RS512,
+            "ES256" => Alg::ES256,
+            "ES384" => Alg::ES384,
+            "ES512" => Alg::ES512,
+            "PS256" => Alg::PS256,
+            "PS384" => Alg::PS384,
+            "PS512" => Alg::PS512,
+            "EdDSA" => Alg::EdDSA,
+            "Ed25519" => Alg::Ed25519,
+            "none" => Alg::None,
+            _ => Alg::Other(name.to_owned()),
+        }
+    }
+}
+
+impl Display for Alg {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for Alg {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Alg {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(Alg::from(name.as_str()))
+    }
 }
 
 /// HMAC using SHA-256
+#[cfg(feature = "alg-hs")]
 pub struct HS256;
 
 /// HMAC using SHA-384
+#[cfg(feature = "alg-hs")]
 pub struct HS384;
 
 /// HMAC using SHA-512
+#[cfg(feature = "alg-hs")]
 pub struct HS512;
 
 /// RSASSA-PKCS1-v1_5 using SHA-256
+#[cfg(feature = "alg-rs")]
 pub struct RS256;
 
 /// RSASSA-PKCS1-v1_5 using SHA-384
+#[cfg(feature = "alg-rs")]
 pub struct RS384;
 
 /// RSASSA-PKCS1-v1_5 using SHA-512
+#[cfg(feature = "alg-rs")]
 pub struct RS512;
 
 /// ECDSA using P-256 and SHA-256
+#[cfg(feature = "alg-es")]
 pub struct ES256;
 
 /// ECDSA using P-384 and SHA-384
+#[cfg(feature = "alg-es")]
 pub struct ES384;
 
 /// RSASSA-PSS using SHA-256 and MGF1 with SHA-256
+#[cfg(feature = "alg-rs")]
 pub struct PS256;
 
 /// RSASSA-PSS using SHA-384 and MGF1 with SHA-384
+#[cfg(feature = "alg-rs")]
 pub struct PS384;
 
 /// RSASSA-PSS using SHA-512 and MGF1 with SHA-512
+#[cfg(feature = "alg-rs")]
 pub struct PS512;
 
 /// Ed25519 using SHA-512
+#[cfg(feature = "alg-eddsa")]
 pub struct Ed25519;
 
+#[cfg(feature = "alg-hs")]
 impl Algorithm for HS256 {
     type SignKey = [u8];
     type VerifyKey = [u8];
@@ -66,14 +282,20 @@ impl Algorithm for HS256 {
     }
 
     fn sign(data: impl AsRef<[u8]>, key: &Self::SignKey) -> Result<Vec<u8>, Error> {
-        sign_hmac(data, key, hmac::HMAC_SHA256)
+        Ok(crypto::hmac(Hash::Sha256, key, data.as_ref()))
     }
 
     fn verify(data: impl AsRef<[u8]>, sig: impl AsRef<[u8]>, key: &Self::VerifyKey) -> Result<(), Error> {
         verify_symmetric(sig, Self::sign(data, key)?)
     }
+
+    #[inline]
+    fn audit_key(key: &Self::VerifyKey) -> Option<Finding> {
+        audit_hmac_key(Self::name(), key, 32)
+    }
 }
 
+#[cfg(feature = "alg-hs")]
 impl Algorithm for HS384 {
     type SignKey = [u8];
     type VerifyKey = [u8];
@@ -83,14 +305,20 @@ impl Algorithm for HS384 {
     }
 
     fn sign(data: impl AsRef<[u8]>, key: &Self::SignKey) -> Result<Vec<u8>, Error> {
-        sign_hmac(data, key, hmac::HMAC_SHA384)
+        Ok(crypto::hmac(Hash::Sha384, key, data.as_ref()))
     }
 
     fn verify(data: impl AsRef<[u8]>, sig: impl AsRef<[u8]>, key: &Self::VerifyKey) -> Result<(), Error> {
         verify_symmetric(sig, Self::sign(data, key)?)
     }
+
+    #[inline]
+    fn audit_key(key: &Self::VerifyKey) -> Option<Finding> {
+        audit_hmac_key(Self::name(), key, 48)
+    }
 }
 
+#[cfg(feature = "alg-hs")]
 impl Algorithm for HS512 {
     type SignKey = [u8];
     type VerifyKey = [u8];
@@ -100,14 +328,20 @@ impl Algorithm for HS512 {
     }
 
     fn sign(data: impl AsRef<[u8]>, key: &Self::SignKey) -> Result<Vec<u8>, Error> {
-        sign_hmac(data, key, hmac::HMAC_SHA512)
+        Ok(crypto::hmac(Hash::Sha512, key, data.as_ref()))
     }
 
     fn verify(data: impl AsRef<[u8]>, sig: impl AsRef<[u8]>, key: &Self::VerifyKey) -> Result<(), Error> {
         verify_symmetric(sig, Self::sign(data, key)?)
     }
+
+    #[inline]
+    fn audit_key(key: &Self::VerifyKey) -> Option<Finding> {
+        audit_hmac_key(Self::name(), key, 64)
+    }
 }
 
+#[cfg(feature = "alg-rs")]
 impl Algorithm for RS256 {
     type SignKey = RsaKeyPair;
     type VerifyKey = [u8];
@@ -117,14 +351,23 @@ impl Algorithm for RS256 {
     }
 
     fn sign(data: impl AsRef<[u8]>, key: &Self::SignKey) -> Result<Vec<u8>, Error> {
-        sign_rsa(data, key, &signature::RSA_PKCS1_SHA256)
+        crypto::rsa_sign(key, RsaPadding::Pkcs1, Hash::Sha256, data.as_ref())
     }
 
     fn verify(data: impl AsRef<[u8]>, sig: impl AsRef<[u8]>, key: &Self::VerifyKey) -> Result<(), Error> {
-        verify_asymmetric(data, sig, key, &signature::RSA_PKCS1_2048_8192_SHA256)
+        crypto::rsa_verify_sized(RsaPadding::Pkcs1, Hash::Sha256, key, data.as_ref(), sig.as_ref(), RsaKeySize::DEFAULT)
+    }
+
+    fn key_id(key: &Self::SignKey) -> Option<String> {
+        Self::public_jwk(key)?.thumbprint().ok()
+    }
+
+    fn public_jwk(key: &Self::SignKey) -> Option<Jwk> {
+        rsa_jwk(key)
     }
 }
 
+#[cfg(feature = "alg-rs")]
 impl Algorithm for RS384 {
     type SignKey = RsaKeyPair;
     type VerifyKey = [u8];
@@ -134,14 +377,23 @@ impl Algorithm for RS384 {
     }
 
     fn sign(data: impl AsRef<[u8]>, key: &Self::SignKey) -> Result<Vec<u8>, Error> {
-        sign_rsa(data, key, &signature::RSA_PKCS1_SHA384)
+        crypto::rsa_sign(key, RsaPadding::Pkcs1, Hash::Sha384, data.as_ref())
     }
 
     fn verify(data: impl AsRef<[u8]>, sig: impl AsRef<[u8]>, key: &Self::VerifyKey) -> Result<(), Error> {
-        verify_asymmetric(data, sig, key, &signature::RSA_PKCS1_2048_8192_SHA384)
+        crypto::rsa_verify_sized(RsaPadding::Pkcs1, Hash::Sha384, key, data.as_ref(), sig.as_ref(), RsaKeySize::DEFAULT)
+    }
+
+    fn key_id(key: &Self::SignKey) -> Option<String> {
+        Self::public_jwk(key)?.thumbprint().ok()
+    }
+
+    fn public_jwk(key: &Self::SignKey) -> Option<Jwk> {
+        rsa_jwk(key)
     }
 }
 
+#[cfg(feature = "alg-rs")]
 impl Algorithm for RS512 {
     type SignKey = RsaKeyPair;
     type VerifyKey = [u8];
@@ -151,14 +403,23 @@ impl Algorithm for RS512 {
     }
 
     fn sign(data: impl AsRef<[u8]>, key: &Self::SignKey) -> Result<Vec<u8>, Error> {
-        sign_rsa(data, key, &signature::RSA_PKCS1_SHA512)
+        crypto::rsa_sign(key, RsaPadding::Pkcs1, Hash::Sha512, data.as_ref())
     }
 
     fn verify(data: impl AsRef<[u8]>, sig: impl AsRef<[u8]>, key: &Self::VerifyKey) -> Result<(), Error> {
-        verify_asymmetric(data, sig, key, &signature::RSA_PKCS1_2048_8192_SHA512)
+        crypto::rsa_verify_sized(RsaPadding::Pkcs1, Hash::Sha512, key, data.as_ref(), sig.as_ref(), RsaKeySize::DEFAULT)
+    }
+
+    fn key_id(key: &Self::SignKey) -> Option<String> {
+        Self::public_jwk(key)?.thumbprint().ok()
+    }
+
+    fn public_jwk(key: &Self::SignKey) -> Option<Jwk> {
+        rsa_jwk(key)
     }
 }
 
+#[cfg(feature = "alg-es")]
 impl Algorithm for ES256 {
     type SignKey = [u8];
     type VerifyKey = [u8];
@@ -168,14 +429,23 @@ impl Algorithm for ES256 {
     }
 
     fn sign(data: impl AsRef<[u8]>, key: &Self::SignKey) -> Result<Vec<u8>, Error> {
-        sign_ecdsa(data, key, &signature::ECDSA_P256_SHA256_FIXED_SIGNING)
+        crypto::ecdsa_sign(Curve::P256, key, data.as_ref())
     }
 
     fn verify(data: impl AsRef<[u8]>, sig: impl AsRef<[u8]>, key: &Self::VerifyKey) -> Result<(), Error> {
-        verify_asymmetric(data, sig, key, &signature::ECDSA_P256_SHA256_FIXED)
+        crypto::ecdsa_verify(Curve::P256, key, data.as_ref(), sig.as_ref())
+    }
+
+    fn key_id(key: &Self::SignKey) -> Option<String> {
+        Self::public_jwk(key)?.thumbprint().ok()
+    }
+
+    fn public_jwk(key: &Self::SignKey) -> Option<Jwk> {
+        ecdsa_jwk(key, Curve::P256)
     }
 }
 
+#[cfg(feature = "alg-es")]
 impl Algorithm for ES384 {
     type SignKey = [u8];
     type VerifyKey = [u8];
@@ -185,14 +455,23 @@ impl Algorithm for ES384 {
     }
 
     fn sign(data: impl AsRef<[u8]>, key: &Self::SignKey) -> Result<Vec<u8>, Error> {
-        sign_ecdsa(data, key, &signature::ECDSA_P384_SHA384_FIXED_SIGNING)
+        crypto::ecdsa_sign(Curve::P384, key, data.as_ref())
     }
 
     fn verify(data: impl AsRef<[u8]>, sig: impl AsRef<[u8]>, key: &Self::VerifyKey) -> Result<(), Error> {
-        verify_asymmetric(data, sig, key, &signature::ECDSA_P384_SHA384_FIXED)
+        crypto::ecdsa_verify(Curve::P384, key, data.as_ref(), sig.as_ref())
+    }
+
+    fn key_id(key: &Self::SignKey) -> Option<String> {
+        Self::public_jwk(key)?.thumbprint().ok()
+    }
+
+    fn public_jwk(key: &Self::SignKey) -> Option<Jwk> {
+        ecdsa_jwk(key, Curve::P384)
     }
 }
 
+#[cfg(feature = "alg-rs")]
 impl Algorithm for PS256 {
     type SignKey = RsaKeyPair;
     type VerifyKey = [u8];
@@ -202,14 +481,23 @@ impl Algorithm for PS256 {
     }
 
     fn sign(data: impl AsRef<[u8]>, key: &Self::SignKey) -> Result<Vec<u8>, Error> {
-        sign_rsa(data, key, &signature::RSA_PSS_SHA256)
+        crypto::rsa_sign(key, RsaPadding::Pss, Hash::Sha256, data.as_ref())
     }
 
     fn verify(data: impl AsRef<[u8]>, sig: impl AsRef<[u8]>, key: &Self::VerifyKey) -> Result<(), Error> {
-        verify_asymmetric(data, sig, key, &signature::RSA_PSS_2048_8192_SHA256)
+        crypto::rsa_verify_sized(RsaPadding::Pss, Hash::Sha256, key, data.as_ref(), sig.as_ref(), RsaKeySize::DEFAULT)
+    }
+
+    fn key_id(key: &Self::SignKey) -> Option<String> {
+        Self::public_jwk(key)?.thumbprint().ok()
+    }
+
+    fn public_jwk(key: &Self::SignKey) -> Option<Jwk> {
+        rsa_jwk(key)
     }
 }
 
+#[cfg(feature = "alg-rs")]
 impl Algorithm for PS384 {
     type SignKey = RsaKeyPair;
     type VerifyKey = [u8];
@@ -219,14 +507,23 @@ impl Algorithm for PS384 {
     }
 
     fn sign(data: impl AsRef<[u8]>, key: &Self::SignKey) -> Result<Vec<u8>, Error> {
-        sign_rsa(data, key, &signature::RSA_PSS_SHA384)
+        crypto::rsa_sign(key, RsaPadding::Pss, Hash::Sha384, data.as_ref())
     }
 
     fn verify(data: impl AsRef<[u8]>, sig: impl AsRef<[u8]>, key: &Self::VerifyKey) -> Result<(), Error> {
-        verify_asymmetric(data, sig, key, &signature::RSA_PSS_2048_8192_SHA384)
+        crypto::rsa_verify_sized(RsaPadding::Pss, Hash::Sha384, key, data.as_ref(), sig.as_ref(), RsaKeySize::DEFAULT)
+    }
+
+    fn key_id(key: &Self::SignKey) -> Option<String> {
+        Self::public_jwk(key)?.thumbprint().ok()
+    }
+
+    fn public_jwk(key: &Self::SignKey) -> Option<Jwk> {
+        rsa_jwk(key)
     }
 }
 
+#[cfg(feature = "alg-rs")]
 impl Algorithm for PS512 {
     type SignKey = RsaKeyPair;
     type VerifyKey = [u8];
@@ -236,14 +533,23 @@ impl Algorithm for PS512 {
     }
 
     fn sign(data: impl AsRef<[u8]>, key: &Self::SignKey) -> Result<Vec<u8>, Error> {
-        sign_rsa(data, key, &signature::RSA_PSS_SHA512)
+        crypto::rsa_sign(key, RsaPadding::Pss, Hash::Sha512, data.as_ref())
     }
 
     fn verify(data: impl AsRef<[u8]>, sig: impl AsRef<[u8]>, key: &Self::VerifyKey) -> Result<(), Error> {
-        verify_asymmetric(data, sig, key, &signature::RSA_PSS_2048_8192_SHA512)
+        crypto::rsa_verify_sized(RsaPadding::Pss, Hash::Sha512, key, data.as_ref(), sig.as_ref(), RsaKeySize::DEFAULT)
+    }
+
+    fn key_id(key: &Self::SignKey) -> Option<String> {
+        Self::public_jwk(key)?.thumbprint().ok()
+    }
+
+    fn public_jwk(key: &Self::SignKey) -> Option<Jwk> {
+        rsa_jwk(key)
     }
 }
 
+#[cfg(feature = "alg-eddsa")]
 impl Algorithm for Ed25519 {
     type SignKey = Ed25519KeyPair;
     type VerifyKey = [u8];
@@ -253,46 +559,56 @@ impl Algorithm for Ed25519 {
     }
 
     fn sign(data: impl AsRef<[u8]>, key: &Self::SignKey) -> Result<Vec<u8>, Error> {
-        sign_eddsa(data, key)
+        Ok(crypto::ed25519_sign(key, data.as_ref()))
     }
 
     fn verify(data: impl AsRef<[u8]>, sig: impl AsRef<[u8]>, key: &Self::VerifyKey) -> Result<(), Error> {
-        verify_asymmetric(data, sig, key, &signature::ED25519)
+        crypto::ed25519_verify(key, data.as_ref(), sig.as_ref())
     }
-}
 
-fn sign_hmac(data: impl AsRef<[u8]>, key: impl AsRef<[u8]>, alg: hmac::Algorithm) -> Result<Vec<u8>, Error> {
-    let key = hmac::Key::new(alg, key.as_ref());
-    let tag = hmac::sign(&key, data.as_ref());
-    Ok(tag.as_ref().to_owned())
+    fn key_id(key: &Self::SignKey) -> Option<String> {
+        Self::public_jwk(key)?.thumbprint().ok()
+    }
+
+    fn public_jwk(key: &Self::SignKey) -> Option<Jwk> {
+        eddsa_jwk(key)
+    }
 }
 
-fn sign_rsa(data: impl AsRef<[u8]>, key: &RsaKeyPair, alg: &'static impl RsaEncoding) -> Result<Vec<u8>, Error> {
-    let rng = SystemRandom::new();
-    let mut sig = vec![0; key.public_modulus_len()];
-    key.sign(alg, &rng, data.as_ref(), &mut sig)?;
-    Ok(sig)
+#[cfg(feature = "alg-rs")]
+fn rsa_jwk(key: &RsaKeyPair) -> Option<Jwk> {
+    let (n, e) = crypto::rsa_public_components(key);
+    Some(Jwk::rsa(n, e))
 }
 
-fn sign_ecdsa(data: impl AsRef<[u8]>, key: impl AsRef<[u8]>, alg: &'static EcdsaSigningAlgorithm) -> Result<Vec<u8>, Error> {
-    let key_pair = EcdsaKeyPair::from_pkcs8(alg, key.as_ref())?;
-    let rng = SystemRandom::new();
-    let sig = key_pair.sign(&rng, data.as_ref())?;
-    Ok(sig.as_ref().to_owned())
+#[cfg(feature = "alg-es")]
+fn ecdsa_jwk(key: &[u8], curve: Curve) -> Option<Jwk> {
+    let point = crypto::ecdsa_public_point(curve, key).ok()?;
+    // uncompressed point: 0x04 || x || y
+    let point = &point[1..];
+    let (x, y) = point.split_at(point.len() / 2);
+    let crv = match curve {
+        Curve::P256 => "P-256",
+        Curve::P384 => "P-384",
+    };
+    Some(Jwk::ec(crv, x, y))
 }
 
+#[cfg(feature = "alg-eddsa")]
 #[inline]
-fn sign_eddsa(data: impl AsRef<[u8]>, key: &Ed25519KeyPair) -> Result<Vec<u8>, Error> {
-    Ok(key.sign(data.as_ref()).as_ref().to_owned())
+fn eddsa_jwk(key: &Ed25519KeyPair) -> Option<Jwk> {
+    Some(Jwk::okp("Ed25519", crypto::ed25519_public_key(key)))
 }
 
+#[cfg(feature = "alg-hs")]
 #[inline]
 fn verify_symmetric(sig: impl AsRef<[u8]>, expect: impl AsRef<[u8]>) -> Result<(), Error> {
     (sig.as_ref() == expect.as_ref()).then_some(()).ok_or(Error::InvalidSignature)
 }
 
-fn verify_asymmetric(msg: impl AsRef<[u8]>, sig: impl AsRef<[u8]>, key: impl AsRef<[u8]>, alg: &'static impl VerificationAlgorithm) -> Result<(), Error> {
-    let key = UnparsedPublicKey::new(alg, key.as_ref());
-    key.verify(msg.as_ref(), sig.as_ref())
-        .map_err(|_| Error::InvalidSignature)
+/// A key shorter than the hash output of the algorithm, see https://tools.ietf.org/html/rfc7518#section-3.2
+#[cfg(feature = "alg-hs")]
+#[inline]
+fn audit_hmac_key(alg: &'static str, key: &[u8], min: usize) -> Option<Finding> {
+    (key.len() < min).then_some(Finding::ShortHmacKey { alg, len: key.len(), min })
 }
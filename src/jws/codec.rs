@@ -0,0 +1,203 @@
+//! Payload codecs, selected by the `cty` header
+//!
+//! The payload is JSON by default. With the feature `cbor` or `msgpack` it can be CBOR or
+//! MessagePack instead, marked by the `cty` header `cbor` or `msgpack`, for compact tokens.
+//!
+//! ```rust
+//! use jwts::{Claims, jws};
+//! use jwts::jws::{DecodeContext, Encoder, Header, VerifyWith};
+//! use jwts::jws::alg::HS256;
+//! use jwts::jws::codec::{AnyCodec, Json};
+//!
+//! let token = Encoder::new().encode_with::<HS256, _>(&Json, Header::default(), &Claims::default(), b"secret").unwrap();
+//! let decoded = DecodeContext::new().decode_with::<Claims, _>(&token, &AnyCodec, VerifyWith::<HS256>(b"secret")).unwrap();
+//! assert_eq!(decoded.header.cty, None);
+//! ```
+//!
+//! With the feature `gzip`, payloads of any codec can be compressed for tokens of large claim
+//! sets, see `Encoder::gzip_payload` and `DecodeContext::with_gzip`. The compression is marked by
+//! the private header parameter `ZIP_PARAM`, so only decoders of this crate understand it.
+
+#[cfg(feature = "gzip")]
+use std::io::{Read, Write};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json as json;
+
+use crate::error::{Error, Segment};
+
+/// The private header parameter of compressed payloads, `gzip` for gzip.
+pub const ZIP_PARAM: &str = "x-zip";
+
+/// Encodes and decodes the payload segment.
+pub trait PayloadCodec {
+    /// The `cty` header of the encoded payloads, `None` to leave it as is.
+    fn cty(&self) -> Option<&str>;
+
+    /// Encode the payload.
+    fn encode<P: Serialize + ?Sized>(&self, payload: &P) -> Result<Vec<u8>, Error>;
+
+    /// Decode the payload of a token with the `cty` header.
+    fn decode<P: DeserializeOwned>(&self, cty: Option<&str>, payload: &[u8]) -> Result<P, Error>;
+}
+
+impl<C: PayloadCodec> PayloadCodec for &C {
+    #[inline]
+    fn cty(&self) -> Option<&str> {
+        (**self).cty()
+    }
+
+    #[inline]
+    fn encode<P: Serialize + ?Sized>(&self, payload: &P) -> Result<Vec<u8>, Error> {
+        (**self).encode(payload)
+    }
+
+    #[inline]
+    fn decode<P: DeserializeOwned>(&self, cty: Option<&str>, payload: &[u8]) -> Result<P, Error> {
+        (**self).decode(cty, payload)
+    }
+}
+
+/// JSON payloads, decoded regardless of the `cty` header as by `jws::decode`.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Json;
+
+/// CBOR payloads of the `cty` header `cbor`, requires the feature `cbor`.
+#[cfg(feature = "cbor")]
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Cbor;
+
+/// MessagePack payloads of the `cty` header `msgpack`, requires the feature `msgpack`.
+#[cfg(feature = "msgpack")]
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct MessagePack;
+
+/// Decodes the payload with the enabled codec of its `cty` header, JSON otherwise, and
+/// encodes JSON.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct AnyCodec;
+
+impl PayloadCodec for Json {
+    #[inline]
+    fn cty(&self) -> Option<&str> {
+        None
+    }
+
+    #[inline]
+    fn encode<P: Serialize + ?Sized>(&self, payload: &P) -> Result<Vec<u8>, Error> {
+        json::to_vec(payload).map_err(|e| Error::json(Segment::Payload, e))
+    }
+
+    #[inline]
+    fn decode<P: DeserializeOwned>(&self, _cty: Option<&str>, payload: &[u8]) -> Result<P, Error> {
+        json::from_slice(payload).map_err(|e| Error::json(Segment::Payload, e))
+    }
+}
+
+#[cfg(feature = "cbor")]
+impl PayloadCodec for Cbor {
+    #[inline]
+    fn cty(&self) -> Option<&str> {
+        Some("cbor")
+    }
+
+    fn encode<P: Serialize + ?Sized>(&self, payload: &P) -> Result<Vec<u8>, Error> {
+        let mut buf = Vec::new();
+        ciborium::into_writer(payload, &mut buf).map_err(Error::payload)?;
+        Ok(buf)
+    }
+
+    fn decode<P: DeserializeOwned>(&self, cty: Option<&str>, payload: &[u8]) -> Result<P, Error> {
+        expect_cty(cty, "cbor")?;
+        ciborium::from_reader(payload).map_err(Error::payload)
+    }
+}
+
+#[cfg(feature = "msgpack")]
+impl PayloadCodec for MessagePack {
+    #[inline]
+    fn cty(&self) -> Option<&str> {
+        Some("msgpack")
+    }
+
+    #[inline]
+    fn encode<P: Serialize + ?Sized>(&self, payload: &P) -> Result<Vec<u8>, Error> {
+        rmp_serde::to_vec_named(payload).map_err(Error::payload)
+    }
+
+    fn decode<P: DeserializeOwned>(&self, cty: Option<&str>, payload: &[u8]) -> Result<P, Error> {
+        expect_cty(cty, "msgpack")?;
+        rmp_serde::from_slice(payload).map_err(Error::payload)
+    }
+}
+
+impl PayloadCodec for AnyCodec {
+    #[inline]
+    fn cty(&self) -> Option<&str> {
+        None
+    }
+
+    #[inline]
+    fn encode<P: Serialize + ?Sized>(&self, payload: &P) -> Result<Vec<u8>, Error> {
+        Json.encode(payload)
+    }
+
+    fn decode<P: DeserializeOwned>(&self, cty: Option<&str>, payload: &[u8]) -> Result<P, Error> {
+        match cty {
+            #[cfg(feature = "cbor")]
+            Some(x) if is_media_type(x, "cbor") => Cbor.decode(cty, payload),
+            #[cfg(feature = "msgpack")]
+            Some(x) if is_media_type(x, "msgpack") => MessagePack.decode(cty, payload),
+            _ => Json.decode(cty, payload),
+        }
+    }
+}
+
+/// Whether the payload of a token with the `cty` header is JSON, that is the header is absent
+/// or a JSON media type such as `json` or `application/ld+json`. Payloads of other media types
+/// can be decoded as bytes with `jws::decode_raw`.
+pub fn is_json(cty: Option<&str>) -> bool {
+    cty.is_none_or(|x| {
+        let x = x.split(';').next().unwrap_or_default().trim();
+        is_media_type(x, "json") || x.get(x.len().saturating_sub(5)..).is_some_and(|x| x.eq_ignore_ascii_case("+json"))
+    })
+}
+
+/// Whether the `cty` is the media type, with the `application/` prefix omitted as recommended
+/// by https://tools.ietf.org/html/rfc7515#section-4.1.10
+pub(crate) fn is_media_type(cty: &str, name: &str) -> bool {
+    let cty = match cty.get(..12) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("application/") => &cty[12..],
+        _ => cty,
+    };
+    cty.eq_ignore_ascii_case(name)
+}
+
+#[cfg(any(feature = "cbor", feature = "msgpack"))]
+fn expect_cty(cty: Option<&str>, name: &str) -> Result<(), Error> {
+    match cty {
+        Some(x) if is_media_type(x, name) => Ok(()),
+        _ => Err(Error::Rejected("unexpected cty")),
+    }
+}
+
+/// Compress the payload with gzip.
+#[cfg(feature = "gzip")]
+pub(crate) fn gzip(payload: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(payload).and_then(|_| encoder.finish()).map_err(Error::payload)
+}
+
+/// Decompress the gzip payload, rejecting payloads decompressed to more than `max_len` bytes
+/// without decompressing the rest.
+#[cfg(feature = "gzip")]
+pub(crate) fn gunzip(payload: &[u8], max_len: usize) -> Result<Vec<u8>, Error> {
+    let mut buf = Vec::new();
+    let limit = u64::try_from(max_len).unwrap_or(u64::MAX).saturating_add(1);
+    flate2::read::GzDecoder::new(payload).take(limit).read_to_end(&mut buf).map_err(Error::payload)?;
+    if buf.len() > max_len {
+        return Err(Error::Rejected("decompressed payload too large"));
+    }
+    Ok(buf)
+}
@@ -1,61 +1,806 @@
 //! Decode
 
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+use std::sync::Arc;
+
 use serde::de::DeserializeOwned;
+use serde::Serialize;
 use serde_json as json;
+use serde_json::{Map, Value};
 
-use crate::bs64;
-use crate::error::Error;
-use crate::jws::Algorithm;
+use crate::{bearer, bs64, claims, der};
+use crate::audit::Finding;
+use crate::error::{Error, Segment};
+use crate::jwk::Jwk;
+use crate::jws::{Alg, Algorithm};
+use crate::key::ParsedKey;
+#[cfg(feature = "alg-rs")]
+use crate::key::RsaKeySize;
+use crate::keystore::AsyncKeyStore;
+use crate::redact::Redacted;
+#[cfg(feature = "gzip")]
+use crate::jws::codec;
+use crate::jws::codec::{Json, PayloadCodec};
 
-use super::Header;
+use super::{Header, Signature};
 
 /// A JWS token.
-#[derive(Debug, Eq, PartialEq, Clone)]
+#[derive(Eq, PartialEq, Clone)]
 pub struct Token<P> {
     /// header of token
     pub header: Header,
     /// payload of token
     pub payload: P,
     /// signature of token
-    pub signature: Vec<u8>,
+    pub signature: Signature,
+}
+
+/// Masks the signature, the payload as by its own `Debug`, see `redact::Masked` for JSON payloads
+/// and `redact::Reveal` to print them.
+impl<P: Debug> Debug for Token<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Token")
+            .field("header", &self.header)
+            .field("payload", &self.payload)
+            .field("signature", &self.signature)
+            .finish()
+    }
+}
+
+impl Token<Value> {
+    /// Deserialize the claim of the name on demand, a missing claim as `null`, e.g. to read a
+    /// claim without a payload type:
+    ///
+    /// ```rust
+    /// use jwts::{Claims, jws};
+    /// use jwts::jws::{Header, VerifyWith};
+    /// use jwts::jws::alg::HS256;
+    ///
+    /// let claims = Claims { iss: Some("sea".to_owned()), ..Default::default() };
+    /// let token = jws::encode::<HS256>(Header::default(), &claims, b"secret").unwrap();
+    /// let token = jws::decode::<serde_json::Value>(&token, VerifyWith::<HS256>(b"secret")).unwrap();
+    /// assert_eq!(token.claim::<String>("iss").unwrap(), "sea");
+    /// assert_eq!(token.claim::<Option<u64>>("exp").unwrap(), None);
+    /// ```
+    #[inline]
+    pub fn claim<T: DeserializeOwned>(&self, name: &str) -> Result<T, Error> {
+        deserialize_claim(self.payload.get(name), name)
+    }
+
+    /// Deserialize the value at the JSON Pointer (RFC 6901) on demand, e.g. `/realm_access/roles`.
+    #[inline]
+    pub fn claim_at<T: DeserializeOwned>(&self, pointer: &str) -> Result<T, Error> {
+        deserialize_claim(self.payload.pointer(pointer), pointer)
+    }
+}
+
+impl Token<Map<String, Value>> {
+    /// Deserialize the claim of the name on demand, see `Token<Value>::claim`.
+    #[inline]
+    pub fn claim<T: DeserializeOwned>(&self, name: &str) -> Result<T, Error> {
+        deserialize_claim(self.payload.get(name), name)
+    }
+
+    /// Deserialize the value at the JSON Pointer (RFC 6901) on demand, see `Token<Value>::claim_at`.
+    pub fn claim_at<T: DeserializeOwned>(&self, pointer: &str) -> Result<T, Error> {
+        let value = pointer.strip_prefix('/').and_then(|path| {
+            let end = path.find('/').unwrap_or(path.len());
+            let name = path[..end].replace("~1", "/").replace("~0", "~");
+            self.payload.get(&name)?.pointer(&path[end..])
+        });
+        deserialize_claim(value, pointer)
+    }
+}
+
+impl<P> Token<P> {
+    /// Create a `Token` of the parts, with the signature in base64url as in the token, e.g.
+    /// stored or logged with the claims.
+    pub fn from_parts(header: Header, payload: P, signature_b64: &str) -> Result<Self, Error> {
+        let signature = bs64::to_bytes(signature_b64).map_err(|e| Error::base64(Segment::Signature, e))?;
+        Ok(Token { header, payload, signature: signature.as_slice().into() })
+    }
+
+    /// The signature in base64url as in the token, e.g. to compare with a stored or logged one.
+    #[inline]
+    pub fn signature_b64(&self) -> String {
+        bs64::from_bytes(&self.signature)
+    }
+}
+
+/// Deserialize the claim, a missing one as `null` so that it is `None` as an `Option`.
+fn deserialize_claim<T: DeserializeOwned>(value: Option<&Value>, name: &str) -> Result<T, Error> {
+    let result = match value {
+        Some(value) => T::deserialize(value),
+        None => T::deserialize(&Value::Null)
+            .map_err(|_| <json::Error as serde::de::Error>::custom(format!("missing claim `{}`", name))),
+    };
+    result.map_err(|e| Error::json(Segment::Payload, e))
+}
+
+impl<P: Serialize> Token<P> {
+    /// Encode in the compact serialization as-is, the signature is not computed again.
+    pub fn to_compact(&self) -> Result<String, Error> {
+        let header = json::to_vec(&self.header).map_err(|e| Error::json(Segment::Header, e))?;
+        let payload = json::to_vec(&self.payload).map_err(|e| Error::json(Segment::Payload, e))?;
+        Ok(format!("{}.{}.{}", bs64::from_bytes(header), bs64::from_bytes(payload), bs64::from_bytes(&self.signature)))
+    }
+
+    /// Sign the header and the payload again with `A`, e.g. to reissue a token with modified
+    /// claims. The `alg` header is set to `A`, the held signature is ignored.
+    ///
+    /// ```rust
+    /// use jwts::{Claims, jws};
+    /// use jwts::jws::{Header, VerifyWith};
+    /// use jwts::jws::alg::{HS256, HS512};
+    ///
+    /// let token = jws::encode::<HS256>(Header::default(), &Claims::default(), b"upstream").unwrap();
+    /// let mut token = jws::decode::<Claims>(&token, VerifyWith::<HS256>(b"upstream")).unwrap();
+    /// token.payload.sub = Some("sea".to_owned());
+    /// let reissued = token.encode::<HS512>(b"gateway").unwrap();
+    /// let token = jws::decode::<Claims>(&reissued, VerifyWith::<HS512>(b"gateway")).unwrap();
+    /// assert_eq!(token.payload.sub.as_deref(), Some("sea"));
+    /// ```
+    #[inline]
+    pub fn encode<A: Algorithm>(&self, key: &A::SignKey) -> Result<String, Error> {
+        super::encode::<A>(self.header.clone(), &self.payload, key)
+    }
 }
 
 pub trait Verify<P> {
+    /// Verify the signature of the signing input `f2s`, the first two segments of the token as
+    /// they are, see `SigningInput` for the exact protected header octets.
     fn verify(&self, f2s: &str, signature: &[u8], header: &Header, payload: &P) -> Result<(), Error>;
+
+    /// Report the weaknesses of the verification, see `Verifier::audit`.
+    #[inline]
+    fn audit(&self, _findings: &mut Vec<Finding>) {}
 }
 
-pub struct NoVerify;
+/// The signing input of `Verify::verify` split into its segments, e.g. for the exact protected
+/// header octets of counter-signature schemes, which the parsed `Header` does not preserve.
+///
+/// ```rust
+/// use jwts::{Claims, Error, jws};
+/// use jwts::jws::{Header, SigningInput, Verify};
+/// use jwts::jws::alg::HS256;
+///
+/// struct RawHeader;
+///
+/// impl<P> Verify<P> for RawHeader {
+///     fn verify(&self, f2s: &str, _signature: &[u8], _header: &Header, _payload: &P) -> Result<(), Error> {
+///         let input = SigningInput::new(f2s);
+///         assert_eq!(input.header_bytes()?, br#"{"typ":"JWT","alg":"HS256"}"#);
+///         assert_eq!(format!("{}.{}", input.header(), input.payload()), f2s);
+///         Ok(())
+///     }
+/// }
+///
+/// let token = jws::encode::<HS256>(Header::default(), &Claims::default(), b"secret").unwrap();
+/// jws::decode::<Claims>(&token, RawHeader).unwrap();
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct SigningInput<'a> {
+    header: &'a str,
+    payload: &'a str,
+}
+
+impl<'a> SigningInput<'a> {
+    /// Split the signing input, all of it is the header if it has no '.'.
+    #[inline]
+    pub fn new(f2s: &'a str) -> Self {
+        let (header, payload) = f2s.split_once('.').unwrap_or((f2s, ""));
+        SigningInput { header, payload }
+    }
+
+    /// The base64url header segment as it is in the token.
+    #[inline]
+    pub fn header(&self) -> &'a str {
+        self.header
+    }
+
+    /// The base64url payload segment as it is in the token.
+    #[inline]
+    pub fn payload(&self) -> &'a str {
+        self.payload
+    }
+
+    /// The exact protected header octets, the decoded header segment.
+    #[inline]
+    pub fn header_bytes(&self) -> Result<Vec<u8>, Error> {
+        bs64::to_bytes(self.header).map_err(|e| Error::base64(Segment::Header, e))
+    }
+
+    /// The decoded payload segment.
+    #[inline]
+    pub fn payload_bytes(&self) -> Result<Vec<u8>, Error> {
+        bs64::to_bytes(self.payload).map_err(|e| Error::base64(Segment::Payload, e))
+    }
+}
+
+/// Skip the signature verification, so the header and the claims are not authenticated and
+/// anyone can forge them. Only for inspecting tokens, e.g. in debugging tools; use
+/// `decode_header` to select the key of a token before verifying it.
+pub struct DangerousNoVerify;
 
 pub struct VerifyWith<'a, A: Algorithm>(pub &'a A::VerifyKey);
 
-impl<P> Verify<P> for NoVerify {
+impl<A: Algorithm> Clone for VerifyWith<'_, A> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<A: Algorithm> Copy for VerifyWith<'_, A> {}
+
+/// Verify with a key of `jwts::key` parsed once by the crypto backend, such as `RsaPublicKey`,
+/// where `VerifyWith` has the backend parse the key bytes on every verification.
+pub struct VerifyWithKey<'a, A: Algorithm, K: ?Sized> {
+    key: &'a K,
+    _alg: PhantomData<A>,
+}
+
+impl<'a, A: Algorithm, K: ?Sized> VerifyWithKey<'a, A, K> {
+    #[inline]
+    pub fn new(key: &'a K) -> Self {
+        VerifyWithKey {
+            key,
+            _alg: PhantomData,
+        }
+    }
+}
+
+impl<A: Algorithm, K: ?Sized> Clone for VerifyWithKey<'_, A, K> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<A: Algorithm, K: ?Sized> Copy for VerifyWithKey<'_, A, K> {}
+
+/// Verify with each of the keys in order until one matches, for tokens without `kid` during a
+/// key rollover. The error of the last key is returned if none matches.
+pub struct VerifyWithAnyKey<'a, A: Algorithm>(pub &'a [&'a A::VerifyKey]);
+
+/// Verify with the public key embedded in the `jwk` header, as used by DPoP proofs and ACME.
+///
+/// Anyone can sign a token with their own key and embed it, so the policy is mandatory and
+/// decides whether the embedded key is acceptable, e.g. by its thumbprint. Symmetric keys
+/// and keys with private parameters are always rejected.
+pub struct VerifyWithEmbeddedJwk<A: Algorithm, F: Fn(&Jwk, &Header) -> bool> {
+    policy: F,
+    _alg: PhantomData<A>,
+}
+
+impl<A: Algorithm, F: Fn(&Jwk, &Header) -> bool> VerifyWithEmbeddedJwk<A, F> {
+    #[inline]
+    pub fn new(policy: F) -> Self {
+        VerifyWithEmbeddedJwk {
+            policy,
+            _alg: PhantomData,
+        }
+    }
+}
+
+/// Verify with the RS or PS algorithm `A` by an RSA key with a modulus of the accepted size,
+/// instead of the 2048 to 8192 bits of `VerifyWith`.
+///
+/// ```rust
+/// use jwts::{Claims, Error, jws};
+/// use jwts::jws::{VerifyWith, VerifyWithRsaKeySize};
+/// use jwts::jws::alg::RS256;
+/// use jwts::key::RsaKeySize;
+///
+/// # let key_pair = jwts::crypto::RsaKeyPair::from_der(include_bytes!("../../tests/rsa-pri.der")).unwrap();
+/// # let token = jws::encode::<RS256>(Default::default(), &Claims::default(), &key_pair).unwrap();
+/// let key = include_bytes!("../../tests/rsa-pub.der");
+/// jws::decode::<Claims>(&token, VerifyWith::<RS256>(key)).unwrap();
+///
+/// let verify = VerifyWithRsaKeySize::<RS256>::new(key, RsaKeySize::new(3072, 8192));
+/// let err = jws::decode::<Claims>(&token, verify).unwrap_err();
+/// assert_eq!(err, Error::RsaKeySize { bits: 2048, min: 3072, max: 8192 });
+/// ```
+#[cfg(feature = "alg-rs")]
+pub struct VerifyWithRsaKeySize<'a, A: Algorithm> {
+    key: &'a [u8],
+    size: RsaKeySize,
+    _alg: PhantomData<A>,
+}
+
+#[cfg(feature = "alg-rs")]
+impl<'a, A: Algorithm> VerifyWithRsaKeySize<'a, A> {
+    #[inline]
+    pub fn new(key: &'a [u8], size: RsaKeySize) -> Self {
+        VerifyWithRsaKeySize {
+            key,
+            size,
+            _alg: PhantomData,
+        }
+    }
+}
+
+/// Omits the key.
+impl<A: Algorithm> Debug for VerifyWith<'_, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VerifyWith").field("alg", &A::name()).field("key", &Redacted).finish()
+    }
+}
+
+/// Omits the key.
+impl<A: Algorithm, K: ?Sized> Debug for VerifyWithKey<'_, A, K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VerifyWithKey").field("alg", &A::name()).field("key", &Redacted).finish()
+    }
+}
+
+/// Omits the keys.
+impl<A: Algorithm> Debug for VerifyWithAnyKey<'_, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VerifyWithAnyKey").field("alg", &A::name()).field("keys", &self.0.len()).finish()
+    }
+}
+
+/// Omits the key.
+#[cfg(feature = "alg-rs")]
+impl<A: Algorithm> Debug for VerifyWithRsaKeySize<'_, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VerifyWithRsaKeySize").field("alg", &A::name()).field("key", &Redacted).field("size", &self.size).finish()
+    }
+}
+
+/// Verify with `V`, also accepting ECDSA signatures in ASN.1 DER as written by some
+/// non-conforming emitters, converted to the fixed `r || s` form of RFC 7518 first.
+///
+/// ```rust
+/// use jwts::{Claims, jws};
+/// use jwts::jws::{LenientEcdsa, VerifyWith};
+/// use jwts::jws::alg::ES256;
+/// use jwts::key::EcPublicKey;
+///
+/// # let token = jws::encode::<ES256>(Default::default(), &Claims::default(), include_bytes!("../../tests/ecdsa-pri.pk8")).unwrap();
+/// let key = EcPublicKey::from_spki_der(include_bytes!("../../tests/ecdsa-pub.der")).unwrap();
+/// jws::decode::<Claims>(&token, LenientEcdsa(VerifyWith::<ES256>(&key))).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct LenientEcdsa<V>(pub V);
+
+impl<P, V: Verify<P> + ?Sized> Verify<P> for &V {
+    #[inline]
+    fn verify(&self, f2s: &str, signature: &[u8], header: &Header, payload: &P) -> Result<(), Error> {
+        (**self).verify(f2s, signature, header, payload)
+    }
+
+    #[inline]
+    fn audit(&self, findings: &mut Vec<Finding>) {
+        (**self).audit(findings)
+    }
+}
+
+impl<P, V: Verify<P>> Verify<P> for LenientEcdsa<V> {
+    fn verify(&self, f2s: &str, signature: &[u8], header: &Header, payload: &P) -> Result<(), Error> {
+        let len = match header.alg {
+            Some(Alg::ES256) => 32,
+            Some(Alg::ES384) => 48,
+            Some(Alg::ES512) => 66,
+            _ => return self.0.verify(f2s, signature, header, payload),
+        };
+        if signature.len() == len * 2 || signature.first() != Some(&der::SEQUENCE) {
+            return self.0.verify(f2s, signature, header, payload);
+        }
+        let fixed = der::ecdsa_signature(signature, len).map_err(|_| Error::InvalidSignature)?;
+        self.0.verify(f2s, &fixed, header, payload)
+    }
+
+    #[inline]
+    fn audit(&self, findings: &mut Vec<Finding>) {
+        self.0.audit(findings)
+    }
+}
+
+impl<P> Verify<P> for DangerousNoVerify {
     fn verify(&self, _f2s: &str, _signature: &[u8], _header: &Header, _payload: &P) -> Result<(), Error> {
         Ok(())
     }
+
+    #[inline]
+    fn audit(&self, findings: &mut Vec<Finding>) {
+        findings.push(Finding::NoSignatureVerification)
+    }
 }
 
 impl<'a, P, A: Algorithm> Verify<P> for VerifyWith<'a, A> {
     fn verify(&self, f2s: &str, signature: &[u8], _header: &Header, _payload: &P) -> Result<(), Error> {
         A::verify(f2s, signature, self.0)
     }
+
+    #[inline]
+    fn audit(&self, findings: &mut Vec<Finding>) {
+        findings.extend(A::audit_key(self.0))
+    }
+}
+
+impl<P, A: Algorithm, K: ParsedKey + ?Sized> Verify<P> for VerifyWithKey<'_, A, K> {
+    #[inline]
+    fn verify(&self, f2s: &str, signature: &[u8], _header: &Header, _payload: &P) -> Result<(), Error> {
+        self.key.verify(&Alg::from(A::name()), f2s.as_bytes(), signature)
+    }
+}
+
+impl<'a, P, A: Algorithm> Verify<P> for VerifyWithAnyKey<'a, A> {
+    fn verify(&self, f2s: &str, signature: &[u8], _header: &Header, _payload: &P) -> Result<(), Error> {
+        let mut result = Err(Error::InvalidKey("no key"));
+        for key in self.0 {
+            result = A::verify(f2s, signature, key);
+            if result.is_ok() {
+                break;
+            }
+        }
+        result
+    }
+
+    #[inline]
+    fn audit(&self, findings: &mut Vec<Finding>) {
+        findings.extend(self.0.iter().filter_map(|key| A::audit_key(key)))
+    }
+}
+
+#[cfg(feature = "alg-rs")]
+impl<'a, P, A: Algorithm> Verify<P> for VerifyWithRsaKeySize<'a, A> {
+    fn verify(&self, f2s: &str, signature: &[u8], _header: &Header, _payload: &P) -> Result<(), Error> {
+        Alg::from(A::name()).verify_rsa(f2s, signature, self.key, self.size)
+    }
+
+    #[inline]
+    fn audit(&self, findings: &mut Vec<Finding>) {
+        if self.size.min_bits < RsaKeySize::DEFAULT.min_bits {
+            findings.push(Finding::ShortRsaKey { min: self.size.min_bits })
+        }
+    }
+}
+
+impl<P, A: Algorithm<VerifyKey=[u8]>, F: Fn(&Jwk, &Header) -> bool> Verify<P> for VerifyWithEmbeddedJwk<A, F> {
+    fn verify(&self, f2s: &str, signature: &[u8], header: &Header, _payload: &P) -> Result<(), Error> {
+        let jwk = header.jwk.as_ref().ok_or(Error::InvalidKey("missing jwk"))?;
+        if jwk.kty == "oct" || jwk.extra.contains_key("d") {
+            return Err(Error::InvalidKey("embedded jwk is not a public key"));
+        }
+        if jwk.alg.as_ref().is_some_and(|x| x.as_str() != A::name()) {
+            return Err(Error::InvalidKey("embedded jwk is for another algorithm"));
+        }
+        (self.policy)(jwk, header).then_some(()).ok_or(Error::Rejected("embedded jwk rejected by policy"))?;
+        A::verify(f2s, signature, &jwk.to_verify_key()?)
+    }
+}
+
+/// Reusable buffers for decoding, so that repeated decoding does not allocate scratch space
+/// once the buffers have grown to the token size.
+///
+/// Only the decoded `Token` itself is allocated, as it owns its header, payload and signature.
+/// `Verifier` keeps one per thread.
+#[derive(Debug, Default, Clone)]
+pub struct DecodeContext {
+    header: Vec<u8>,
+    payload: Vec<u8>,
+    signature: Vec<u8>,
+    lenient_numeric_dates: bool,
+    lenient_string_claims: bool,
+    lenient_parsing: bool,
+    strict_header: Option<Arc<[String]>>,
+    crit_extensions: Option<Arc<[String]>>,
+    #[cfg(feature = "gzip")]
+    gzip_max_len: Option<usize>,
+}
+
+impl DecodeContext {
+    /// Create a `DecodeContext` with empty buffers.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The context with the default options, keeping its buffers.
+    #[inline]
+    pub(crate) fn reset(self) -> Self {
+        DecodeContext {
+            header: self.header,
+            payload: self.payload,
+            signature: self.signature,
+            ..Self::default()
+        }
+    }
+
+    /// Accept `exp`, `nbf` and `iat` as floats or numeric strings, as emitted by some issuers,
+    /// normalized to whole seconds before the payload is deserialized.
+    #[inline]
+    pub fn with_lenient_numeric_dates(self) -> Self {
+        DecodeContext {
+            lenient_numeric_dates: true,
+            ..self
+        }
+    }
+
+    /// Accept `iss`, `sub`, `aud` and `jti` as numbers, as emitted by some issuers, converted to
+    /// strings before the payload is deserialized.
+    #[inline]
+    pub fn with_lenient_string_claims(self) -> Self {
+        DecodeContext {
+            lenient_string_claims: true,
+            ..self
+        }
+    }
+
+    /// Ignore whitespace around the token and a `Bearer` scheme before it, see `bearer::strip`.
+    #[inline]
+    pub fn with_lenient_parsing(self) -> Self {
+        DecodeContext {
+            lenient_parsing: true,
+            ..self
+        }
+    }
+
+    /// Reject headers with parameters other than the registered ones and the allowed, before the
+    /// signature is verified, for closed ecosystems detecting tampered tokens or misconfigured
+    /// issuers early.
+    #[inline]
+    pub fn with_strict_header(self, allowed: impl IntoIterator<Item=impl Into<String>>) -> Self {
+        self.with_strict_header_shared(allowed.into_iter().map(Into::into).collect())
+    }
+
+    #[inline]
+    pub(crate) fn with_strict_header_shared(self, allowed: Arc<[String]>) -> Self {
+        DecodeContext {
+            strict_header: Some(allowed),
+            ..self
+        }
+    }
+
+    /// Accept the extensions in the `crit` header as understood, tokens marking any other header
+    /// parameter as critical are rejected, see https://tools.ietf.org/html/rfc7515#section-4.1.11
+    #[inline]
+    pub fn with_crit_extensions(self, extensions: impl IntoIterator<Item=impl Into<String>>) -> Self {
+        self.with_crit_extensions_shared(extensions.into_iter().map(Into::into).collect())
+    }
+
+    #[inline]
+    pub(crate) fn with_crit_extensions_shared(self, extensions: Arc<[String]>) -> Self {
+        DecodeContext {
+            crit_extensions: Some(extensions),
+            ..self
+        }
+    }
+
+    /// Accept payloads compressed by `Encoder::gzip_payload`, decompressed to at most `max_len`
+    /// bytes before the signature is verified. Compressed payloads are rejected without it.
+    #[cfg(feature = "gzip")]
+    #[inline]
+    pub fn with_gzip(self, max_len: usize) -> Self {
+        DecodeContext {
+            gzip_max_len: Some(max_len),
+            ..self
+        }
+    }
+
+    /// Decode a token with the specific verification, see `decode`.
+    #[inline]
+    pub fn decode<P: DeserializeOwned>(&mut self, token: &str, verify: impl Verify<P>) -> Result<Token<P>, Error> {
+        self.decode_with(token, &Json, verify)
+    }
+
+    /// Decode a token with the payload decoded by the codec according to its `cty` header.
+    pub fn decode_with<P: DeserializeOwned, C: PayloadCodec>(&mut self, token: &str, codec: &C, verify: impl Verify<P>) -> Result<Token<P>, Error> {
+        let (signature, f2s) = rsplit2_dot(self.strip(token))?;
+        let (payload, header) = rsplit2_dot(f2s)?;
+
+        decode_segment(signature, &mut self.signature, Segment::Signature)?;
+        decode_segment(header, &mut self.header, Segment::Header)?;
+        decode_segment(payload, &mut self.payload, Segment::Payload)?;
+
+        let header = self.parse_header()?;
+        #[cfg(feature = "gzip")]
+        if let Some(payload) = self.inflate(&header, &self.payload)? {
+            self.payload = payload;
+        }
+        let cty = header.cty.as_deref();
+        let payload = if self.lenient_numeric_dates || self.lenient_string_claims {
+            let mut payload = codec.decode(cty, &self.payload)?;
+            if self.lenient_numeric_dates {
+                claims::normalize_numeric_dates(&mut payload);
+            }
+            if self.lenient_string_claims {
+                claims::normalize_string_claims(&mut payload);
+            }
+            json::from_value(payload).map_err(|e| Error::json(Segment::Payload, e))?
+        } else {
+            codec.decode(cty, &self.payload)?
+        };
+
+        verify.verify(f2s, &self.signature, &header, &payload)?;
+
+        Ok(Token { header, payload, signature: Signature::from(self.signature.as_slice()) })
+    }
+
+    /// Decode a token with the payload bytes as they are, without parsing them, for payloads of
+    /// a non-JSON media type in the `cty` header, see `codec::is_json`.
+    pub fn decode_raw(&mut self, token: &str, verify: impl Verify<Vec<u8>>) -> Result<Token<Vec<u8>>, Error> {
+        let (signature, f2s) = rsplit2_dot(self.strip(token))?;
+        let (payload, header) = rsplit2_dot(f2s)?;
+
+        decode_segment(signature, &mut self.signature, Segment::Signature)?;
+        decode_segment(header, &mut self.header, Segment::Header)?;
+        decode_segment(payload, &mut self.payload, Segment::Payload)?;
+
+        let header = self.parse_header()?;
+        #[cfg(feature = "gzip")]
+        let payload = match self.inflate(&header, &self.payload)? {
+            Some(payload) => payload,
+            None => self.payload.clone(),
+        };
+        #[cfg(not(feature = "gzip"))]
+        let payload = self.payload.clone();
+        verify.verify(f2s, &self.signature, &header, &payload)?;
+
+        Ok(Token { header, payload, signature: Signature::from(self.signature.as_slice()) })
+    }
+
+    /// The decoded header, checked for critical extensions not understood, and for unknown
+    /// parameters in the strict mode.
+    fn parse_header(&self) -> Result<Header, Error> {
+        let header: Header = json::from_slice(&self.header).map_err(|e| Error::json(Segment::Header, e))?;
+        if let Some(crit) = &header.crit {
+            let understood = self.crit_extensions.as_deref().unwrap_or_default();
+            if crit.iter().any(|x| !understood.contains(x)) {
+                return Err(Error::Rejected("unknown critical header parameter"));
+            }
+        }
+        if let Some(allowed) = &self.strict_header {
+            #[cfg(feature = "gzip")]
+            let allowed_zip = |x: &str| self.gzip_max_len.is_some() && x == codec::ZIP_PARAM;
+            #[cfg(not(feature = "gzip"))]
+            let allowed_zip = |_: &str| false;
+            if header.extra.keys().any(|x| !allowed.contains(x) && !allowed_zip(x)) {
+                return Err(Error::Rejected("unknown header parameter"));
+            }
+        }
+        Ok(header)
+    }
+
+    /// The decompressed payload if compressed, rejected if compressed payloads are not accepted.
+    #[cfg(feature = "gzip")]
+    fn inflate(&self, header: &Header, payload: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        let Some(zip) = header.extra.get(codec::ZIP_PARAM) else { return Ok(None) };
+        match (self.gzip_max_len, zip.as_str()) {
+            (Some(max_len), Some("gzip")) => codec::gunzip(payload, max_len).map(Some),
+            (Some(_), _) => Err(Error::Rejected("unsupported payload compression")),
+            (None, _) => Err(Error::Rejected("compressed payload not accepted")),
+        }
+    }
+
+    /// The token stripped with the lenient parsing.
+    #[inline]
+    fn strip<'t>(&self, token: &'t str) -> &'t str {
+        if self.lenient_parsing { bearer::strip(token) } else { token }
+    }
+
+    /// Decode a token of untrusted bytes like `decode`, invalid UTF-8 is `Error::InvalidUtf8`.
+    #[inline]
+    pub fn decode_bytes<P: DeserializeOwned>(&mut self, token: &[u8], verify: impl Verify<P>) -> Result<Token<P>, Error> {
+        self.decode(std::str::from_utf8(token).map_err(Error::InvalidUtf8)?, verify)
+    }
 }
 
 /// Decode a token with the specific verification
+#[inline]
 pub fn decode<P: DeserializeOwned>(token: &str, verify: impl Verify<P>) -> Result<Token<P>, Error> {
-    let (signature, f2s) = rsplit2_dot(token)?;
-    let signature = bs64::to_bytes(signature)?;
+    DecodeContext::new().decode(token, verify)
+}
 
-    let (payload, header) = rsplit2_dot(f2s)?;
+/// Decode a token and verify it with `A` by the key resolved asynchronously by the store, e.g.
+/// fetched by the `kid` of the header or the `iss` of the payload, which are not verified yet
+/// when passed to the store.
+///
+/// ```rust
+/// use jwts::{Claims, Error, jws};
+/// use jwts::jws::Header;
+/// use jwts::jws::alg::HS256;
+///
+/// # async fn run() {
+/// let store = |header: &Header, _: &Claims| {
+///     let kid = header.kid.clone();
+///     async move {
+///         match kid.as_deref() {
+///             Some("key-1") => Ok(b"secret".to_vec()), // e.g. a database lookup
+///             _ => Err(Error::InvalidKey("no matching key")),
+///         }
+///     }
+/// };
+/// let token = jws::encode::<HS256>(Header::default().with_kid("key-1"), &Claims::default(), b"secret").unwrap();
+/// jws::decode_async::<HS256, Claims>(&token, store).await.unwrap();
+/// # }
+/// ```
+pub async fn decode_async<A: Algorithm, P: DeserializeOwned>(
+    token: &str,
+    store: impl AsyncKeyStore<P, Key: AsRef<A::VerifyKey>>,
+) -> Result<Token<P>, Error> {
+    let decoded = decode::<P>(token, DangerousNoVerify)?;
+    let key = store.resolve(&decoded.header, &decoded.payload).await?;
+    let (_, f2s) = rsplit2_dot(token)?;
+    A::verify(f2s, &decoded.signature, key.as_ref())?;
+    Ok(decoded)
+}
 
-    let header = bs64::to_bytes(header)?;
-    let payload = bs64::to_bytes(payload)?;
+/// Decode the header of a token without verifying it, e.g. to select the key by `kid` or the
+/// verification by `alg`. The header is not authenticated until the token is decoded with a
+/// verification.
+pub fn decode_header(token: &str) -> Result<Header, Error> {
+    let (_, f2s) = rsplit2_dot(token)?;
+    let (_, header) = rsplit2_dot(f2s)?;
+    let header = bs64::to_bytes(header).map_err(|e| Error::base64(Segment::Header, e))?;
+    json::from_slice(&header).map_err(|e| Error::json(Segment::Header, e))
+}
+
+/// A segment of a token, as it is in the token and base64url-decoded.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RawSegment<'t> {
+    /// The base64url segment in the token
+    pub encoded: &'t str,
+    /// The decoded bytes
+    pub bytes: Vec<u8>,
+}
+
+/// The header segment of `decode_split`.
+pub type RawHeader<'t> = RawSegment<'t>;
+/// The payload segment of `decode_split`.
+pub type RawPayload<'t> = RawSegment<'t>;
+/// The signature segment of `decode_split`.
+pub type RawSignature<'t> = RawSegment<'t>;
+
+/// Split a token into its base64url-decoded segments without parsing or verifying them, for
+/// tooling such as linters and token inspectors. Nothing of the token is authenticated.
+///
+/// ```rust
+/// use jwts::{Claims, jws};
+/// use jwts::jws::Header;
+/// use jwts::jws::alg::HS256;
+///
+/// let token = jws::encode::<HS256>(Header::default(), &Claims::default(), b"secret").unwrap();
+/// let (header, payload, signature) = jws::decode_split(&token).unwrap();
+/// assert_eq!(header.bytes, br#"{"typ":"JWT","alg":"HS256"}"#);
+/// assert_eq!(payload.bytes, b"{}");
+/// assert_eq!(signature.bytes.len(), 32);
+/// assert_eq!(format!("{}.{}.{}", header.encoded, payload.encoded, signature.encoded), token);
+/// ```
+pub fn decode_split(token: &str) -> Result<(RawHeader<'_>, RawPayload<'_>, RawSignature<'_>), Error> {
+    let (signature, f2s) = rsplit2_dot(token)?;
+    let (payload, header) = rsplit2_dot(f2s)?;
+    let segment = |encoded, segment| -> Result<RawSegment<'_>, Error> {
+        let bytes = bs64::to_bytes(encoded).map_err(|e| Error::base64(segment, e))?;
+        Ok(RawSegment { encoded, bytes })
+    };
+    Ok((segment(header, Segment::Header)?, segment(payload, Segment::Payload)?, segment(signature, Segment::Signature)?))
+}
 
-    let header: Header = json::from_slice(&header)?;
-    let payload = json::from_slice(&payload)?;
+/// Decode a token with the payload bytes as they are, see `DecodeContext::decode_raw`.
+#[inline]
+pub fn decode_raw(token: &str, verify: impl Verify<Vec<u8>>) -> Result<Token<Vec<u8>>, Error> {
+    DecodeContext::new().decode_raw(token, verify)
+}
 
-    verify.verify(f2s, &signature, &header, &payload)?;
+/// Decode a token of untrusted bytes, such as read off the wire, with the specific verification.
+///
+/// Any input is rejected with an `Error` instead of a panic, provided the verification and the
+/// deserialization of `P` do not panic themselves; invalid UTF-8 is `Error::InvalidUtf8`.
+#[inline]
+pub fn decode_bytes<P: DeserializeOwned>(token: &[u8], verify: impl Verify<P>) -> Result<Token<P>, Error> {
+    DecodeContext::new().decode_bytes(token, verify)
+}
 
-    Ok(Token { header, payload, signature })
+/// Decode a base64url segment into the cleared buffer.
+#[inline]
+fn decode_segment(s: &str, buf: &mut Vec<u8>, segment: Segment) -> Result<(), Error> {
+    buf.clear();
+    bs64::decode_into(s, buf).map_err(|e| Error::base64(segment, e))
 }
 
 /// Reverse split the string to 2 sections with '.'
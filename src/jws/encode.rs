@@ -1,24 +1,226 @@
 //! Encode
 
+use serde::de::IgnoredAny;
 use serde::Serialize;
 use serde_json as json;
 
-use crate::{bs64, Error};
+use crate::bs64;
+use crate::error::{Error, Segment};
 use crate::jws::{Algorithm, Header};
+#[cfg(feature = "gzip")]
+use crate::jws::codec;
+use crate::jws::codec::{Json, PayloadCodec};
 
-/// Encode and sign a token, return the signed token as `String`.
-pub fn encode<A: Algorithm>(header: Header, payload: &impl Serialize, key: &A::SignKey) -> Result<String, Error> {
-    let header = header.with_algorithm::<A>();
-    let header = json::to_string(&header)
-        .map(bs64::from_bytes)?;
+/// Signature length reserved up front, enough for every builtin algorithm with RSA keys up to 4096 bits.
+const SIGNATURE_LEN_HINT: usize = 512;
+
+/// Token encoder with options.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Encoder {
+    auto_kid: bool,
+    canonical_header: bool,
+    #[cfg(feature = "gzip")]
+    gzip: bool,
+}
+
+impl Encoder {
+    /// Create a new `Encoder` with the default options.
+    #[inline]
+    pub fn new() -> Self {
+        Encoder {
+            auto_kid: true,
+            canonical_header: false,
+            #[cfg(feature = "gzip")]
+            gzip: false,
+        }
+    }
+
+    /// Whether to populate the absent `kid` header from the signing key, see `Algorithm::key_id`.
+    /// Enabled by default.
+    #[inline]
+    pub fn auto_kid(self, enabled: bool) -> Self {
+        Encoder {
+            auto_kid: enabled,
+            ..self
+        }
+    }
+
+    /// Whether to serialize the header canonically, with the members of every object sorted by
+    /// name and no whitespace, so the same header is always encoded to the same bytes. Disabled
+    /// by default, the registered parameters are then in the order of the `Header` fields and
+    /// the others in the order of `serde_json::Map`, which keeps the insertion order instead of
+    /// sorting with the `serde_json` feature `preserve_order`.
+    #[inline]
+    pub fn canonical_header(self, enabled: bool) -> Self {
+        Encoder {
+            canonical_header: enabled,
+            ..self
+        }
+    }
+
+    /// Whether to compress the payload with gzip, marked by the private header parameter
+    /// `codec::ZIP_PARAM`, for tokens of large claim sets between services decoding them with
+    /// `DecodeContext::with_gzip` of this crate. Disabled by default.
+    ///
+    /// ```rust
+    /// use jwts::{Claims, jws};
+    /// use jwts::jws::{DecodeContext, Encoder, Header, VerifyWith};
+    /// use jwts::jws::alg::HS256;
+    ///
+    /// let claims = serde_json::json!({ "roles": vec!["reader"; 1000] });
+    /// let token = Encoder::new().gzip_payload(true).encode::<HS256>(Header::default(), &claims, b"secret").unwrap();
+    /// assert!(token.len() < 1000);
+    /// let decoded = DecodeContext::new().with_gzip(64 * 1024).decode::<serde_json::Value>(&token, VerifyWith::<HS256>(b"secret")).unwrap();
+    /// assert_eq!(decoded.payload, claims);
+    /// ```
+    #[cfg(feature = "gzip")]
+    #[inline]
+    pub fn gzip_payload(self, enabled: bool) -> Self {
+        Encoder {
+            gzip: enabled,
+            ..self
+        }
+    }
+
+    /// Encode and sign a token, return the signed token as `String`.
+    #[inline]
+    pub fn encode<A: Algorithm>(&self, header: Header, payload: &impl Serialize, key: &A::SignKey) -> Result<String, Error> {
+        self.encode_with::<A, _>(&Json, header, payload, key)
+    }
+
+    /// Encode and sign a token with the payload encoded by the codec, setting its `cty` header.
+    pub fn encode_with<A: Algorithm, C: PayloadCodec>(&self, codec: &C, mut header: Header, payload: &impl Serialize, key: &A::SignKey) -> Result<String, Error> {
+        if let Some(cty) = codec.cty() {
+            header.cty = Some(cty.to_owned());
+        }
+        self.sign::<A>(header, &codec.encode(payload)?, key)
+    }
+
+    /// Encode and sign a token of the payload bytes as they are, such as a binary envelope
+    /// of the media type in the `cty` header, see `jws::decode_raw`.
+    #[inline]
+    pub fn encode_bytes<A: Algorithm>(&self, header: Header, payload: &[u8], key: &A::SignKey) -> Result<String, Error> {
+        self.sign::<A>(header, payload, key)
+    }
+
+    /// Encode and sign a token of the JSON payload as it is, keeping its member order, number
+    /// representations and whitespace, such as a payload serialized by another stack.
+    pub fn encode_json<A: Algorithm>(&self, header: Header, payload: &str, key: &A::SignKey) -> Result<String, Error> {
+        json::from_str::<IgnoredAny>(payload).map_err(|e| Error::json(Segment::Payload, e))?;
+        self.sign::<A>(header, payload.as_bytes(), key)
+    }
 
-    let payload = json::to_string(&payload)
-        .map(bs64::from_bytes)?;
+    /// Build the signing input `base64(header) + "." + base64(payload)` of the header as it is,
+    /// with the `alg` of the external signer, which signs it. The token is the signing input,
+    /// '.' and the base64url signature.
+    ///
+    /// ```rust
+    /// use jwts::{bs64, Claims, jws};
+    /// use jwts::jws::{Algorithm, Encoder, Header, VerifyWith};
+    /// use jwts::jws::alg::HS256;
+    ///
+    /// let header = Header::default().with_algorithm::<HS256>();
+    /// let input = Encoder::new().signing_input(header, &Claims::default()).unwrap();
+    /// let signature = HS256::sign(&input, b"secret").unwrap(); // e.g. returned by a KMS
+    /// let token = format!("{}.{}", input, bs64::from_bytes(signature)); // or `jws::assemble`
+    /// jws::decode::<Claims>(&token, VerifyWith::<HS256>(b"secret")).unwrap();
+    /// ```
+    #[inline]
+    pub fn signing_input(&self, header: Header, payload: &impl Serialize) -> Result<String, Error> {
+        self.f2s(header, &Json.encode(payload)?, 0)
+    }
 
-    let f2s = [header, payload].join(".");
-    let signature = A::sign(&f2s, &key)?;
+    fn sign<A: Algorithm>(&self, header: Header, payload: &[u8], key: &A::SignKey) -> Result<String, Error> {
+        let mut header = header.with_algorithm::<A>();
+        if self.auto_kid && header.kid.is_none() {
+            header.kid = A::key_id(key);
+        }
+        let mut token = self.f2s(header, payload, 1 + bs64::encoded_len(SIGNATURE_LEN_HINT))?;
+        let signature = A::sign(&token, key)?;
+
+        token.push('.');
+        bs64::encode_into(signature, &mut token);
+        Ok(token)
+    }
+
+    /// The signing input, with the capacity for `additional` more bytes.
+    fn f2s(&self, header: Header, payload: &[u8], additional: usize) -> Result<String, Error> {
+        #[cfg(feature = "gzip")]
+        let mut header = header;
+        #[cfg(feature = "gzip")]
+        let compressed;
+        #[cfg(feature = "gzip")]
+        let payload = match self.gzip {
+            true => {
+                header.extra.insert(codec::ZIP_PARAM.to_owned(), "gzip".into());
+                compressed = codec::gzip(payload)?;
+                compressed.as_slice()
+            }
+            false => payload,
+        };
+        let header = if self.canonical_header {
+            let mut header = json::to_value(&header).map_err(|e| Error::json(Segment::Header, e))?;
+            header.sort_all_objects();
+            json::to_vec(&header)
+        } else {
+            json::to_vec(&header)
+        };
+        let header = header.map_err(|e| Error::json(Segment::Header, e))?;
+
+        let f2s_len = bs64::encoded_len(header.len()) + 1 + bs64::encoded_len(payload.len());
+        let mut token = String::with_capacity(f2s_len + additional);
+        bs64::encode_into(header, &mut token);
+        token.push('.');
+        bs64::encode_into(payload, &mut token);
+        Ok(token)
+    }
+}
+
+impl Default for Encoder {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Encode and sign a token with the default `Encoder`, return the signed token as `String`.
+#[inline]
+pub fn encode<A: Algorithm>(header: Header, payload: &impl Serialize, key: &A::SignKey) -> Result<String, Error> {
+    Encoder::new().encode::<A>(header, payload, key)
+}
+
+/// Build the signing input of the header and the payload with the default `Encoder`, see
+/// `Encoder::signing_input`.
+#[inline]
+pub fn signing_input(header: Header, payload: &impl Serialize) -> Result<String, Error> {
+    Encoder::new().signing_input(header, payload)
+}
 
-    let trd = bs64::from_bytes(signature);
+/// Assemble a token of the base64url header and payload segments signed elsewhere, such as
+/// those of `signing_input` signed by a KMS, and the signature bytes. The header segment must be
+/// a JSON header and the payload segment base64url, so a malformed token is never handed out.
+///
+/// ```rust
+/// use jwts::{Claims, jws};
+/// use jwts::jws::{Algorithm, Header, SigningInput, VerifyWith};
+/// use jwts::jws::alg::HS256;
+///
+/// let input = jws::signing_input(Header::default().with_algorithm::<HS256>(), &Claims::default()).unwrap();
+/// let signature = HS256::sign(&input, b"secret").unwrap(); // e.g. returned by a KMS
+/// let input = SigningInput::new(&input);
+/// let token = jws::assemble(input.header(), input.payload(), &signature).unwrap();
+/// jws::decode::<Claims>(&token, VerifyWith::<HS256>(b"secret")).unwrap();
+/// ```
+pub fn assemble(header: &str, payload: &str, signature: &[u8]) -> Result<String, Error> {
+    let header_bytes = bs64::to_bytes(header).map_err(|e| Error::base64(Segment::Header, e))?;
+    json::from_slice::<Header>(&header_bytes).map_err(|e| Error::json(Segment::Header, e))?;
+    bs64::to_bytes(payload).map_err(|e| Error::base64(Segment::Payload, e))?;
 
-    Ok([f2s, trd].join("."))
+    let mut token = String::with_capacity(header.len() + 1 + payload.len() + 1 + bs64::encoded_len(signature.len()));
+    token.push_str(header);
+    token.push('.');
+    token.push_str(payload);
+    token.push('.');
+    bs64::encode_into(signature, &mut token);
+    Ok(token)
 }
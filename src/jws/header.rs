@@ -1,8 +1,11 @@
 //! Header
 
 use serde_derive::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
-use crate::jws::Algorithm;
+use crate::bs64;
+use crate::jwk::Jwk;
+use crate::jws::{Alg, Algorithm};
 
 /// Registered Header Parameter Names, see https://tools.ietf.org/html/rfc7515#section-4.1
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
@@ -12,13 +15,16 @@ pub struct Header {
     pub typ: Option<String>,
     /// Algorithm
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub alg: Option<String>,
+    pub alg: Option<Alg>,
     /// Content type
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cty: Option<String>,
     /// JSON Key URL
     #[serde(skip_serializing_if = "Option::is_none")]
     pub jku: Option<String>,
+    /// JSON Web Key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jwk: Option<Jwk>,
     /// Key ID
     #[serde(skip_serializing_if = "Option::is_none")]
     pub kid: Option<String>,
@@ -28,6 +34,18 @@ pub struct Header {
     /// X.509 certificate thumbprint
     #[serde(skip_serializing_if = "Option::is_none")]
     pub x5t: Option<String>,
+    /// X.509 certificate SHA-256 thumbprint
+    #[serde(rename = "x5t#S256", skip_serializing_if = "Option::is_none")]
+    pub x5t_s256: Option<String>,
+    /// X.509 certificate chain
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x5c: Option<Vec<String>>,
+    /// Critical
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crit: Option<Vec<String>>,
+    /// Additional parameters, either unregistered or private
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 impl Header {
@@ -39,19 +57,117 @@ impl Header {
             alg: None,
             cty: None,
             jku: None,
+            jwk: None,
             kid: None,
             x5u: None,
             x5t: None,
+            x5t_s256: None,
+            x5c: None,
+            crit: None,
+            extra: Map::new(),
         }
     }
 
     #[inline]
     pub fn with_algorithm<A: Algorithm>(self) -> Self {
         Header {
-            alg: Some(A::name().to_owned()),
+            alg: Some(Alg::from(A::name())),
+            ..self
+        }
+    }
+
+    #[inline]
+    pub fn with_typ(self, typ: impl Into<String>) -> Self {
+        Header {
+            typ: Some(typ.into()),
             ..self
         }
     }
+
+    #[inline]
+    pub fn with_cty(self, cty: impl Into<String>) -> Self {
+        Header {
+            cty: Some(cty.into()),
+            ..self
+        }
+    }
+
+    #[inline]
+    pub fn with_jku(self, jku: impl Into<String>) -> Self {
+        Header {
+            jku: Some(jku.into()),
+            ..self
+        }
+    }
+
+    #[inline]
+    pub fn with_jwk(self, jwk: Jwk) -> Self {
+        Header {
+            jwk: Some(jwk),
+            ..self
+        }
+    }
+
+    #[inline]
+    pub fn with_kid(self, kid: impl Into<String>) -> Self {
+        Header {
+            kid: Some(kid.into()),
+            ..self
+        }
+    }
+
+    #[inline]
+    pub fn with_x5u(self, x5u: impl Into<String>) -> Self {
+        Header {
+            x5u: Some(x5u.into()),
+            ..self
+        }
+    }
+
+    #[inline]
+    pub fn with_x5t(self, x5t: impl Into<String>) -> Self {
+        Header {
+            x5t: Some(x5t.into()),
+            ..self
+        }
+    }
+
+    #[inline]
+    pub fn with_x5t_s256(self, x5t_s256: impl Into<String>) -> Self {
+        Header {
+            x5t_s256: Some(x5t_s256.into()),
+            ..self
+        }
+    }
+
+    #[inline]
+    pub fn with_x5c(self, x5c: Vec<String>) -> Self {
+        Header {
+            x5c: Some(x5c),
+            ..self
+        }
+    }
+
+    /// Set `x5c` from DER encoded certificates, the first one contains the signing key.
+    #[inline]
+    pub fn with_x5c_der(self, chain: &[impl AsRef<[u8]>]) -> Self {
+        self.with_x5c(chain.iter().map(bs64::std_from_bytes).collect())
+    }
+
+    #[inline]
+    pub fn with_crit(self, crit: Vec<String>) -> Self {
+        Header {
+            crit: Some(crit),
+            ..self
+        }
+    }
+
+    /// Set an additional parameter, which is kept in `extra`.
+    #[inline]
+    pub fn with_param(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.extra.insert(name.into(), value.into());
+        self
+    }
 }
 
 impl Default for Header {
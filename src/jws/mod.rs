@@ -1,11 +1,18 @@
 //! JSON Web Signature, see https://tools.ietf.org/html/rfc7515
 
-pub use self::alg::Algorithm;
-pub use self::decode::{decode, NoVerify, Token, Verify, VerifyWith};
-pub use self::encode::encode;
+pub use self::alg::{Alg, Algorithm};
+pub use self::decode::{decode, decode_async, decode_bytes, decode_header, decode_raw, decode_split, DangerousNoVerify, DecodeContext, LenientEcdsa, RawHeader, RawPayload, RawSegment, RawSignature, SigningInput, Token, Verify, VerifyWith, VerifyWithAnyKey, VerifyWithKey, VerifyWithEmbeddedJwk};
+#[cfg(feature = "alg-rs")]
+pub use self::decode::VerifyWithRsaKeySize;
+pub use self::encode::{assemble, encode, signing_input, Encoder};
 pub use self::header::Header;
+pub use self::signature::Signature;
 
 pub mod alg;
+pub mod codec;
+#[cfg(feature = "alg-hs")]
+pub mod stream;
 mod decode;
 mod encode;
 mod header;
+mod signature;
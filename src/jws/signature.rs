@@ -0,0 +1,107 @@
+//! Signature
+
+use std::fmt::{Debug, Formatter};
+use std::ops::Deref;
+
+/// Signatures up to this length are stored inline, covering the HMAC, ECDSA and EdDSA algorithms.
+const INLINE_LEN: usize = 96;
+
+/// The signature bytes of a token, stored inline unless longer than any non-RSA signature.
+#[derive(Clone)]
+pub struct Signature(Repr);
+
+#[derive(Clone)]
+enum Repr {
+    Inline { len: u8, buf: [u8; INLINE_LEN] },
+    Heap(Vec<u8>),
+}
+
+impl Signature {
+    /// The signature bytes.
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        match &self.0 {
+            Repr::Inline { len, buf } => &buf[..*len as usize],
+            Repr::Heap(vec) => vec,
+        }
+    }
+
+    /// Convert to a `Vec<u8>`.
+    #[inline]
+    pub fn into_vec(self) -> Vec<u8> {
+        match self.0 {
+            Repr::Inline { .. } => self.as_slice().to_vec(),
+            Repr::Heap(vec) => vec,
+        }
+    }
+}
+
+impl Deref for Signature {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl AsRef<[u8]> for Signature {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl From<&[u8]> for Signature {
+    fn from(bytes: &[u8]) -> Self {
+        if bytes.len() <= INLINE_LEN {
+            let mut buf = [0; INLINE_LEN];
+            buf[..bytes.len()].copy_from_slice(bytes);
+            Signature(Repr::Inline { len: bytes.len() as u8, buf })
+        } else {
+            Signature(Repr::Heap(bytes.to_vec()))
+        }
+    }
+}
+
+impl From<Vec<u8>> for Signature {
+    #[inline]
+    fn from(vec: Vec<u8>) -> Self {
+        if vec.len() <= INLINE_LEN {
+            Signature::from(vec.as_slice())
+        } else {
+            Signature(Repr::Heap(vec))
+        }
+    }
+}
+
+impl From<Signature> for Vec<u8> {
+    #[inline]
+    fn from(signature: Signature) -> Self {
+        signature.into_vec()
+    }
+}
+
+impl PartialEq for Signature {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl Eq for Signature {}
+
+impl PartialEq<[u8]> for Signature {
+    #[inline]
+    fn eq(&self, other: &[u8]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+/// The first bytes and the length only, see `redact::Reveal` to print all bytes.
+impl Debug for Signature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let hex: String = self.iter().take(4).map(|x| format!("{:02x}", x)).collect();
+        write!(f, "Signature({}.., {} bytes)", hex, self.len())
+    }
+}
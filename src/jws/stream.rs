@@ -0,0 +1,102 @@
+//! Streaming sign and verify of detached payloads
+//!
+//! The payload is read incrementally and never held in memory as a whole, the token is in the
+//! detached form of RFC 7515 Appendix F, `header..signature`, the payload travelling separately.
+//! Only the HMAC algorithms can compute the signature incrementally.
+
+use std::io::Read;
+
+use serde_json as json;
+
+use crate::bs64;
+use crate::crypto::{Hash, HmacContext};
+use crate::error::{Error, Segment};
+use crate::jws::{Algorithm, Header};
+use crate::jws::alg::{HS256, HS384, HS512};
+
+/// Size of the chunks read from the payload, a multiple of 3 to encode as base64 without padding.
+const CHUNK_LEN: usize = 3 * 1024;
+
+/// An algorithm able to compute the signature incrementally.
+pub trait StreamAlgorithm: Algorithm<SignKey=[u8], VerifyKey=[u8]> {
+    /// Start an incremental computation with the key.
+    fn context(key: &[u8]) -> HmacContext;
+}
+
+impl StreamAlgorithm for HS256 {
+    #[inline]
+    fn context(key: &[u8]) -> HmacContext {
+        HmacContext::new(Hash::Sha256, key)
+    }
+}
+
+impl StreamAlgorithm for HS384 {
+    #[inline]
+    fn context(key: &[u8]) -> HmacContext {
+        HmacContext::new(Hash::Sha384, key)
+    }
+}
+
+impl StreamAlgorithm for HS512 {
+    #[inline]
+    fn context(key: &[u8]) -> HmacContext {
+        HmacContext::new(Hash::Sha512, key)
+    }
+}
+
+/// Sign the payload read from the reader, return the detached token `header..signature`.
+pub fn sign_detached<A: StreamAlgorithm>(header: Header, payload: impl Read, key: &[u8]) -> Result<String, Error> {
+    let header = json::to_vec(&header.with_algorithm::<A>()).map_err(|e| Error::json(Segment::Header, e))?;
+    let mut token = bs64::from_bytes(header);
+    let signature = sign_stream::<A>(&token, payload, key)?;
+    token.push_str("..");
+    bs64::encode_into(signature, &mut token);
+    Ok(token)
+}
+
+/// Verify the detached token `header..signature` against the payload read from the reader,
+/// return the header.
+pub fn verify_detached<A: StreamAlgorithm>(token: &str, payload: impl Read, key: &[u8]) -> Result<Header, Error> {
+    let (header, signature) = token.split_once("..").ok_or(Error::Malformed)?;
+    if signature.contains('.') {
+        return Err(Error::Malformed);
+    }
+    let signature = bs64::to_bytes(signature).map_err(|e| Error::base64(Segment::Signature, e))?;
+    let decoded = bs64::to_bytes(header).map_err(|e| Error::base64(Segment::Header, e))?;
+    let decoded: Header = json::from_slice(&decoded).map_err(|e| Error::json(Segment::Header, e))?;
+
+    let expect = sign_stream::<A>(header, payload, key)?;
+    let diff = expect.iter().zip(&signature).fold(0, |acc, (x, y)| acc | (x ^ y));
+    (expect.len() == signature.len() && diff == 0)
+        .then_some(decoded)
+        .ok_or(Error::InvalidSignature)
+}
+
+/// Sign `header.base64url(payload)` with the base64url encoded header.
+fn sign_stream<A: StreamAlgorithm>(header: &str, mut payload: impl Read, key: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut context = A::context(key);
+    context.update(header.as_bytes());
+    context.update(b".");
+
+    let mut buf = vec![0; CHUNK_LEN];
+    let mut encoded = String::with_capacity(bs64::encoded_len(CHUNK_LEN));
+    let mut filled = 0;
+    loop {
+        let n = payload.read(&mut buf[filled..]).map_err(Error::io)?;
+        if n == 0 {
+            break;
+        }
+        filled += n;
+        let whole = filled / 3 * 3;
+        encoded.clear();
+        bs64::encode_into(&buf[..whole], &mut encoded);
+        context.update(encoded.as_bytes());
+        buf.copy_within(whole..filled, 0);
+        filled -= whole;
+    }
+    encoded.clear();
+    bs64::encode_into(&buf[..filled], &mut encoded);
+    context.update(encoded.as_bytes());
+
+    Ok(context.sign())
+}
@@ -0,0 +1,781 @@
+//! Parsed verification keys
+//!
+//! The public key types are parsed by the crypto backend once when constructed, so a bad key is
+//! reported up front instead of as a failed verification, and the parsed key is reused by every
+//! verification through `VerifyWithKey`. They can be shared across threads, and deref to the raw
+//! bytes expected as the `VerifyKey` of the builtin algorithms.
+//!
+//! ```rust
+//! use jwts::{Claims, jws};
+//! use jwts::jws::{Header, VerifyWithKey};
+//! use jwts::jws::alg::Ed25519;
+//! use jwts::crypto::Ed25519KeyPair;
+//! use jwts::key::Ed25519PublicKey;
+//!
+//! let key_pair = Ed25519KeyPair::from_pkcs8(include_bytes!("../tests/eddsa-pri-rfc5958.pk8")).unwrap();
+//! let token = jws::encode::<Ed25519>(Header::default(), &Claims::default(), &key_pair).unwrap();
+//!
+//! let key = Ed25519PublicKey::from_bytes(include_bytes!("../tests/eddsa-pub.der")).unwrap();
+//! jws::decode::<Claims>(&token, VerifyWithKey::<Ed25519, _>::new(&key)).unwrap();
+//! ```
+//!
+//! `load_sign_key` and `load_verify_key` read a key file of any supported format and key type,
+//! e.g. to bootstrap an application from configured paths:
+//!
+//! ```rust
+//! use jwts::key::{self, AnyVerifyKey};
+//!
+//! let key = key::load_verify_key("tests/rsa-pub.pem").unwrap();
+//! assert!(matches!(key, AnyVerifyKey::Rsa(_)));
+//! assert_eq!(key.kty(), "RSA");
+//! ```
+
+use std::fmt::{Debug, Formatter};
+use std::fs;
+use std::ops::Deref;
+use std::path::Path;
+
+use serde_json as json;
+use serde_json::Value;
+
+use crate::{bs64, crypto, der, pem};
+#[cfg(feature = "alg-es")]
+use crate::crypto::Curve;
+#[cfg(feature = "alg-eddsa")]
+use crate::crypto::Ed25519KeyPair;
+#[cfg(feature = "alg-rs")]
+use crate::crypto::RsaKeyPair;
+use crate::error::Error;
+use crate::jwk::Jwk;
+use crate::jws::Alg;
+use crate::redact::Redacted;
+
+/// An HMAC secret for the HS algorithms, wiped on drop with the feature `zeroize`.
+#[derive(Clone, Eq, PartialEq)]
+pub struct HsSecret(Vec<u8>);
+
+/// An RSA public key for the RS and PS algorithms, parsed from a DER-encoded RSAPublicKey
+/// (PKCS#1) which it derefs to.
+#[derive(Clone)]
+pub struct RsaPublicKey {
+    der: Vec<u8>,
+    #[cfg(feature = "alg-rs")]
+    parsed: crypto::ParsedRsaKey,
+}
+
+/// The accepted sizes of RSA moduli in bits, 2048 to 8192 by default as required by RFC 7518
+/// section 3.3.
+///
+/// Widening the range, e.g. for tokens signed by legacy 1024-bit keys, is an explicit choice of
+/// the integrator, see `VerifyWithRsaKeySize`. Keys outside the range are rejected with
+/// `Error::RsaKeySize` naming the range. ring and aws-lc-rs verify keys under 2048 bits with
+/// RS256 and RS512 only, and no keys over 8192 bits.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct RsaKeySize {
+    pub min_bits: usize,
+    pub max_bits: usize,
+}
+
+impl RsaKeySize {
+    /// 2048 to 8192 bits.
+    pub const DEFAULT: RsaKeySize = RsaKeySize { min_bits: 2048, max_bits: 8192 };
+
+    #[inline]
+    pub const fn new(min_bits: usize, max_bits: usize) -> Self {
+        RsaKeySize { min_bits, max_bits }
+    }
+
+    /// Check the bit length of a modulus.
+    pub fn check(&self, bits: usize) -> Result<(), Error> {
+        if (self.min_bits..=self.max_bits).contains(&bits) {
+            Ok(())
+        } else {
+            Err(Error::RsaKeySize { bits, min: self.min_bits, max: self.max_bits })
+        }
+    }
+}
+
+impl Default for RsaKeySize {
+    #[inline]
+    fn default() -> Self {
+        RsaKeySize::DEFAULT
+    }
+}
+
+/// A P-256 or P-384 public key for the ES algorithms, parsed from an uncompressed point which
+/// it derefs to.
+#[derive(Clone)]
+pub struct EcPublicKey {
+    point: Vec<u8>,
+    #[cfg(feature = "alg-es")]
+    parsed: crypto::ParsedEcKey,
+}
+
+/// An Ed25519 public key for the EdDSA algorithm, parsed from the 32 bytes which it derefs to.
+#[derive(Clone)]
+pub struct Ed25519PublicKey {
+    bytes: [u8; 32],
+    #[cfg(feature = "alg-eddsa")]
+    parsed: crypto::ParsedEd25519Key,
+}
+
+/// A P-256 or P-384 private key for the ES algorithms, as DER-encoded PKCS#8, wiped on drop with
+/// the feature `zeroize`.
+#[derive(Clone, Eq, PartialEq)]
+pub struct EcPrivateKey(Vec<u8>, &'static str);
+
+/// A signing key of the type found by `load_sign_key`.
+#[derive(Debug)]
+pub enum AnySignKey {
+    /// An HMAC secret for the HS algorithms
+    Hs(HsSecret),
+    /// An RSA private key for the RS and PS algorithms, boxed as it is much larger than the others
+    /// with some backends
+    #[cfg(feature = "alg-rs")]
+    Rsa(Box<RsaKeyPair>),
+    /// An EC private key for the ES algorithms
+    Ec(EcPrivateKey),
+    /// An Ed25519 private key for the EdDSA algorithm
+    #[cfg(feature = "alg-eddsa")]
+    Ed25519(Ed25519KeyPair),
+}
+
+/// A verification key of the type found by `load_verify_key`, deref to the `VerifyKey` of the
+/// builtin algorithms.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum AnyVerifyKey {
+    /// An HMAC secret for the HS algorithms
+    Hs(HsSecret),
+    /// An RSA public key for the RS and PS algorithms
+    Rsa(RsaPublicKey),
+    /// An EC public key for the ES algorithms
+    Ec(EcPublicKey),
+    /// An Ed25519 public key for the EdDSA algorithm
+    Ed25519(Ed25519PublicKey),
+}
+
+const FOUND_PUBLIC: Error = Error::InvalidKey("expected a private key, found a public key");
+const FOUND_PRIVATE: Error = Error::InvalidKey("expected a public key, found a private key");
+
+/// Load the signing key of a file: a PEM or DER of PKCS#8, PKCS#1 RSA or SEC1 EC, or a private JWK.
+///
+/// A raw HMAC secret can not be told apart from a malformed key, read it with `HsSecret::new`.
+pub fn load_sign_key(path: impl AsRef<Path>) -> Result<AnySignKey, Error> {
+    let mut bytes = fs::read(path).map_err(Error::io)?;
+    let key = AnySignKey::parse(&bytes);
+    crypto::wipe(&mut bytes);
+    key
+}
+
+/// Load the verification key of a file: a PEM or DER of SubjectPublicKeyInfo, PKCS#1 RSA or an
+/// X.509 certificate, or a public JWK.
+pub fn load_verify_key(path: impl AsRef<Path>) -> Result<AnyVerifyKey, Error> {
+    let bytes = fs::read(path).map_err(Error::io)?;
+    AnyVerifyKey::parse(&bytes)
+}
+
+impl HsSecret {
+    /// Take the raw bytes of the secret.
+    #[inline]
+    pub fn new(bytes: Vec<u8>) -> Self {
+        HsSecret(bytes)
+    }
+
+    /// Decode a secret handed out as base64 or base64url, padded or not, as by Auth0, instead of
+    /// using the ASCII of the encoded string as the secret.
+    pub fn from_base64(encoded: &str) -> Result<Self, Error> {
+        bs64::any_to_bytes(encoded.trim())
+            .map(HsSecret)
+            .map_err(|_| Error::InvalidKey("HMAC secret is not valid base64"))
+    }
+}
+
+impl Drop for HsSecret {
+    #[inline]
+    fn drop(&mut self) {
+        crypto::wipe(&mut self.0)
+    }
+}
+
+impl Debug for HsSecret {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("HsSecret").field(&Redacted).finish()
+    }
+}
+
+impl Drop for EcPrivateKey {
+    #[inline]
+    fn drop(&mut self) {
+        crypto::wipe(&mut self.0)
+    }
+}
+
+impl Debug for EcPrivateKey {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EcPrivateKey").field("curve", &self.1).finish_non_exhaustive()
+    }
+}
+
+impl RsaPublicKey {
+    /// Parse a DER-encoded RSAPublicKey (PKCS#1).
+    #[inline]
+    pub fn from_pkcs1_der(der: &[u8]) -> Result<Self, Error> {
+        Self::from_pkcs1_der_sized(der, RsaKeySize::DEFAULT)
+    }
+
+    /// Parse a DER-encoded RSAPublicKey (PKCS#1) with a modulus of the accepted size.
+    pub fn from_pkcs1_der_sized(der: &[u8], size: RsaKeySize) -> Result<Self, Error> {
+        let (key, rest) = der::read(der, der::SEQUENCE)?;
+        let (n, key) = der::read(key, der::INTEGER)?;
+        let (e, key) = der::read(key, der::INTEGER)?;
+        if !rest.is_empty() || !key.is_empty() {
+            return Err(Error::InvalidKey("malformed RSA public key"));
+        }
+        RsaPublicKey::new(der.to_owned(), n, e, size)
+    }
+
+    /// Parse a DER-encoded SubjectPublicKeyInfo holding an RSA key.
+    #[inline]
+    pub fn from_spki_der(spki: &[u8]) -> Result<Self, Error> {
+        Self::from_pkcs1_der(der::spki_public_key(spki)?)
+    }
+
+    /// Parse a DER-encoded SubjectPublicKeyInfo holding an RSA key with a modulus of the
+    /// accepted size.
+    #[inline]
+    pub fn from_spki_der_sized(spki: &[u8], size: RsaKeySize) -> Result<Self, Error> {
+        Self::from_pkcs1_der_sized(der::spki_public_key(spki)?, size)
+    }
+
+    /// Build from the big-endian modulus and public exponent.
+    #[inline]
+    pub fn from_components(n: &[u8], e: &[u8]) -> Result<Self, Error> {
+        Self::from_components_sized(n, e, RsaKeySize::DEFAULT)
+    }
+
+    /// Build from the big-endian modulus and public exponent, with a modulus of the accepted size.
+    pub fn from_components_sized(n: &[u8], e: &[u8], size: RsaKeySize) -> Result<Self, Error> {
+        let components = [der::write_uint(n), der::write_uint(e)].concat();
+        RsaPublicKey::new(der::write(der::SEQUENCE, &components), n, e, size)
+    }
+
+    /// The big-endian modulus and public exponent without leading zeros, e.g. to store the key
+    /// for `from_components`.
+    pub fn components(&self) -> (&[u8], &[u8]) {
+        let read = |x| der::read(x, der::INTEGER).expect("checked on construction");
+        let (key, _) = der::read(&self.der, der::SEQUENCE).expect("checked on construction");
+        let (n, key) = read(key);
+        let (e, _) = read(key);
+        (strip_leading_zeros(n), strip_leading_zeros(e))
+    }
+
+    /// Verify the signature of the data by an RS or PS algorithm with the parsed key.
+    #[cfg(feature = "alg-rs")]
+    #[inline]
+    pub fn verify(&self, alg: &Alg, data: &[u8], sig: &[u8]) -> Result<(), Error> {
+        let (padding, hash) = alg.rsa_params()?;
+        self.parsed.verify(padding, hash, data, sig)
+    }
+
+    fn new(der: Vec<u8>, n: &[u8], e: &[u8], size: RsaKeySize) -> Result<Self, Error> {
+        check_rsa_components(n, e, size)?;
+        Ok(RsaPublicKey {
+            #[cfg(feature = "alg-rs")]
+            parsed: crypto::ParsedRsaKey::new(strip_leading_zeros(n), strip_leading_zeros(e), der::uint_bits(n))?,
+            der,
+        })
+    }
+}
+
+impl EcPublicKey {
+    /// Parse an uncompressed SEC1 point, the curve is determined by its length.
+    pub fn from_sec1(point: &[u8]) -> Result<Self, Error> {
+        #[cfg(feature = "alg-es")]
+        let curve = match point.len() {
+            65 => Curve::P256,
+            _ => Curve::P384,
+        };
+        match point {
+            [0x04, ..] if point.len() == 65 || point.len() == 97 => Ok(EcPublicKey {
+                #[cfg(feature = "alg-es")]
+                parsed: crypto::ParsedEcKey::new(curve, point)?,
+                point: point.to_owned(),
+            }),
+            _ => Err(Error::InvalidKey("EC point must be uncompressed P-256 or P-384")),
+        }
+    }
+
+    /// Parse a DER-encoded SubjectPublicKeyInfo holding an EC key.
+    #[inline]
+    pub fn from_spki_der(spki: &[u8]) -> Result<Self, Error> {
+        Self::from_sec1(der::spki_public_key(spki)?)
+    }
+
+    /// The JWK name of the curve, `P-256` or `P-384`.
+    #[inline]
+    pub fn curve(&self) -> &'static str {
+        if self.point.len() == 65 { "P-256" } else { "P-384" }
+    }
+
+    /// Verify the signature of the data by the ES algorithm of the curve with the parsed key.
+    #[cfg(feature = "alg-es")]
+    pub fn verify(&self, alg: &Alg, data: &[u8], sig: &[u8]) -> Result<(), Error> {
+        match (alg, self.curve()) {
+            (Alg::ES256, "P-256") | (Alg::ES384, "P-384") => self.parsed.verify(data, sig),
+            _ => Err(Error::Rejected("algorithm does not match the key")),
+        }
+    }
+}
+
+impl EcPrivateKey {
+    /// Parse a DER-encoded PKCS#8 PrivateKeyInfo holding an EC key.
+    pub fn from_pkcs8(pkcs8: &[u8]) -> Result<Self, Error> {
+        let (info, _) = der::read(pkcs8, der::SEQUENCE)?;
+        let (_version, rest) = der::read(info, der::INTEGER)?;
+        let (algorithm, _) = der::read(rest, der::SEQUENCE)?;
+        let curve = algorithm.strip_prefix(der::EC_OID).ok_or(Error::InvalidKey("not an EC key"))?;
+        let key = EcPrivateKey(pkcs8.to_owned(), curve_name(curve)?);
+        #[cfg(feature = "alg-es")]
+        crypto::ecdsa_public_point(key.curve_id(), &key.0)?;
+        Ok(key)
+    }
+
+    /// Parse a DER-encoded ECPrivateKey (SEC1) naming its curve and holding its public key.
+    pub fn from_sec1(sec1: &[u8]) -> Result<Self, Error> {
+        let (key, _) = der::read(sec1, der::SEQUENCE)?;
+        let (_version, rest) = der::read(key, der::INTEGER)?;
+        let (d, mut rest) = der::read(rest, der::OCTET_STRING)?;
+        let (mut curve, mut point) = (None, None);
+        while !rest.is_empty() {
+            let (tag, contents, next) = der::read_any(rest)?;
+            match tag {
+                der::CONTEXT_0 => curve = Some(contents),
+                der::CONTEXT_1 => point = Some(der::read(contents, der::BIT_STRING)?.0),
+                _ => {}
+            }
+            rest = next;
+        }
+        let curve = curve.ok_or(Error::InvalidKey("EC private key without curve"))?;
+        let point = point.and_then(|x| x.strip_prefix(&[0])).ok_or(Error::InvalidKey("EC private key without public key"))?;
+        Self::from_components(curve, d, point)
+    }
+
+    /// The JWK name of the curve, `P-256` or `P-384`.
+    #[inline]
+    pub fn curve(&self) -> &'static str {
+        self.1
+    }
+
+    #[cfg(feature = "alg-es")]
+    #[inline]
+    fn curve_id(&self) -> Curve {
+        if self.1 == "P-256" { Curve::P256 } else { Curve::P384 }
+    }
+
+    /// Wrap the private scalar and the public point in PKCS#8 with the public key, as ring takes.
+    fn from_components(curve: &[u8], d: &[u8], point: &[u8]) -> Result<Self, Error> {
+        let mut ec_private_key = der::write(der::SEQUENCE, &[
+            der::write(der::INTEGER, &[1]),
+            der::write(der::OCTET_STRING, d),
+            der::write(der::CONTEXT_1, &der::write(der::BIT_STRING, &[&[0], point].concat())),
+        ].concat());
+        let mut pkcs8 = der::write(der::SEQUENCE, &[
+            der::write(der::INTEGER, &[0]),
+            der::write(der::SEQUENCE, &[der::EC_OID, curve].concat()),
+            der::write(der::OCTET_STRING, &ec_private_key),
+        ].concat());
+        let key = Self::from_pkcs8(&pkcs8);
+        crypto::wipe(&mut ec_private_key);
+        crypto::wipe(&mut pkcs8);
+        key
+    }
+}
+
+impl AnySignKey {
+    /// Parse a signing key of any supported format, see `load_sign_key`.
+    pub fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        if let Some(jwk) = parse_jwk(bytes)? {
+            return Self::from_jwk(&jwk);
+        }
+        let Some((label, mut der)) = parse_pem(bytes)? else { return Self::from_der(bytes) };
+        let key = match label {
+            "PRIVATE KEY" | "RSA PRIVATE KEY" | "EC PRIVATE KEY" => Self::from_der(&der),
+            "ENCRYPTED PRIVATE KEY" => Err(Error::InvalidKey("encrypted private keys are not supported")),
+            "PUBLIC KEY" | "RSA PUBLIC KEY" | "CERTIFICATE" => Err(FOUND_PUBLIC),
+            _ => Err(Error::InvalidKey("unsupported PEM label")),
+        };
+        crypto::wipe(&mut der);
+        key
+    }
+
+    /// Parse a DER-encoded PKCS#8 PrivateKeyInfo, RSAPrivateKey (PKCS#1) or ECPrivateKey (SEC1).
+    pub fn from_der(der: &[u8]) -> Result<Self, Error> {
+        let (key, _) = der::read(der, der::SEQUENCE)?;
+        let rest = match der::read_any(key)? {
+            (der::INTEGER, _, rest) => rest,
+            (der::SEQUENCE, ..) => return Err(FOUND_PUBLIC),
+            _ => return Err(Error::InvalidKey("unrecognized private key")),
+        };
+        match der::read_any(rest)? {
+            (der::SEQUENCE, algorithm, _) if algorithm.starts_with(der::RSA_OID) => rsa_pkcs8(der),
+            (der::SEQUENCE, algorithm, _) if algorithm.starts_with(der::EC_OID) => EcPrivateKey::from_pkcs8(der).map(AnySignKey::Ec),
+            (der::SEQUENCE, algorithm, _) if algorithm == der::ED25519_OID => ed25519_pkcs8(der),
+            (der::SEQUENCE, ..) => Err(Error::InvalidKey("unsupported key algorithm")),
+            (der::INTEGER, ..) => rsa_pkcs1(der),
+            (der::OCTET_STRING, ..) => EcPrivateKey::from_sec1(der).map(AnySignKey::Ec),
+            _ => Err(Error::InvalidKey("unrecognized private key")),
+        }
+    }
+
+    /// Convert a private JWK of the types "RSA", "EC", "OKP" or "oct".
+    pub fn from_jwk(jwk: &Jwk) -> Result<Self, Error> {
+        let private = |name| jwk.extra.get(name)
+            .and_then(Value::as_str)
+            .ok_or(Error::InvalidKey("missing private key parameter"))
+            .and_then(jwk_param);
+        let public = |x: &Option<String>| x.as_deref().ok_or(Error::InvalidKey("missing key parameter")).and_then(jwk_param);
+        match jwk.kty.as_str() {
+            "oct" => public(&jwk.k).map(|k| AnySignKey::Hs(HsSecret(k))),
+            "RSA" => {
+                let mut params = vec![public(&jwk.n)?, public(&jwk.e)?];
+                for name in ["d", "p", "q", "dp", "dq", "qi"] {
+                    params.push(private(name)?);
+                }
+                let mut components = der::write(der::INTEGER, &[0]);
+                for param in &mut params {
+                    components.extend(der::write_uint(param));
+                    crypto::wipe(param);
+                }
+                let mut pkcs1 = der::write(der::SEQUENCE, &components);
+                crypto::wipe(&mut components);
+                let key = rsa_pkcs1(&pkcs1);
+                crypto::wipe(&mut pkcs1);
+                key
+            }
+            "EC" => {
+                let curve = match jwk.crv.as_deref() {
+                    Some("P-256") => der::P256_OID,
+                    Some("P-384") => der::P384_OID,
+                    _ => return Err(Error::InvalidKey("EC key must be P-256 or P-384")),
+                };
+                let point = [&[0x04], &public(&jwk.x)?[..], &public(&jwk.y)?[..]].concat();
+                let mut d = private("d")?;
+                let key = EcPrivateKey::from_components(curve, &d, &point);
+                crypto::wipe(&mut d);
+                key.map(AnySignKey::Ec)
+            }
+            "OKP" if jwk.crv.as_deref() == Some("Ed25519") => {
+                let mut d = private("d")?;
+                let mut pkcs8 = der::write(der::SEQUENCE, &[
+                    der::write(der::INTEGER, &[1]),
+                    der::write(der::SEQUENCE, der::ED25519_OID),
+                    der::write(der::OCTET_STRING, &der::write(der::OCTET_STRING, &d)),
+                    der::write(der::IMPLICIT_1, &[&[0], &public(&jwk.x)?[..]].concat()),
+                ].concat());
+                let key = ed25519_pkcs8(&pkcs8);
+                crypto::wipe(&mut d);
+                crypto::wipe(&mut pkcs8);
+                key
+            }
+            "OKP" => Err(Error::InvalidKey("OKP key must be Ed25519")),
+            _ => Err(Error::InvalidKey("unsupported key type")),
+        }
+    }
+
+    /// The JWK key type, "oct", "RSA", "EC" or "OKP".
+    pub fn kty(&self) -> &'static str {
+        match self {
+            AnySignKey::Hs(_) => "oct",
+            #[cfg(feature = "alg-rs")]
+            AnySignKey::Rsa(_) => "RSA",
+            AnySignKey::Ec(_) => "EC",
+            #[cfg(feature = "alg-eddsa")]
+            AnySignKey::Ed25519(_) => "OKP",
+        }
+    }
+}
+
+impl AnyVerifyKey {
+    /// Parse a verification key of any supported format, see `load_verify_key`.
+    pub fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        if let Some(jwk) = parse_jwk(bytes)? {
+            return Self::from_jwk(&jwk);
+        }
+        let Some((label, der)) = parse_pem(bytes)? else { return Self::from_der(bytes) };
+        match label {
+            "PUBLIC KEY" | "RSA PUBLIC KEY" | "CERTIFICATE" => Self::from_der(&der),
+            "PRIVATE KEY" | "RSA PRIVATE KEY" | "EC PRIVATE KEY" | "ENCRYPTED PRIVATE KEY" => Err(FOUND_PRIVATE),
+            _ => Err(Error::InvalidKey("unsupported PEM label")),
+        }
+    }
+
+    /// Parse a DER-encoded SubjectPublicKeyInfo, RSAPublicKey (PKCS#1) or X.509 certificate.
+    pub fn from_der(der: &[u8]) -> Result<Self, Error> {
+        let (key, _) = der::read(der, der::SEQUENCE)?;
+        match der::read_any(key)? {
+            (der::INTEGER, _, rest) => match der::read_any(rest)? {
+                (der::INTEGER, _, []) => RsaPublicKey::from_pkcs1_der(der).map(AnyVerifyKey::Rsa),
+                _ => Err(FOUND_PRIVATE),
+            },
+            (der::SEQUENCE, algorithm, _) if algorithm.first() == Some(&0x06) => Self::from_spki_der(der),
+            (der::SEQUENCE, ..) => Self::from_spki_der(der::certificate_spki(der)?),
+            _ => Err(Error::InvalidKey("unrecognized public key")),
+        }
+    }
+
+    /// Parse a DER-encoded SubjectPublicKeyInfo of an RSA, EC or Ed25519 key.
+    pub fn from_spki_der(spki: &[u8]) -> Result<Self, Error> {
+        let (info, _) = der::read(spki, der::SEQUENCE)?;
+        let (algorithm, _) = der::read(info, der::SEQUENCE)?;
+        if algorithm.starts_with(der::RSA_OID) {
+            RsaPublicKey::from_spki_der(spki).map(AnyVerifyKey::Rsa)
+        } else if algorithm.starts_with(der::EC_OID) {
+            EcPublicKey::from_spki_der(spki).map(AnyVerifyKey::Ec)
+        } else if algorithm == der::ED25519_OID {
+            Ed25519PublicKey::from_spki_der(spki).map(AnyVerifyKey::Ed25519)
+        } else {
+            Err(Error::InvalidKey("unsupported key algorithm"))
+        }
+    }
+
+    /// Convert a public JWK of the types "RSA", "EC", "OKP" or "oct".
+    pub fn from_jwk(jwk: &Jwk) -> Result<Self, Error> {
+        let key = jwk.to_verify_key()?;
+        match jwk.kty.as_str() {
+            "RSA" => RsaPublicKey::from_pkcs1_der(&key).map(AnyVerifyKey::Rsa),
+            "EC" => EcPublicKey::from_sec1(&key).map(AnyVerifyKey::Ec),
+            "OKP" if jwk.crv.as_deref() == Some("Ed25519") => Ed25519PublicKey::from_bytes(&key).map(AnyVerifyKey::Ed25519),
+            "OKP" => Err(Error::InvalidKey("OKP key must be Ed25519")),
+            _ => Ok(AnyVerifyKey::Hs(HsSecret(key))),
+        }
+    }
+
+    /// The JWK key type, "oct", "RSA", "EC" or "OKP".
+    pub fn kty(&self) -> &'static str {
+        match self {
+            AnyVerifyKey::Hs(_) => "oct",
+            AnyVerifyKey::Rsa(_) => "RSA",
+            AnyVerifyKey::Ec(_) => "EC",
+            AnyVerifyKey::Ed25519(_) => "OKP",
+        }
+    }
+}
+
+/// A verification key parsed once by the crypto backend, see `jws::VerifyWithKey`.
+pub trait ParsedKey {
+    /// Verify the signature of the data by the algorithm, rejected if it does not fit the key.
+    fn verify(&self, alg: &Alg, data: &[u8], sig: &[u8]) -> Result<(), Error>;
+}
+
+#[cfg(feature = "alg-rs")]
+impl ParsedKey for RsaPublicKey {
+    #[inline]
+    fn verify(&self, alg: &Alg, data: &[u8], sig: &[u8]) -> Result<(), Error> {
+        RsaPublicKey::verify(self, alg, data, sig)
+    }
+}
+
+#[cfg(feature = "alg-es")]
+impl ParsedKey for EcPublicKey {
+    #[inline]
+    fn verify(&self, alg: &Alg, data: &[u8], sig: &[u8]) -> Result<(), Error> {
+        EcPublicKey::verify(self, alg, data, sig)
+    }
+}
+
+#[cfg(feature = "alg-eddsa")]
+impl ParsedKey for Ed25519PublicKey {
+    #[inline]
+    fn verify(&self, alg: &Alg, data: &[u8], sig: &[u8]) -> Result<(), Error> {
+        Ed25519PublicKey::verify(self, alg, data, sig)
+    }
+}
+
+/// HMAC secrets are verified as their bytes.
+impl ParsedKey for AnyVerifyKey {
+    fn verify(&self, alg: &Alg, data: &[u8], sig: &[u8]) -> Result<(), Error> {
+        if alg.kty() != Some(self.kty()) {
+            return Err(Error::Rejected("algorithm does not match the key"));
+        }
+        match self {
+            #[cfg(feature = "alg-rs")]
+            AnyVerifyKey::Rsa(key) => key.verify(alg, data, sig),
+            #[cfg(feature = "alg-es")]
+            AnyVerifyKey::Ec(key) => key.verify(alg, data, sig),
+            #[cfg(feature = "alg-eddsa")]
+            AnyVerifyKey::Ed25519(key) => key.verify(alg, data, sig),
+            _ => alg.verify(data, sig, self),
+        }
+    }
+}
+
+impl Deref for AnyVerifyKey {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        match self {
+            AnyVerifyKey::Hs(x) => x,
+            AnyVerifyKey::Rsa(x) => x,
+            AnyVerifyKey::Ec(x) => x,
+            AnyVerifyKey::Ed25519(x) => x,
+        }
+    }
+}
+
+impl AsRef<[u8]> for AnyVerifyKey {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl Ed25519PublicKey {
+    /// Parse the 32-byte public key.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let bytes: [u8; 32] = bytes.try_into().map_err(|_| Error::InvalidKey("Ed25519 public key must be 32 bytes"))?;
+        Ok(Ed25519PublicKey {
+            #[cfg(feature = "alg-eddsa")]
+            parsed: crypto::ParsedEd25519Key::new(&bytes)?,
+            bytes,
+        })
+    }
+
+    /// Parse a DER-encoded SubjectPublicKeyInfo holding an Ed25519 key.
+    #[inline]
+    pub fn from_spki_der(spki: &[u8]) -> Result<Self, Error> {
+        Self::from_bytes(der::spki_public_key(spki)?)
+    }
+
+    /// Verify the signature of the data by EdDSA with the parsed key.
+    #[cfg(feature = "alg-eddsa")]
+    pub fn verify(&self, alg: &Alg, data: &[u8], sig: &[u8]) -> Result<(), Error> {
+        match alg {
+            Alg::EdDSA | Alg::Ed25519 => self.parsed.verify(data, sig),
+            _ => Err(Error::Rejected("algorithm does not match the key")),
+        }
+    }
+}
+
+macro_rules! impl_key_bytes {
+    ($($key:ident.$bytes:tt),*) => {$(
+        impl Deref for $key {
+            type Target = [u8];
+
+            #[inline]
+            fn deref(&self) -> &[u8] {
+                &self.$bytes
+            }
+        }
+
+        impl AsRef<[u8]> for $key {
+            #[inline]
+            fn as_ref(&self) -> &[u8] {
+                &self.$bytes
+            }
+        }
+    )*};
+}
+
+impl_key_bytes!(HsSecret.0, RsaPublicKey.der, EcPublicKey.point, EcPrivateKey.0, Ed25519PublicKey.bytes);
+
+macro_rules! impl_public_key {
+    ($($key:ident),*) => {$(
+        /// Compares the key material.
+        impl PartialEq for $key {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                **self == **other
+            }
+        }
+
+        impl Eq for $key {}
+
+        /// Shows the key material, not the parsed key.
+        impl Debug for $key {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                f.debug_tuple(stringify!($key)).field(&&**self).finish()
+            }
+        }
+    )*};
+}
+
+impl_public_key!(RsaPublicKey, EcPublicKey, Ed25519PublicKey);
+
+/// Check the RSA components against the limits accepted for verification.
+fn check_rsa_components(n: &[u8], e: &[u8], size: RsaKeySize) -> Result<(), Error> {
+    size.check(der::uint_bits(n))?;
+    let e = strip_leading_zeros(e);
+    let exponent = e.iter().fold(0u64, |acc, &x| acc << 8 | x as u64);
+    if e.len() > 5 || exponent < 3 || exponent % 2 == 0 {
+        return Err(Error::InvalidKey("invalid RSA public exponent"));
+    }
+    Ok(())
+}
+
+#[inline]
+fn strip_leading_zeros(bytes: &[u8]) -> &[u8] {
+    let skip = bytes.iter().take_while(|&&x| x == 0).count();
+    &bytes[skip..]
+}
+
+/// The label and the DER of the first PEM block, if the bytes are PEM.
+fn parse_pem(bytes: &[u8]) -> Result<Option<(&str, Vec<u8>)>, Error> {
+    let Ok(text) = std::str::from_utf8(bytes) else { return Ok(None) };
+    let Some((label, _)) = text.split_once("-----BEGIN ").and_then(|(_, x)| x.split_once("-----")) else { return Ok(None) };
+    let block = pem::decode(text, label)?.into_iter().next();
+    block.map(|x| Some((label, x))).ok_or(Error::InvalidKey("malformed PEM"))
+}
+
+/// The JWK, if the bytes are a JSON object.
+fn parse_jwk(bytes: &[u8]) -> Result<Option<Jwk>, Error> {
+    if bytes.trim_ascii_start().first() != Some(&b'{') {
+        return Ok(None);
+    }
+    json::from_slice(bytes).map(Some).map_err(|_| Error::InvalidKey("malformed JWK"))
+}
+
+#[inline]
+fn jwk_param(x: &str) -> Result<Vec<u8>, Error> {
+    bs64::to_bytes(x).map_err(|_| Error::InvalidKey("invalid key parameter"))
+}
+
+fn curve_name(oid: &[u8]) -> Result<&'static str, Error> {
+    match oid {
+        der::P256_OID => Ok("P-256"),
+        der::P384_OID => Ok("P-384"),
+        _ => Err(Error::InvalidKey("EC key must be P-256 or P-384")),
+    }
+}
+
+#[cfg(feature = "alg-rs")]
+fn rsa_pkcs1(der: &[u8]) -> Result<AnySignKey, Error> {
+    Ok(AnySignKey::Rsa(Box::new(RsaKeyPair::from_der(der)?)))
+}
+
+#[cfg(not(feature = "alg-rs"))]
+fn rsa_pkcs1(_der: &[u8]) -> Result<AnySignKey, Error> {
+    Err(Error::InvalidKey("RSA keys require the feature `alg-rs`"))
+}
+
+#[cfg(feature = "alg-rs")]
+fn rsa_pkcs8(der: &[u8]) -> Result<AnySignKey, Error> {
+    Ok(AnySignKey::Rsa(Box::new(RsaKeyPair::from_pkcs8(der)?)))
+}
+
+#[cfg(not(feature = "alg-rs"))]
+fn rsa_pkcs8(_der: &[u8]) -> Result<AnySignKey, Error> {
+    Err(Error::InvalidKey("RSA keys require the feature `alg-rs`"))
+}
+
+#[cfg(feature = "alg-eddsa")]
+fn ed25519_pkcs8(der: &[u8]) -> Result<AnySignKey, Error> {
+    Ok(AnySignKey::Ed25519(crypto::ed25519_from_pkcs8(der)?))
+}
+
+#[cfg(not(feature = "alg-eddsa"))]
+fn ed25519_pkcs8(_der: &[u8]) -> Result<AnySignKey, Error> {
+    Err(Error::InvalidKey("Ed25519 keys require the feature `alg-eddsa`"))
+}
@@ -0,0 +1,320 @@
+//! Signing keys of an issuer
+//!
+//! A `SigningKeyRing` holds the signing keys of an issuer by key ID, signs with the active one
+//! and publishes the public keys of all of them, for rotating the signing key without downtime:
+//! publish the new key, wait for the verifiers to refresh the JWK Set, activate the new key,
+//! then remove the old one once its tokens have expired.
+//!
+//! ```rust
+//! use jwts::{Claims, jws};
+//! use jwts::crypto::RsaKeyPair;
+//! use jwts::jws::Header;
+//! use jwts::jws::alg::RS256;
+//! use jwts::keyring::SigningKeyRing;
+//! use jwts::keystore::VerifyWithKeyStore;
+//!
+//! let ring = SigningKeyRing::<RS256>::new()
+//!     .with_key("2024-01", RsaKeyPair::from_der(include_bytes!("../tests/rsa-pri.der")).unwrap());
+//! let token = ring.sign(Header::default(), &Claims::default()).unwrap();
+//! assert_eq!(jws::decode_header(&token).unwrap().kid.as_deref(), Some("2024-01"));
+//!
+//! let jwks = ring.jwks(); // serve it at the `jwks_uri` of the issuer
+//! jws::decode::<Claims>(&token, VerifyWithKeyStore::<RS256, _>::new(jwks)).unwrap();
+//! ```
+//!
+//! A `KeyRotation` keeps the schedule of the rotation: it tells when the active key is too old,
+//! and removes the keys rotated out once the tokens they signed have expired.
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use jwts::crypto::RsaKeyPair;
+//! use jwts::jws::alg::RS256;
+//! use jwts::keyring::{KeyRotation, SigningKeyRing};
+//!
+//! let day = Duration::from_secs(24 * 60 * 60);
+//! let ring = SigningKeyRing::<RS256>::new()
+//!     .with_key("2024-01", RsaKeyPair::from_der(include_bytes!("../tests/rsa-pri.der")).unwrap());
+//! let rotation = KeyRotation::new(ring.clone(), 30 * day, day);
+//! // e.g. in a daily job
+//! if rotation.needs_rotation() {
+//!     rotation.rotate("2024-02", RsaKeyPair::from_der(include_bytes!("../tests/rsa-pri-another.der")).unwrap());
+//! }
+//! rotation.retire_expired();
+//! ```
+
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::error::Error;
+use crate::jwk::{Jwk, JwkSet};
+use crate::jws::{self, Alg, Algorithm, Header};
+use crate::redact::Redacted;
+use crate::time::NumericDate;
+
+/// Signing keys by key ID with an active key, see the module docs.
+///
+/// The first key added is active until another is activated. Clones share the keys, so that a
+/// rotation is seen by all of them.
+pub struct SigningKeyRing<A: Algorithm> {
+    keys: Arc<RwLock<Keys<A>>>,
+}
+
+struct Keys<A: Algorithm> {
+    keys: Vec<(String, Arc<A::SignKey>)>,
+    active: Option<String>,
+}
+
+impl<A: Algorithm> SigningKeyRing<A> {
+    /// Create a `SigningKeyRing` without keys.
+    #[inline]
+    pub fn new() -> Self {
+        SigningKeyRing { keys: Arc::new(RwLock::new(Keys { keys: Vec::new(), active: None })) }
+    }
+
+    /// Add the key with the key ID, see `insert`.
+    #[inline]
+    pub fn with_key(self, kid: impl Into<String>, key: impl Into<Arc<A::SignKey>>) -> Self {
+        self.insert(kid, key);
+        self
+    }
+
+    /// Add the key with the key ID, replacing the key of the same ID. The key is active if no
+    /// other key is.
+    pub fn insert(&self, kid: impl Into<String>, key: impl Into<Arc<A::SignKey>>) {
+        let (kid, key) = (kid.into(), key.into());
+        let mut keys = self.keys.write().unwrap_or_else(|e| e.into_inner());
+        keys.active.get_or_insert_with(|| kid.clone());
+        match keys.keys.iter_mut().find(|(x, _)| *x == kid) {
+            Some((_, x)) => *x = key,
+            None => keys.keys.push((kid, key)),
+        }
+    }
+
+    /// Sign with the key of the key ID from now on.
+    pub fn activate(&self, kid: &str) -> Result<(), Error> {
+        let mut keys = self.keys.write().unwrap_or_else(|e| e.into_inner());
+        if !keys.keys.iter().any(|(x, _)| x == kid) {
+            return Err(Error::InvalidKey("no matching key"));
+        }
+        keys.active = Some(kid.to_owned());
+        Ok(())
+    }
+
+    /// Remove the key of the key ID, the active key is not removed.
+    pub fn remove(&self, kid: &str) -> Result<(), Error> {
+        let mut keys = self.keys.write().unwrap_or_else(|e| e.into_inner());
+        if keys.active.as_deref() == Some(kid) {
+            return Err(Error::Rejected("the active key cannot be removed"));
+        }
+        let len = keys.keys.len();
+        keys.keys.retain(|(x, _)| x != kid);
+        match keys.keys.len() < len {
+            true => Ok(()),
+            false => Err(Error::InvalidKey("no matching key")),
+        }
+    }
+
+    /// The key ID of the active key.
+    #[inline]
+    pub fn active_kid(&self) -> Option<String> {
+        self.keys.read().unwrap_or_else(|e| e.into_inner()).active.clone()
+    }
+
+    /// Encode the payload signed by the active key, with its key ID in the `kid` header.
+    pub fn sign(&self, header: Header, payload: &impl Serialize) -> Result<String, Error> {
+        let (kid, key) = {
+            let keys = self.keys.read().unwrap_or_else(|e| e.into_inner());
+            let kid = keys.active.clone().ok_or(Error::InvalidKey("no active key"))?;
+            let key = keys.keys.iter().find(|(x, _)| *x == kid).map(|(_, x)| x.clone());
+            (kid, key.ok_or(Error::InvalidKey("no active key"))?)
+        };
+        jws::encode::<A>(header.with_kid(kid), payload, &key)
+    }
+
+    /// The public keys of all keys, with their key IDs, `alg` and `use`. Symmetric keys are
+    /// never published.
+    pub fn jwks(&self) -> JwkSet {
+        let keys = self.keys.read().unwrap_or_else(|e| e.into_inner());
+        let keys = keys.keys.iter()
+            .filter_map(|(kid, key)| {
+                Some(Jwk {
+                    kid: Some(kid.clone()),
+                    alg: Some(Alg::from(A::name())),
+                    r#use: Some("sig".to_owned()),
+                    ..A::public_jwk(key)?
+                })
+            })
+            .collect();
+        JwkSet { keys }
+    }
+}
+
+impl<A: Algorithm> Default for SigningKeyRing<A> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: Algorithm> Clone for SigningKeyRing<A> {
+    #[inline]
+    fn clone(&self) -> Self {
+        SigningKeyRing { keys: self.keys.clone() }
+    }
+}
+
+/// Omits the keys.
+impl<A: Algorithm> Debug for SigningKeyRing<A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let keys = self.keys.read().unwrap_or_else(|e| e.into_inner());
+        f.debug_struct("SigningKeyRing")
+            .field("alg", &A::name())
+            .field("kids", &keys.keys.iter().map(|(x, _)| x).collect::<Vec<_>>())
+            .field("active", &keys.active)
+            .field("keys", &Redacted)
+            .finish()
+    }
+}
+
+/// The rotation schedule of the keys of a `SigningKeyRing`, see the module docs.
+///
+/// Keys are rotated once the active one is older than `max_age`, and removed `max_token_ttl`
+/// after they were rotated out, when no token they signed is valid anymore. The keys in the
+/// ring when the schedule is created are taken as created then. Clones share the schedule.
+pub struct KeyRotation<A: Algorithm> {
+    ring: SigningKeyRing<A>,
+    max_age: Duration,
+    max_token_ttl: Duration,
+    schedule: Arc<RwLock<Schedule>>,
+}
+
+#[derive(Debug)]
+struct Schedule {
+    created: Vec<(String, NumericDate)>,
+    retiring: Vec<(String, NumericDate)>,
+}
+
+impl<A: Algorithm> KeyRotation<A> {
+    /// Create a `KeyRotation` of the ring, rotating keys older than `max_age` and removing them
+    /// `max_token_ttl`, the longest lifetime of the tokens signed, after they were rotated out.
+    pub fn new(ring: SigningKeyRing<A>, max_age: Duration, max_token_ttl: Duration) -> Self {
+        let now = NumericDate::now();
+        let created = ring.keys.read().unwrap_or_else(|e| e.into_inner())
+            .keys.iter()
+            .map(|(kid, _)| (kid.clone(), now))
+            .collect();
+        let schedule = Arc::new(RwLock::new(Schedule { created, retiring: Vec::new() }));
+        KeyRotation { ring, max_age, max_token_ttl, schedule }
+    }
+
+    /// The ring of the keys.
+    #[inline]
+    pub fn ring(&self) -> &SigningKeyRing<A> {
+        &self.ring
+    }
+
+    /// The time the key of the key ID was added.
+    pub fn created_at(&self, kid: &str) -> Option<NumericDate> {
+        let schedule = self.schedule.read().unwrap_or_else(|e| e.into_inner());
+        schedule.created.iter().find(|(x, _)| x == kid).map(|(_, x)| *x)
+    }
+
+    /// Whether a new key should be generated, see `needs_rotation_at`.
+    #[inline]
+    pub fn needs_rotation(&self) -> bool {
+        self.needs_rotation_at(NumericDate::now())
+    }
+
+    /// Whether a new key should be generated at the time, if the active key is older than
+    /// `max_age` or there is no active key.
+    pub fn needs_rotation_at(&self, now: NumericDate) -> bool {
+        self.ring.active_kid()
+            .and_then(|x| self.created_at(&x))
+            .is_none_or(|x| now.saturating_duration_since(x) >= self.max_age)
+    }
+
+    /// Add the key and sign with it from now on, see `rotate_at`.
+    #[inline]
+    pub fn rotate(&self, kid: impl Into<String>, key: impl Into<Arc<A::SignKey>>) {
+        self.rotate_at(kid, key, NumericDate::now())
+    }
+
+    /// Add the key created at the time and sign with it from now on, scheduling the active key
+    /// for removal.
+    pub fn rotate_at(&self, kid: impl Into<String>, key: impl Into<Arc<A::SignKey>>, now: NumericDate) {
+        let kid = kid.into();
+        self.add_key_at(kid.clone(), key, now);
+        // the key was just added
+        let _ = self.activate_at(&kid, now);
+    }
+
+    /// Add the key created at the time without activating it, e.g. to publish it in the JWK Set
+    /// ahead of the rotation.
+    pub fn add_key_at(&self, kid: impl Into<String>, key: impl Into<Arc<A::SignKey>>, now: NumericDate) {
+        let kid = kid.into();
+        self.ring.insert(kid.clone(), key);
+        let mut schedule = self.schedule.write().unwrap_or_else(|e| e.into_inner());
+        schedule.created.retain(|(x, _)| *x != kid);
+        schedule.retiring.retain(|(x, _)| *x != kid);
+        schedule.created.push((kid, now));
+    }
+
+    /// Sign with the key of the key ID from the time on, scheduling the active key for removal.
+    pub fn activate_at(&self, kid: &str, now: NumericDate) -> Result<(), Error> {
+        let previous = self.ring.active_kid();
+        self.ring.activate(kid)?;
+        let mut schedule = self.schedule.write().unwrap_or_else(|e| e.into_inner());
+        schedule.retiring.retain(|(x, _)| x != kid);
+        if let Some(previous) = previous.filter(|x| x != kid) {
+            schedule.retiring.retain(|(x, _)| *x != previous);
+            schedule.retiring.push((previous, now + self.max_token_ttl));
+        }
+        Ok(())
+    }
+
+    /// Remove the keys rotated out whose tokens have expired, see `retire_expired_at`.
+    #[inline]
+    pub fn retire_expired(&self) -> Vec<String> {
+        self.retire_expired_at(NumericDate::now())
+    }
+
+    /// Remove the keys rotated out more than `max_token_ttl` before the time, returning their
+    /// key IDs.
+    pub fn retire_expired_at(&self, now: NumericDate) -> Vec<String> {
+        let mut schedule = self.schedule.write().unwrap_or_else(|e| e.into_inner());
+        let (expired, retiring) = schedule.retiring.drain(..).partition(|(_, x)| *x <= now);
+        schedule.retiring = retiring;
+        let retired: Vec<_> = expired.into_iter()
+            .map(|(kid, _)| kid)
+            .filter(|x: &String| self.ring.remove(x).is_ok())
+            .collect();
+        schedule.created.retain(|(x, _)| !retired.contains(x));
+        retired
+    }
+}
+
+impl<A: Algorithm> Clone for KeyRotation<A> {
+    #[inline]
+    fn clone(&self) -> Self {
+        KeyRotation {
+            ring: self.ring.clone(),
+            max_age: self.max_age,
+            max_token_ttl: self.max_token_ttl,
+            schedule: self.schedule.clone(),
+        }
+    }
+}
+
+impl<A: Algorithm> Debug for KeyRotation<A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyRotation")
+            .field("ring", &self.ring)
+            .field("max_age", &self.max_age)
+            .field("max_token_ttl", &self.max_token_ttl)
+            .field("schedule", &*self.schedule.read().unwrap_or_else(|e| e.into_inner()))
+            .finish()
+    }
+}
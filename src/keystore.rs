@@ -0,0 +1,245 @@
+//! Verification keys selected per token
+//!
+//! A `KeyStore` picks the key of a token from its header and payload, usually by `kid`, and
+//! `VerifyWithKeyStore` verifies with it. Maps of `kid` to key, JWK Sets and closures are key stores, and
+//! `RotatingKeyStore` holds the rotated keys of self-issued tokens.
+//!
+//! ```rust
+//! use std::collections::HashMap;
+//! use jwts::{Claims, jws};
+//! use jwts::jws::Header;
+//! use jwts::jws::alg::HS256;
+//! use jwts::keystore::VerifyWithKeyStore;
+//!
+//! let keys = HashMap::from([("key-1".to_owned(), b"secret-1".to_vec()), ("key-2".to_owned(), b"secret-2".to_vec())]);
+//! let token = jws::encode::<HS256>(Header::default().with_kid("key-2"), &Claims::default(), b"secret-2").unwrap();
+//! jws::decode::<Claims>(&token, VerifyWithKeyStore::<HS256, _>::new(&keys)).unwrap();
+//! ```
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::BuildHasher;
+use std::marker::PhantomData;
+use std::sync::{Arc, RwLock};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::Error;
+use crate::jwk::JwkSet;
+use crate::jws::{self, Algorithm, Header, Verify};
+
+/// Selects the verification key of a token.
+pub trait KeyStore<P = Value> {
+    type Key;
+
+    /// The key of the token, `None` if there is no matching key.
+    fn key_for(&self, header: &Header, payload: &P) -> Option<Self::Key>;
+}
+
+impl<P, K, F: Fn(&Header, &P) -> Option<K>> KeyStore<P> for F {
+    type Key = K;
+
+    #[inline]
+    fn key_for(&self, header: &Header, payload: &P) -> Option<K> {
+        self(header, payload)
+    }
+}
+
+/// Resolves the verification key of a token asynchronously, e.g. by fetching the JWK Set of the
+/// issuer or querying a database, see `jws::decode_async`.
+pub trait AsyncKeyStore<P = Value> {
+    type Key;
+
+    /// The key of the token, an error if there is no matching key or it could not be resolved.
+    fn resolve(&self, header: &Header, payload: &P) -> impl Future<Output=Result<Self::Key, Error>> + Send;
+}
+
+impl<P, K, F, Fut> AsyncKeyStore<P> for F
+where
+    F: Fn(&Header, &P) -> Fut,
+    Fut: Future<Output=Result<K, Error>> + Send,
+{
+    type Key = K;
+
+    #[inline]
+    fn resolve(&self, header: &Header, payload: &P) -> impl Future<Output=Result<K, Error>> + Send {
+        self(header, payload)
+    }
+}
+
+/// Keys by `kid`, cloned out of the map, see the implementation for `&HashMap` to borrow them.
+impl<P, K: Clone, S: BuildHasher> KeyStore<P> for HashMap<String, K, S> {
+    type Key = K;
+
+    #[inline]
+    fn key_for(&self, header: &Header, _payload: &P) -> Option<K> {
+        self.get(header.kid.as_deref()?).cloned()
+    }
+}
+
+/// Keys by `kid`.
+impl<'a, P, K, S: BuildHasher> KeyStore<P> for &'a HashMap<String, K, S> {
+    type Key = &'a K;
+
+    #[inline]
+    fn key_for(&self, header: &Header, _payload: &P) -> Option<&'a K> {
+        self.get(header.kid.as_deref()?)
+    }
+}
+
+/// Keys by `kid`, or the only key for tokens without one, skipping keys of another `alg` or of
+/// a `kty` other than that of the `alg` header, so that e.g. a public RSA key is never taken as
+/// the HMAC secret of an `HS256` token.
+impl<P> KeyStore<P> for JwkSet {
+    type Key = Vec<u8>;
+
+    fn key_for(&self, header: &Header, _payload: &P) -> Option<Vec<u8>> {
+        let alg = header.alg.as_ref()?;
+        let jwk = match &header.kid {
+            Some(kid) => self.find(kid),
+            None if self.keys.len() == 1 => self.keys.first(),
+            None => None,
+        };
+        jwk.filter(|x| x.alg.as_ref().is_none_or(|x| x == alg))
+            .filter(|x| alg.kty() == Some(x.kty.as_str()))
+            .and_then(|x| x.to_verify_key().ok())
+    }
+}
+
+/// The one key of every token, e.g. of an issuer with a single key, see `VerifyWithIssuerResolver`.
+#[derive(Clone)]
+pub struct SingleKey<K>(pub K);
+
+impl<P, K: Clone> KeyStore<P> for SingleKey<K> {
+    type Key = K;
+
+    #[inline]
+    fn key_for(&self, _header: &Header, _payload: &P) -> Option<K> {
+        Some(self.0.clone())
+    }
+}
+
+/// Verify with the key selected by the `KeyStore`.
+pub struct VerifyWithKeyStore<A: Algorithm, S> {
+    store: S,
+    _alg: PhantomData<A>,
+}
+
+impl<A: Algorithm, S> VerifyWithKeyStore<A, S> {
+    #[inline]
+    pub fn new(store: S) -> Self {
+        VerifyWithKeyStore {
+            store,
+            _alg: PhantomData,
+        }
+    }
+}
+
+impl<A: Algorithm, S: Clone> Clone for VerifyWithKeyStore<A, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        VerifyWithKeyStore::new(self.store.clone())
+    }
+}
+
+impl<P, A: Algorithm, S: KeyStore<P>> Verify<P> for VerifyWithKeyStore<A, S>
+where
+    S::Key: AsRef<A::VerifyKey>,
+{
+    fn verify(&self, f2s: &str, signature: &[u8], header: &Header, payload: &P) -> Result<(), Error> {
+        let key = self.store.key_for(header, payload).ok_or(Error::InvalidKey("no matching key"))?;
+        A::verify(f2s, signature, key.as_ref())
+    }
+}
+
+/// Keys of self-issued tokens, signing with the primary key and verifying with it and a number
+/// of previous keys, rotated at runtime.
+///
+/// Clones share the keys, so a rotation is seen by every clone at once.
+///
+/// ```rust
+/// use jwts::{Claims, jws};
+/// use jwts::jws::Header;
+/// use jwts::jws::alg::HS256;
+/// use jwts::keystore::{RotatingKeyStore, VerifyWithKeyStore};
+///
+/// let store = RotatingKeyStore::<HS256>::new("key-1", b"secret-1".as_slice(), b"secret-1".as_slice(), 1);
+/// let token = store.sign(Header::default(), &Claims::default()).unwrap();
+/// store.rotate("key-2", b"secret-2".as_slice(), b"secret-2".as_slice());
+/// jws::decode::<Claims>(&token, VerifyWithKeyStore::<HS256, _>::new(store.clone())).unwrap();
+/// ```
+pub struct RotatingKeyStore<A: Algorithm> {
+    keys: Arc<RwLock<Vec<RotatingKey<A>>>>,
+    previous: usize,
+}
+
+struct RotatingKey<A: Algorithm> {
+    kid: String,
+    sign: Arc<A::SignKey>,
+    verify: Arc<A::VerifyKey>,
+}
+
+impl<A: Algorithm> RotatingKeyStore<A> {
+    /// Create a `RotatingKeyStore` with the primary key, keeping the `previous` keys for verification after rotations.
+    pub fn new(kid: impl Into<String>, sign: impl Into<Arc<A::SignKey>>, verify: impl Into<Arc<A::VerifyKey>>, previous: usize) -> Self {
+        let primary = RotatingKey {
+            kid: kid.into(),
+            sign: sign.into(),
+            verify: verify.into(),
+        };
+        RotatingKeyStore {
+            keys: Arc::new(RwLock::new(vec![primary])),
+            previous,
+        }
+    }
+
+    /// Make the key the primary one, the oldest key beyond the previous ones kept is dropped.
+    pub fn rotate(&self, kid: impl Into<String>, sign: impl Into<Arc<A::SignKey>>, verify: impl Into<Arc<A::VerifyKey>>) {
+        let primary = RotatingKey {
+            kid: kid.into(),
+            sign: sign.into(),
+            verify: verify.into(),
+        };
+        let mut keys = self.keys.write().unwrap_or_else(|e| e.into_inner());
+        keys.insert(0, primary);
+        keys.truncate(self.previous + 1);
+    }
+
+    /// The `kid` of the primary key.
+    pub fn primary_kid(&self) -> String {
+        self.keys.read().unwrap_or_else(|e| e.into_inner())[0].kid.clone()
+    }
+
+    /// Encode and sign a token with the primary key, setting the `kid` header.
+    pub fn sign(&self, header: Header, payload: &impl Serialize) -> Result<String, Error> {
+        let (kid, key) = {
+            let keys = self.keys.read().unwrap_or_else(|e| e.into_inner());
+            (keys[0].kid.clone(), keys[0].sign.clone())
+        };
+        jws::encode::<A>(header.with_kid(kid), payload, &key)
+    }
+}
+
+impl<A: Algorithm> Clone for RotatingKeyStore<A> {
+    #[inline]
+    fn clone(&self) -> Self {
+        RotatingKeyStore {
+            keys: self.keys.clone(),
+            previous: self.previous,
+        }
+    }
+}
+
+/// Keys by `kid`, the primary key for tokens without one.
+impl<P, A: Algorithm> KeyStore<P> for RotatingKeyStore<A> {
+    type Key = Arc<A::VerifyKey>;
+
+    fn key_for(&self, header: &Header, _payload: &P) -> Option<Arc<A::VerifyKey>> {
+        let keys = self.keys.read().unwrap_or_else(|e| e.into_inner());
+        match &header.kid {
+            Some(kid) => keys.iter().find(|x| &x.kid == kid),
+            None => keys.first(),
+        }.map(|x| x.verify.clone())
+    }
+}
@@ -20,14 +20,15 @@
 //!
 //! ```rust
 //! use jwts::{Claims, jws};
-//! use jwts::jws::{Header, NoVerify, Token, VerifyWith};
+//! use jwts::jws::{DangerousNoVerify, Header, Token, VerifyWith};
 //! use jwts::jws::alg::HS256;
 //!
 //! let claims = Claims::default();
 //! let token = jws::encode::<HS256>(Header::default(), &claims, b"secret").unwrap();
 //!
-//! let Token {..} = jws::decode::<Claims>(&token, NoVerify).unwrap(); // no verify
 //! let Token {..} = jws::decode::<Claims>(&token, VerifyWith::<HS256>(b"secret")).unwrap(); // verify with algorithm and key
+//! let Header {..} = jws::decode_header(&token).unwrap(); // peek at the header, e.g. to select the key
+//! let Token {..} = jws::decode::<Claims>(&token, DangerousNoVerify).unwrap(); // no verify, the claims are not authenticated
 //! ```
 //!
 //! ## Validate Claims
@@ -63,6 +64,28 @@
 //! claims.validate(ExpectIss("sea")).unwrap();
 //! ```
 //!
+//! ## Verify and Validate
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use jwts::{Claims, jws, Verifier};
+//! use jwts::jws::{Header, Token, VerifyWith};
+//! use jwts::jws::alg::HS256;
+//! use jwts::validate::{ExpectIss, ExpiredTime};
+//!
+//! let claims = Claims {
+//!     iss: Some("sea".to_owned()),
+//!     ..Default::default()
+//! };
+//! let claims = claims.expired_in(Duration::from_secs(60));
+//! let token = jws::encode::<HS256>(Header::default(), &claims, b"secret").unwrap();
+//!
+//! let verifier = Verifier::new(VerifyWith::<HS256>(b"secret"))
+//!     .with_validation(ExpiredTime)
+//!     .with_validation(ExpectIss("sea"));
+//! let Token {..} = verifier.verify::<Claims>(&token).unwrap();
+//! ```
+//!
 //! ## Custom Claims Type
 //!
 //! ```rust
@@ -161,11 +184,66 @@
 //! ```
 
 pub use self::claims::Claims;
-pub use self::error::Error;
+pub use self::config::VerifierConfig;
+#[cfg(feature = "uuid")]
+pub use self::claims::JtiUuid;
+pub use self::error::{Error, ErrorKind, Result, Segment, VerifyError};
+pub use self::issuer::Issuer;
+pub use self::multi_issuer::{IssuerPolicy, MultiIssuerVerifier, VerifyWithIssuerResolver};
+pub use self::verifier::{STRICT_MAX_TOKEN_LEN, VerifiedClaims, Verifier, VerifyObserver};
+pub use self::time::NumericDate;
 
+#[cfg(feature = "actix-web")]
+pub mod actix;
+pub mod audit;
+pub mod bearer;
+pub mod bs64;
+pub mod cache;
+pub mod config;
+#[cfg(feature = "reqwest-middleware")]
+pub mod client;
+pub mod crypto;
+#[cfg(feature = "cwt")]
+pub mod cwt;
+pub mod extract;
+pub mod file;
+pub mod jwk;
 pub mod jws;
+pub mod key;
+pub mod keyring;
+pub mod keystore;
+pub mod oauth;
+#[cfg(feature = "paseto")]
+pub mod paseto;
+pub mod pin;
+pub mod prelude;
+pub mod preset;
+pub mod redact;
+pub mod remote;
+pub mod replay;
+pub mod revocation;
+#[cfg(feature = "rfc7520")]
+pub mod rfc7520;
+#[cfg(feature = "zeroize")]
+pub mod secret;
+pub mod session;
+#[cfg(feature = "test-utils")]
+pub mod testing;
+#[cfg(feature = "tower")]
+pub mod tower;
 pub mod validate;
+#[cfg(all(feature = "webcrypto", target_arch = "wasm32", target_os = "unknown"))]
+pub mod webcrypto;
+pub mod x509;
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+#[cfg(any(feature = "actix-web", feature = "tower"))]
+mod auth;
 mod error;
-mod bs64;
+mod der;
+mod pem;
 mod time;
 mod claims;
+mod issuer;
+mod multi_issuer;
+mod verifier;
@@ -0,0 +1,235 @@
+//! Verifier of tokens from several issuers
+
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json as json;
+use serde_json::Value;
+
+use crate::claims;
+use crate::error::{Error, Segment, VerifyError};
+use crate::jws::{self, Alg, Algorithm, Header, Token, Verify};
+use crate::keystore::KeyStore;
+use crate::validate::{claim_string, IssComparison, ValidateError, Validation, ValidationContext};
+use crate::verifier::SharedValidation;
+
+type SharedKeyStore = Arc<dyn Fn(&Header, &Value) -> Option<Vec<u8>> + Send + Sync>;
+
+/// Keys, algorithms and audience accepted from one issuer.
+#[derive(Clone)]
+pub struct IssuerPolicy {
+    keys: SharedKeyStore,
+    algorithms: Vec<Alg>,
+    audience: Option<String>,
+    claim_mappings: Vec<(String, String)>,
+}
+
+impl IssuerPolicy {
+    /// Create an `IssuerPolicy` verifying with the keys of the store, such as a `JwkSet`, and the
+    /// builtin algorithms allowed, either all HS or all asymmetric, so that a public key is never
+    /// taken as an HMAC secret.
+    pub fn new<S>(keys: S, algorithms: impl IntoIterator<Item=Alg>) -> Result<Self, Error>
+    where
+        S: KeyStore<Value> + Send + Sync + 'static,
+        S::Key: AsRef<[u8]>,
+    {
+        let algorithms: Vec<_> = algorithms.into_iter().collect();
+        let symmetric = |x: &Alg| x.kty() == Some("oct");
+        if algorithms.is_empty() {
+            return Err(Error::Rejected("no algorithm allowed"));
+        }
+        if algorithms.iter().any(symmetric) && !algorithms.iter().all(symmetric) {
+            return Err(Error::Rejected("HS algorithms mixed with asymmetric algorithms"));
+        }
+        Ok(IssuerPolicy {
+            keys: Arc::new(move |header, payload| keys.key_for(header, payload).map(|x| x.as_ref().to_owned())),
+            algorithms,
+            audience: None,
+            claim_mappings: Vec::new(),
+        })
+    }
+
+    /// Require the audience in the `aud` claim, a string or an array.
+    #[inline]
+    pub fn with_aud(self, aud: impl Into<String>) -> Self {
+        IssuerPolicy { audience: Some(aud.into()), ..self }
+    }
+
+    /// Rename the claim `from` of the tokens of the issuer to `to` before the claims are
+    /// validated and deserialized, see `Verifier::with_claim_mapping`.
+    #[inline]
+    pub fn with_claim_mapping(mut self, from: impl Into<String>, to: impl Into<String>) -> Self {
+        self.claim_mappings.push((from.into(), to.into()));
+        self
+    }
+}
+
+/// Verify tokens of several issuers, selecting the policy by the unverified `iss` claim before
+/// verifying the signature, then validating the audience of the issuer and the common claims
+/// validations.
+///
+/// ```rust
+/// use jwts::{Claims, IssuerPolicy, jws, MultiIssuerVerifier};
+/// use jwts::jwk::{Jwk, JwkSet};
+/// use jwts::jws::{Alg, Header};
+/// use jwts::jws::alg::HS256;
+/// use jwts::validate::ExpiredTime;
+///
+/// let jwks = |k: &[u8]| JwkSet { keys: vec![Jwk { kid: Some("key".to_owned()), ..Jwk::oct(k) }] };
+/// let verifier = MultiIssuerVerifier::new()
+///     .with_issuer("https://a.example.com", IssuerPolicy::new(jwks(b"secret-a"), [Alg::HS256]).unwrap().with_aud("api"))
+///     .with_issuer("https://b.example.com", IssuerPolicy::new(jwks(b"secret-b"), [Alg::HS256]).unwrap().with_aud("api"))
+///     .with_validation(ExpiredTime);
+///
+/// let claims = Claims {
+///     iss: Some("https://b.example.com".to_owned()),
+///     aud: Some("api".to_owned()),
+///     ..Default::default()
+/// }.expired_in(std::time::Duration::from_secs(60));
+/// let token = jws::encode::<HS256>(Header::default().with_kid("key"), &claims, b"secret-b").unwrap();
+/// verifier.verify::<Claims>(&token).unwrap();
+/// ```
+///
+/// A `MultiIssuerVerifier` is `Send + Sync + Clone`, clones share the key stores and validations.
+#[derive(Clone, Default)]
+pub struct MultiIssuerVerifier {
+    issuers: HashMap<String, IssuerPolicy>,
+    comparison: IssComparison,
+    validations: Vec<SharedValidation>,
+}
+
+impl MultiIssuerVerifier {
+    /// Create a `MultiIssuerVerifier` accepting no issuer.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept the tokens of the issuer with the policy.
+    #[inline]
+    pub fn with_issuer(mut self, iss: impl Into<String>, policy: IssuerPolicy) -> Self {
+        self.issuers.insert(iss.into(), policy);
+        self
+    }
+
+    /// Compare the `iss` claim to the issuers by the mode, exactly by default.
+    #[inline]
+    pub fn with_iss_comparison(self, comparison: IssComparison) -> Self {
+        MultiIssuerVerifier { comparison, ..self }
+    }
+
+    /// Add a claims validation of all issuers, validations run in the order added.
+    #[inline]
+    pub fn with_validation(mut self, validation: impl Validation<Value, Error=ValidateError> + Send + Sync + 'static) -> Self {
+        self.validations.push(Arc::new(validation));
+        self
+    }
+
+    /// Decode and verify the token with the policy of its issuer, validate the claims, then
+    /// deserialize the payload as `C`.
+    #[inline]
+    pub fn verify<C: DeserializeOwned>(&self, token: &str) -> Result<Token<C>, VerifyError> {
+        self.verify_with_context(token, &ValidationContext::new())
+    }
+
+    /// Verify the token like `verify`, validating the claims with the request-scoped data of the
+    /// context, see `Validation::validate_with`.
+    pub fn verify_with_context<C: DeserializeOwned>(&self, token: &str, context: &ValidationContext) -> Result<Token<C>, VerifyError> {
+        let Token { header, mut payload, signature } = jws::decode::<Value>(token, self)?;
+        let policy = self.policy(&payload)?;
+        claims::remap_claims(&mut payload, &policy.claim_mappings);
+        if let Some(audience) = &policy.audience {
+            let aud = &payload["aud"];
+            let matches = match aud.as_array() {
+                Some(auds) => auds.iter().any(|x| x.as_str() == Some(audience)),
+                None => aud.as_str() == Some(audience),
+            };
+            if !matches {
+                return Err(ValidateError::InvalidAud { expected: audience.clone(), actual: claim_string(aud) }.into());
+            }
+        }
+        for validation in &self.validations {
+            validation.validate_with(&payload, context)?;
+        }
+        let payload = json::from_value(payload).map_err(|e| Error::json(Segment::Payload, e))?;
+        Ok(Token { header, payload, signature })
+    }
+
+    fn policy(&self, payload: &Value) -> Result<&IssuerPolicy, Error> {
+        let iss = payload["iss"].as_str().ok_or(Error::Rejected("unknown issuer"))?;
+        self.issuers.get(iss)
+            .or_else(|| {
+                self.issuers.iter()
+                    .find(|(x, _)| self.comparison.matches(x, iss))
+                    .map(|(_, policy)| policy)
+            })
+            .ok_or(Error::Rejected("unknown issuer"))
+    }
+}
+
+/// Verifies the signature only, with the policy of the issuer.
+impl Verify<Value> for MultiIssuerVerifier {
+    fn verify(&self, f2s: &str, signature: &[u8], header: &Header, payload: &Value) -> Result<(), Error> {
+        let policy = self.policy(payload)?;
+        let alg = header.alg.as_ref()
+            .filter(|x| policy.algorithms.contains(x))
+            .ok_or(Error::Rejected("algorithm not allowed"))?;
+        let key = (policy.keys)(header, payload).ok_or(Error::InvalidKey("no matching key"))?;
+        alg.verify(f2s, signature, &key)
+    }
+}
+
+/// Verify with the keys of the issuer resolved by the unverified `iss` claim, for accepting the
+/// tokens of many tenants without the per-issuer policies of `MultiIssuerVerifier`.
+///
+/// The resolver returns the `KeyStore` of the issuer, such as its `JwkSet` or a `SingleKey`, or
+/// `None` for an unknown issuer. Tokens without `iss` are rejected.
+///
+/// ```rust
+/// use jwts::{Claims, jws, VerifyWithIssuerResolver};
+/// use jwts::jws::Header;
+/// use jwts::jws::alg::HS256;
+/// use jwts::keystore::SingleKey;
+///
+/// let verify = VerifyWithIssuerResolver::<HS256, _>::new(|iss: &str| match iss {
+///     "https://a.example.com" => Some(SingleKey(b"secret-a".as_slice())),
+///     "https://b.example.com" => Some(SingleKey(b"secret-b".as_slice())),
+///     _ => None,
+/// });
+///
+/// let claims = Claims { iss: Some("https://b.example.com".to_owned()), ..Default::default() };
+/// let token = jws::encode::<HS256>(Header::default(), &claims, b"secret-b").unwrap();
+/// jws::decode::<Claims>(&token, &verify).unwrap();
+/// ```
+pub struct VerifyWithIssuerResolver<A: Algorithm, F> {
+    resolver: F,
+    _alg: PhantomData<A>,
+}
+
+impl<A: Algorithm, F> VerifyWithIssuerResolver<A, F> {
+    #[inline]
+    pub fn new(resolver: F) -> Self {
+        VerifyWithIssuerResolver {
+            resolver,
+            _alg: PhantomData,
+        }
+    }
+}
+
+impl<P: Serialize, A: Algorithm, F, S> Verify<P> for VerifyWithIssuerResolver<A, F>
+where
+    F: Fn(&str) -> Option<S>,
+    S: KeyStore<P>,
+    S::Key: AsRef<A::VerifyKey>,
+{
+    fn verify(&self, f2s: &str, signature: &[u8], header: &Header, payload: &P) -> Result<(), Error> {
+        let claims = json::to_value(payload).unwrap_or_default();
+        let iss = claims["iss"].as_str().ok_or(Error::Rejected("missing issuer"))?;
+        let store = (self.resolver)(iss).ok_or(Error::Rejected("unknown issuer"))?;
+        let key = store.key_for(header, payload).ok_or(Error::InvalidKey("no matching key"))?;
+        A::verify(f2s, signature, key.as_ref())
+    }
+}
@@ -0,0 +1,133 @@
+//! Responses of the OAuth 2.0 token endpoint, see https://tools.ietf.org/html/rfc6749#section-5.1
+//!
+//! A `TokenResponse` is parsed from the JSON body, or the `application/x-www-form-urlencoded`
+//! body of the legacy endpoints, and its OpenID Connect `id_token` verified in one call.
+//!
+//! ```rust
+//! use jwts::{Claims, jws, Verifier};
+//! use jwts::jws::{Header, VerifyWith};
+//! use jwts::jws::alg::HS256;
+//! use jwts::oauth::TokenResponse;
+//! use jwts::validate::ExpectIss;
+//!
+//! let id_token = jws::encode::<HS256>(Header::default(), &Claims { iss: Some("idp".to_owned()), ..Default::default() }, b"secret").unwrap();
+//! let body = format!(r#"{{"access_token":"at","token_type":"Bearer","expires_in":3600,"id_token":"{}"}}"#, id_token);
+//!
+//! let response = TokenResponse::from_json(body.as_bytes()).unwrap();
+//! assert_eq!(response.expires_in, Some(3600));
+//! let verifier = Verifier::new(VerifyWith::<HS256>(b"secret")).with_validation(ExpectIss("idp"));
+//! let id_token = response.verify_id_token::<Claims, _>(&verifier).unwrap();
+//! ```
+
+use std::fmt::{Debug, Formatter};
+
+use serde::de::{DeserializeOwned, Deserializer};
+use serde_derive::{Deserialize, Serialize};
+use serde_json as json;
+use serde_json::{Map, Value};
+
+use crate::error::{Error, VerifyError};
+use crate::jws::{Token, Verify};
+use crate::redact::Redacted;
+use crate::verifier::Verifier;
+
+/// A successful response of the token endpoint.
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TokenResponse {
+    /// The access token
+    pub access_token: String,
+    /// The type of the access token, e.g. `Bearer`
+    pub token_type: String,
+    /// The lifetime of the access token in seconds, a number or a numeric string
+    #[serde(default, deserialize_with = "deserialize_expires_in", skip_serializing_if = "Option::is_none")]
+    pub expires_in: Option<u64>,
+    /// The refresh token
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    /// The OpenID Connect ID token
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_token: Option<String>,
+    /// The scope granted, if other than requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    /// Additional parameters
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl TokenResponse {
+    /// Parse the JSON body of the response.
+    #[inline]
+    pub fn from_json(body: &[u8]) -> Result<Self, Error> {
+        json::from_slice(body).map_err(Error::fetch)
+    }
+
+    /// Parse the `application/x-www-form-urlencoded` body of the response.
+    pub fn from_form(body: &str) -> Result<Self, Error> {
+        let params = body.split('&')
+            .filter(|x| !x.is_empty())
+            .map(|x| {
+                let (name, value) = x.split_once('=').unwrap_or((x, ""));
+                (form_decode(name), Value::String(form_decode(value)))
+            })
+            .collect();
+        json::from_value(Value::Object(params)).map_err(Error::fetch)
+    }
+
+    /// Whether the access token is a bearer token, the `token_type` is case-insensitive.
+    #[inline]
+    pub fn is_bearer(&self) -> bool {
+        self.token_type.eq_ignore_ascii_case("bearer")
+    }
+
+    /// Verify the `id_token` with the verifier, rejected if there is none.
+    pub fn verify_id_token<C: DeserializeOwned, V: Verify<Value>>(&self, verifier: &Verifier<V>) -> Result<Token<C>, VerifyError> {
+        let id_token = self.id_token.as_deref().ok_or(Error::Rejected("no id_token"))?;
+        verifier.verify(id_token)
+    }
+}
+
+/// Masks the tokens, see `redact::Reveal` to print them.
+impl Debug for TokenResponse {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenResponse")
+            .field("access_token", &Redacted)
+            .field("token_type", &self.token_type)
+            .field("expires_in", &self.expires_in)
+            .field("refresh_token", &self.refresh_token.as_ref().map(|_| Redacted))
+            .field("id_token", &self.id_token.as_ref().map(|_| Redacted))
+            .field("scope", &self.scope)
+            .field("extra", &self.extra)
+            .finish()
+    }
+}
+
+fn deserialize_expires_in<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    let value: Option<Value> = serde::Deserialize::deserialize(deserializer)?;
+    match value {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Number(x)) => x.as_u64().map(Some).ok_or_else(|| serde::de::Error::custom("invalid expires_in")),
+        Some(Value::String(x)) => x.parse().map(Some).map_err(|_| serde::de::Error::custom("invalid expires_in")),
+        Some(_) => Err(serde::de::Error::custom("invalid expires_in")),
+    }
+}
+
+/// Decode a form component, '+' as a space and the invalid percent escapes as they are.
+fn form_decode(s: &str) -> String {
+    let s = s.as_bytes();
+    let mut buf = Vec::with_capacity(s.len());
+    let mut i = 0;
+    while i < s.len() {
+        let hex = |i: usize| s.get(i).and_then(|x| (*x as char).to_digit(16));
+        match (s[i], hex(i + 1), hex(i + 2)) {
+            (b'+', _, _) => buf.push(b' '),
+            (b'%', Some(hi), Some(lo)) => {
+                buf.push((hi * 16 + lo) as u8);
+                i += 2;
+            }
+            (x, _, _) => buf.push(x),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&buf).into_owned()
+}
@@ -0,0 +1,205 @@
+//! PASETO v4 tokens, requires the feature `paseto`
+//!
+//! See https://github.com/paseto-standard/paseto-spec/blob/master/docs/01-Protocol-Versions/Version4.md.
+//! `v4.local` tokens are encrypted with XChaCha20 and authenticated with keyed BLAKE2b under a
+//! 32-byte symmetric key, `v4.public` tokens are signed with Ed25519.
+//!
+//! The payload is any serializable type such as `Claims`. PASETO carries the `exp`, `nbf` and
+//! `iat` claims as RFC 3339 date-times, they are converted from and to the seconds since
+//! UNIX_EPOCH of the JWT claims, so the validations of `jwts::validate` apply as for JWTs.
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use jwts::Claims;
+//! use jwts::paseto;
+//! use jwts::validate::{ExpiredTime, Validate};
+//!
+//! let key = [7; 32];
+//! let claims = Claims::new().expired_in(Duration::from_secs(60));
+//! let token = paseto::encrypt(&key, &claims, b"key-1", b"").unwrap();
+//! assert!(token.starts_with("v4.local."));
+//!
+//! let decrypted = paseto::decrypt::<Claims>(&token, &key, b"").unwrap();
+//! assert_eq!(decrypted.footer, b"key-1");
+//! decrypted.payload.validate(ExpiredTime).unwrap();
+//! ```
+
+use blake2::Blake2bMac;
+use blake2::digest::{Mac, consts::{U32, U56}};
+use chacha20::XChaCha20;
+use chacha20::cipher::{KeyIvInit, StreamCipher};
+use serde::de::{DeserializeOwned, Error as _};
+use serde::Serialize;
+use serde_json as json;
+use serde_json::Value;
+
+use crate::{bs64, crypto, time};
+use crate::crypto::Ed25519KeyPair;
+use crate::error::{Error, Segment};
+use crate::jws::Algorithm;
+use crate::jws::alg::Ed25519;
+
+const LOCAL: &str = "v4.local.";
+const PUBLIC: &str = "v4.public.";
+/// Claims of RFC 3339 date-times.
+const TIME_CLAIMS: [&str; 3] = ["exp", "nbf", "iat"];
+
+/// A decrypted or verified PASETO.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Paseto<P> {
+    /// payload of token
+    pub payload: P,
+    /// footer of token, authenticated but not encrypted, empty if absent
+    pub footer: Vec<u8>,
+}
+
+/// Encrypt the payload as a `v4.local` token, with the optional footer and implicit assertion.
+pub fn encrypt(key: &[u8; 32], payload: &impl Serialize, footer: &[u8], implicit: &[u8]) -> Result<String, Error> {
+    let mut nonce = [0; 32];
+    crypto::random(&mut nonce)?;
+    encrypt_with_nonce(key, &nonce, &encode_payload(payload)?, footer, implicit)
+}
+
+/// Decrypt a `v4.local` token, with the implicit assertion it was encrypted with.
+pub fn decrypt<P: DeserializeOwned>(token: &str, key: &[u8; 32], implicit: &[u8]) -> Result<Paseto<P>, Error> {
+    let (body, footer) = split(token, LOCAL)?;
+    if body.len() < 64 {
+        return Err(Error::Malformed);
+    }
+    let (nonce, rest) = body.split_at(32);
+    let (ciphertext, tag) = rest.split_at(rest.len() - 32);
+    let (mut ek, mut n2, mut ak) = split_key(key, nonce);
+
+    let mut mac = blake2b_mac::<Blake2bMac<U32>>(&ak);
+    crypto::wipe(&mut ak);
+    mac.update(&pae(&[LOCAL.as_bytes(), nonce, ciphertext, &footer, implicit]));
+    mac.verify_slice(tag).map_err(|_| Error::InvalidSignature)?;
+
+    let mut message = ciphertext.to_owned();
+    XChaCha20::new(&ek.into(), &n2.into()).apply_keystream(&mut message);
+    crypto::wipe(&mut ek);
+    crypto::wipe(&mut n2);
+    Ok(Paseto { payload: decode_payload(&message)?, footer })
+}
+
+/// Sign the payload as a `v4.public` token, with the optional footer and implicit assertion.
+pub fn sign(key: &Ed25519KeyPair, payload: &impl Serialize, footer: &[u8], implicit: &[u8]) -> Result<String, Error> {
+    let message = encode_payload(payload)?;
+    let signature = Ed25519::sign(pae(&[PUBLIC.as_bytes(), &message, footer, implicit]), key)?;
+    Ok(join(PUBLIC, &[message, signature].concat(), footer))
+}
+
+/// Verify a `v4.public` token with the 32-byte Ed25519 public key and the implicit assertion
+/// it was signed with.
+pub fn verify<P: DeserializeOwned>(token: &str, key: &[u8], implicit: &[u8]) -> Result<Paseto<P>, Error> {
+    let (body, footer) = split(token, PUBLIC)?;
+    if body.len() < 64 {
+        return Err(Error::Malformed);
+    }
+    let (message, signature) = body.split_at(body.len() - 64);
+    Ed25519::verify(pae(&[PUBLIC.as_bytes(), message, &footer, implicit]), signature, key)?;
+    Ok(Paseto { payload: decode_payload(message)?, footer })
+}
+
+/// The footer of a token before its decryption or verification, e.g. to select the key by an
+/// identifier in it. It is not authenticated yet.
+pub fn footer(token: &str) -> Result<Vec<u8>, Error> {
+    let purpose = if token.starts_with(LOCAL) { LOCAL } else { PUBLIC };
+    split(token, purpose).map(|(_, footer)| footer)
+}
+
+/// Encrypt with the nonce, the random one in `encrypt`.
+fn encrypt_with_nonce(key: &[u8; 32], nonce: &[u8; 32], message: &[u8], footer: &[u8], implicit: &[u8]) -> Result<String, Error> {
+    let (mut ek, mut n2, mut ak) = split_key(key, nonce);
+    let mut ciphertext = message.to_owned();
+    XChaCha20::new(&ek.into(), &n2.into()).apply_keystream(&mut ciphertext);
+    crypto::wipe(&mut ek);
+    crypto::wipe(&mut n2);
+
+    let mut mac = blake2b_mac::<Blake2bMac<U32>>(&ak);
+    crypto::wipe(&mut ak);
+    mac.update(&pae(&[LOCAL.as_bytes(), nonce, &ciphertext, footer, implicit]));
+    let tag = mac.finalize().into_bytes();
+    Ok(join(LOCAL, &[nonce, &ciphertext[..], &tag].concat(), footer))
+}
+
+/// The encryption key, the XChaCha20 nonce and the authentication key derived from the key
+/// and the nonce of a `v4.local` token.
+fn split_key(key: &[u8; 32], nonce: &[u8]) -> ([u8; 32], [u8; 24], [u8; 32]) {
+    let mut mac = blake2b_mac::<Blake2bMac<U56>>(key);
+    mac.update(b"paseto-encryption-key");
+    mac.update(nonce);
+    let mut tmp = mac.finalize().into_bytes();
+    let mut mac = blake2b_mac::<Blake2bMac<U32>>(key);
+    mac.update(b"paseto-auth-key-for-aead");
+    mac.update(nonce);
+    let ak = mac.finalize().into_bytes();
+
+    let mut ek = [0; 32];
+    let mut n2 = [0; 24];
+    ek.copy_from_slice(&tmp[..32]);
+    n2.copy_from_slice(&tmp[32..]);
+    crypto::wipe(&mut tmp);
+    (ek, n2, ak.into())
+}
+
+#[inline]
+fn blake2b_mac<M: Mac + blake2::digest::KeyInit>(key: &[u8]) -> M {
+    <M as Mac>::new_from_slice(key).expect("BLAKE2b accepts keys up to 64 bytes")
+}
+
+/// Pre-Authentication Encoding, see https://github.com/paseto-standard/paseto-spec/blob/master/docs/01-Protocol-Versions/Common.md#pae-definition
+fn pae(pieces: &[&[u8]]) -> Vec<u8> {
+    let le64 = |n: usize| (n as u64 & (u64::MAX >> 1)).to_le_bytes();
+    let mut out = le64(pieces.len()).to_vec();
+    for piece in pieces {
+        out.extend_from_slice(&le64(piece.len()));
+        out.extend_from_slice(piece);
+    }
+    out
+}
+
+fn join(header: &str, body: &[u8], footer: &[u8]) -> String {
+    let mut token = header.to_owned();
+    bs64::encode_into(body, &mut token);
+    if !footer.is_empty() {
+        token.push('.');
+        bs64::encode_into(footer, &mut token);
+    }
+    token
+}
+
+/// The decoded body and footer of a token of the header.
+fn split(token: &str, header: &str) -> Result<(Vec<u8>, Vec<u8>), Error> {
+    let rest = token.strip_prefix(header).ok_or(Error::Rejected("unexpected version or purpose"))?;
+    let (body, footer) = rest.split_once('.').unwrap_or((rest, ""));
+    let body = bs64::to_bytes(body).map_err(|e| Error::base64(Segment::Payload, e))?;
+    let footer = bs64::to_bytes(footer).map_err(|e| Error::base64(Segment::Signature, e))?;
+    Ok((body, footer))
+}
+
+/// The payload as JSON, with the time claims as RFC 3339 date-times.
+fn encode_payload(payload: &impl Serialize) -> Result<Vec<u8>, Error> {
+    let mut payload = json::to_value(payload).map_err(|e| Error::json(Segment::Payload, e))?;
+    for name in TIME_CLAIMS {
+        if let Some(value) = payload.get_mut(name) {
+            if let Some(secs) = value.as_u64() {
+                *value = Value::String(time::to_rfc3339(secs));
+            }
+        }
+    }
+    json::to_vec(&payload).map_err(|e| Error::json(Segment::Payload, e))
+}
+
+/// The payload of JSON, with the time claims as seconds since UNIX_EPOCH.
+fn decode_payload<P: DeserializeOwned>(message: &[u8]) -> Result<P, Error> {
+    let mut payload: Value = json::from_slice(message).map_err(|e| Error::json(Segment::Payload, e))?;
+    for name in TIME_CLAIMS {
+        if let Some(value) = payload.get_mut(name) {
+            let secs = value.as_str().and_then(time::from_rfc3339)
+                .ok_or_else(|| Error::json(Segment::Payload, json::Error::custom(format!("invalid date-time of {}", name))))?;
+            *value = secs.into();
+        }
+    }
+    json::from_value(payload).map_err(|e| Error::json(Segment::Payload, e))
+}
@@ -0,0 +1,22 @@
+//! Minimal PEM reader
+
+use crate::{bs64, crypto};
+use crate::error::Error;
+
+/// Decode all PEM blocks with the specific label, e.g. "CERTIFICATE".
+pub fn decode(s: &str, label: &str) -> Result<Vec<Vec<u8>>, Error> {
+    let begin = format!("-----BEGIN {}-----", label);
+    let end = format!("-----END {}-----", label);
+    let mut blocks = Vec::new();
+    let mut rest = s;
+    while let Some(start) = rest.find(&begin) {
+        let tail = &rest[start + begin.len()..];
+        let stop = tail.find(&end).ok_or(Error::InvalidKey("malformed PEM"))?;
+        let body: String = tail[..stop].split_whitespace().collect();
+        let block = bs64::std_to_bytes(&body);
+        crypto::wipe(&mut body.into_bytes());
+        blocks.push(block.map_err(|_| Error::InvalidKey("malformed PEM"))?);
+        rest = &tail[stop + end.len()..];
+    }
+    Ok(blocks)
+}
@@ -0,0 +1,97 @@
+//! Public key pinning by SPKI SHA-256 hash
+//!
+//! A pin is the standard base64 SHA-256 hash of the DER-encoded SubjectPublicKeyInfo of a key, as
+//! computed by `openssl pkey -pubin -outform der | openssl dgst -sha256 -binary | base64`.
+//! `VerifyWithPinnedKey` only verifies with pinned keys, so that a compromised JWK Set endpoint
+//! cannot introduce its own keys.
+//!
+//! ```rust
+//! use jwts::{Claims, jws};
+//! use jwts::jws::Header;
+//! use jwts::jws::alg::ES256;
+//! use jwts::pin::VerifyWithPinnedKey;
+//!
+//! let token = jws::encode::<ES256>(Header::default(), &Claims::default(), include_bytes!("../tests/ecdsa-pri.pk8")).unwrap();
+//! let key = jwts::key::EcPublicKey::from_spki_der(include_bytes!("../tests/ecdsa-pub.der")).unwrap();
+//! let keys = |_: &Header, _: &Claims| Some(key.clone());
+//! let verify = VerifyWithPinnedKey::<ES256, _>::new(keys, ["KYHpS3GVClsvzF9RsmX/z5ffVHDOeOCeDtUq/JWsIII="]);
+//! jws::decode::<Claims>(&token, verify).unwrap();
+//! ```
+
+use std::collections::HashSet;
+use std::marker::PhantomData;
+
+use crate::{bs64, crypto, der};
+use crate::error::Error;
+use crate::jws::{Algorithm, Header, Verify};
+use crate::keystore::KeyStore;
+
+/// AlgorithmIdentifier of rsaEncryption with NULL parameters.
+const RSA: &[u8] = &[0x30, 0x0d, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01, 0x05, 0x00];
+/// AlgorithmIdentifier of id-ecPublicKey on prime256v1.
+const EC_P256: &[u8] = &[0x30, 0x13, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+/// AlgorithmIdentifier of id-ecPublicKey on secp384r1.
+const EC_P384: &[u8] = &[0x30, 0x10, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x22];
+/// AlgorithmIdentifier of id-Ed25519.
+const ED25519: &[u8] = &[0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70];
+
+/// The pin of a DER-encoded SubjectPublicKeyInfo.
+#[inline]
+pub fn spki_pin(spki: impl AsRef<[u8]>) -> String {
+    bs64::std_from_bytes(crypto::sha256(spki.as_ref()))
+}
+
+/// The pin of a verification key of the builtin algorithm named `alg`, symmetric keys have none.
+pub fn key_pin(alg: &str, key: &[u8]) -> Result<String, Error> {
+    let algorithm = match alg {
+        "RS256" | "RS384" | "RS512" | "PS256" | "PS384" | "PS512" => RSA,
+        "ES256" => EC_P256,
+        "ES384" => EC_P384,
+        "Ed25519" | "EdDSA" => ED25519,
+        _ => return Err(Error::InvalidKey("key of the algorithm cannot be pinned")),
+    };
+    let key = der::write(der::BIT_STRING, &[&[0], key].concat());
+    Ok(spki_pin(der::write(der::SEQUENCE, &[algorithm, &key].concat())))
+}
+
+/// Verify with the key selected by the `KeyStore`, only if its pin is one of the pins.
+pub struct VerifyWithPinnedKey<A: Algorithm, S> {
+    store: S,
+    pins: HashSet<String>,
+    _alg: PhantomData<A>,
+}
+
+impl<A: Algorithm, S> VerifyWithPinnedKey<A, S> {
+    /// Create a `VerifyWithPinnedKey` accepting the keys of the pins, see `key_pin` and `spki_pin`.
+    pub fn new(store: S, pins: impl IntoIterator<Item=impl Into<String>>) -> Self {
+        VerifyWithPinnedKey {
+            store,
+            pins: pins.into_iter().map(Into::into).collect(),
+            _alg: PhantomData,
+        }
+    }
+}
+
+impl<A: Algorithm, S: Clone> Clone for VerifyWithPinnedKey<A, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        VerifyWithPinnedKey {
+            store: self.store.clone(),
+            pins: self.pins.clone(),
+            _alg: PhantomData,
+        }
+    }
+}
+
+impl<P, A: Algorithm<VerifyKey=[u8]>, S: KeyStore<P>> Verify<P> for VerifyWithPinnedKey<A, S>
+where
+    S::Key: AsRef<[u8]>,
+{
+    fn verify(&self, f2s: &str, signature: &[u8], header: &Header, payload: &P) -> Result<(), Error> {
+        let key = self.store.key_for(header, payload).ok_or(Error::InvalidKey("no matching key"))?;
+        if !self.pins.contains(&key_pin(A::name(), key.as_ref())?) {
+            return Err(Error::Rejected("key not pinned"));
+        }
+        A::verify(f2s, signature, key.as_ref())
+    }
+}
@@ -0,0 +1,18 @@
+//! Common items in one import
+//!
+//! ```rust
+//! use jwts::prelude::*;
+//!
+//! let claims = Claims {
+//!     iss: Some("sea".to_owned()),
+//!     ..Default::default()
+//! };
+//! let token = encode::<HS256>(Header::default(), &claims, b"secret").unwrap();
+//! let Token { payload, .. } = decode::<Claims>(&token, VerifyWith::<HS256>(b"secret")).unwrap();
+//! payload.validate(ExpectIss("sea")).unwrap();
+//! ```
+
+pub use crate::claims::Claims;
+pub use crate::jws::{decode, decode_header, encode, Header, Token, VerifyWith};
+pub use crate::jws::alg::*;
+pub use crate::validate::{ExpectAud, ExpectIss, ExpectJti, ExpectSub, ExpiredTime, IssuedAtTime, NotBeforeTime, Validate, Validation};
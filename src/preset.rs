@@ -0,0 +1,197 @@
+//! Issuer presets for common identity providers
+//!
+//! ```rust
+//! use jwts::Claims;
+//! use jwts::preset::Preset;
+//! use jwts::validate::Validate;
+//!
+//! let claims = Claims {
+//!     iss: Some("https://accounts.google.com".to_owned()),
+//!     aud: Some("client-id".to_owned()),
+//!     ..Default::default()
+//! };
+//! claims.validate(Preset::google("client-id")).unwrap();
+//! ```
+//!
+//! As a validation alone, a preset checks the claims only. `Verifier::from_preset` also pins the
+//! algorithm of the preset and verifies with the keys of its JWK Set:
+//!
+//! ```rust,no_run
+//! use jwts::{Claims, Verifier};
+//! use jwts::preset::Preset;
+//! use jwts::remote::HttpGet;
+//!
+//! # async fn run(http: &impl HttpGet, token: &str) -> Result<(), Box<dyn std::error::Error>> {
+//! let verifier = Verifier::from_preset_with_http(&Preset::google("client-id"), http).await?;
+//! let token = verifier.verify::<Claims>(token)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::Arc;
+
+use serde::Serialize;
+use serde_json as json;
+use serde_json::Value;
+
+use crate::error::Error;
+use crate::jwk::JwkSet;
+use crate::jws::{Alg, Header, Verify};
+use crate::keystore::KeyStore;
+use crate::remote::{self, HttpGet};
+use crate::validate::{claim_string, ClaimsPolicy, ValidateError, Validation};
+use crate::verifier::Verifier;
+
+/// Placeholder in an issuer template, substituted with the `tid` claim of the token.
+const TENANT_ID: &str = "{tenantid}";
+
+/// Conventions of tokens issued by a specific identity provider.
+///
+/// A preset works as a claims `Validation` checking `iss` and `aud` the way the provider
+/// expects, time claims should still be validated with the builtin validations. The
+/// `algorithm` and `jwks_uri` are enforced by a `Verifier::from_preset` only.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Preset {
+    /// Accepted `iss` values, `{tenantid}` is substituted with the `tid` claim
+    pub issuers: Vec<String>,
+    /// Expected `aud` value
+    pub audience: String,
+    /// URL of the JSON Web Key Set publishing the signing keys
+    pub jwks_uri: String,
+    /// Name of the signing algorithm
+    pub algorithm: &'static str,
+    /// Whether a non-empty `sub` claim is required
+    pub require_sub: bool,
+}
+
+impl Preset {
+    /// Google Sign-In ID tokens, the audience is the OAuth client ID.
+    pub fn google(client_id: impl Into<String>) -> Self {
+        Preset {
+            issuers: vec!["https://accounts.google.com".to_owned(), "accounts.google.com".to_owned()],
+            audience: client_id.into(),
+            jwks_uri: "https://www.googleapis.com/oauth2/v3/certs".to_owned(),
+            algorithm: "RS256",
+            require_sub: false,
+        }
+    }
+
+    /// Microsoft Entra ID v2.0 tokens, the audience is the application (client) ID.
+    ///
+    /// For the multi-tenant endpoints (`common`, `organizations` and `consumers`), the issuer
+    /// is checked against the tenant the token declares in its `tid` claim.
+    pub fn microsoft(tenant: &str, client_id: impl Into<String>) -> Self {
+        let issuer_tenant = match tenant {
+            "common" | "organizations" | "consumers" => TENANT_ID,
+            _ => tenant,
+        };
+        Preset {
+            issuers: vec![format!("https://login.microsoftonline.com/{}/v2.0", issuer_tenant)],
+            audience: client_id.into(),
+            jwks_uri: format!("https://login.microsoftonline.com/{}/discovery/v2.0/keys", tenant),
+            algorithm: "RS256",
+            require_sub: false,
+        }
+    }
+
+    /// Sign in with Apple ID tokens, the audience is the bundle ID or services ID.
+    pub fn apple(client_id: impl Into<String>) -> Self {
+        Preset {
+            issuers: vec!["https://appleid.apple.com".to_owned()],
+            audience: client_id.into(),
+            jwks_uri: "https://appleid.apple.com/auth/keys".to_owned(),
+            algorithm: "RS256",
+            require_sub: false,
+        }
+    }
+
+    /// Firebase Authentication ID tokens, the audience is the Firebase project ID.
+    pub fn firebase(project_id: &str) -> Self {
+        Preset {
+            issuers: vec![format!("https://securetoken.google.com/{}", project_id)],
+            audience: project_id.to_owned(),
+            jwks_uri: "https://www.googleapis.com/service_accounts/v1/jwk/securetoken@system.gserviceaccount.com".to_owned(),
+            algorithm: "RS256",
+            require_sub: true,
+        }
+    }
+
+    /// The accepted issuers, with the tenant placeholder substituted from the `tid` claim.
+    fn issuers_for(&self, claims: &Value) -> Vec<String> {
+        self.issuers.iter()
+            .map(|issuer| match claims["tid"].as_str() {
+                Some(tid) if issuer.contains(TENANT_ID) => issuer.replace(TENANT_ID, tid),
+                _ => issuer.clone(),
+            })
+            .collect()
+    }
+}
+
+/// The signature verification of a `Preset`, with its algorithm and the keys of its JWK Set.
+#[derive(Debug, Clone)]
+pub struct PresetVerify {
+    alg: Alg,
+    jwks: Arc<JwkSet>,
+}
+
+impl Verifier<PresetVerify> {
+    /// Create a `Verifier` of the preset with the keys of its JWK Set, e.g. fetched from
+    /// `jwks_uri` by the caller.
+    ///
+    /// Only the algorithm of the preset is accepted, `exp` is required, `nbf` and `iat` are
+    /// validated if present, and the claims are validated by the preset.
+    pub fn from_preset(preset: &Preset, jwks: JwkSet) -> Self {
+        let alg = Alg::from(preset.algorithm);
+        Verifier::new(PresetVerify { alg: alg.clone(), jwks: Arc::new(jwks) })
+            .with_algorithms([alg])
+            .with_claims_policy(ClaimsPolicy { issuer: None, audience: None, leeway: 0, required_claims: Vec::new() })
+            .with_validation(preset.clone())
+    }
+
+    /// Create a `Verifier` of the preset, fetching the keys of its `jwks_uri` once.
+    pub async fn from_preset_with_http(preset: &Preset, http: &impl HttpGet) -> Result<Self, Error> {
+        let jwks = remote::fetch_jwks(http, &preset.jwks_uri).await?;
+        Ok(Verifier::from_preset(preset, jwks))
+    }
+}
+
+impl<P> Verify<P> for PresetVerify {
+    fn verify(&self, f2s: &str, signature: &[u8], header: &Header, payload: &P) -> Result<(), Error> {
+        if header.alg.as_ref() != Some(&self.alg) {
+            return Err(Error::Rejected("algorithm not allowed"));
+        }
+        let key = self.jwks.key_for(header, payload).ok_or(Error::InvalidKey("no matching key"))?;
+        self.alg.verify(f2s, signature, &key)
+    }
+}
+
+impl<T: Serialize> Validation<T> for Preset {
+    type Error = ValidateError;
+
+    fn validate(&self, claims: &T) -> Result<(), Self::Error> {
+        let claims = json::to_value(claims).unwrap_or_default();
+        let issuers = self.issuers_for(&claims);
+        let iss = &claims["iss"];
+        if !iss.as_str().is_some_and(|x| issuers.iter().any(|issuer| issuer == x)) {
+            return Err(ValidateError::InvalidIss { expected: issuers.join(" or "), actual: claim_string(iss) });
+        }
+        let aud = &claims["aud"];
+        let aud_matches = match aud.as_array() {
+            Some(auds) => auds.iter().any(|x| x.as_str() == Some(&self.audience)),
+            None => aud.as_str() == Some(&self.audience),
+        };
+        if !aud_matches {
+            return Err(ValidateError::InvalidAud { expected: self.audience.clone(), actual: claim_string(aud) });
+        }
+        let sub = &claims["sub"];
+        if self.require_sub && sub.as_str().is_none_or(|x| x.is_empty()) {
+            return Err(ValidateError::InvalidSub { expected: "any".to_owned(), actual: claim_string(sub) });
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn validates_claim(&self, name: &str) -> bool {
+        matches!(name, "iss" | "aud") || (name == "sub" && self.require_sub)
+    }
+}
@@ -0,0 +1,228 @@
+//! Redacted `Debug` output
+//!
+//! The `Debug` implementations of the crate do not print secrets, so that logging a token or a
+//! key with `{:?}` does not leak credentials: signatures are truncated, the `sub` claim, the
+//! private claims of JSON payloads decoded as `Masked`, secret and private key parameters are
+//! masked, and verifiers and issuers omit their keys. Wrap a value in `Reveal` to print it in full.
+//!
+//! ```rust
+//! use jwts::Claims;
+//! use jwts::redact::Reveal;
+//!
+//! let claims = Claims {
+//!     sub: Some("user@example.com".to_owned()),
+//!     ..Default::default()
+//! };
+//! assert!(!format!("{:?}", claims).contains("user@example.com"));
+//! assert!(format!("{:?}", Reveal(&claims)).contains("user@example.com"));
+//! ```
+
+use std::fmt::{Debug, Formatter};
+use std::ops::{Deref, DerefMut};
+
+use serde_derive::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::claims::Claims;
+use crate::jwk::Jwk;
+use crate::jws::{Signature, Token};
+use crate::oauth::TokenResponse;
+use crate::session::TokenPair;
+
+/// Parameters of a JWK that are secret, see https://tools.ietf.org/html/rfc7518#section-6
+pub(crate) const SECRET_JWK_PARAMS: [&str; 8] = ["k", "d", "p", "q", "dp", "dq", "qi", "oth"];
+
+/// Prints the value without redaction, for the types of the crate and the JSON values.
+#[derive(Copy, Clone)]
+pub struct Reveal<'a, T: ?Sized>(pub &'a T);
+
+/// A JSON payload printing its claims masked but the registered ones other than `sub`, e.g. to
+/// decode a `Token<Masked<Value>>` instead of a `Token<Value>`.
+///
+/// ```rust
+/// use jwts::jws::{self, DangerousNoVerify, Header};
+/// use jwts::jws::alg::HS256;
+/// use jwts::redact::{Masked, Reveal};
+/// use serde_json::{json, Value};
+///
+/// let claims = json!({"iss": "sea", "email": "user@example.com"});
+/// let token = jws::encode::<HS256>(Header::default(), &claims, b"secret").unwrap();
+/// let token = jws::decode::<Masked<Value>>(&token, DangerousNoVerify).unwrap();
+/// assert!(!format!("{:?}", token).contains("user@example.com"));
+/// assert!(format!("{:?}", Reveal(&token)).contains("user@example.com"));
+/// assert_eq!(token.payload["iss"], "sea");
+/// ```
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Masked<T>(pub T);
+
+impl<T> Deref for Masked<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Masked<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl Debug for Masked<Value> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            Value::Object(claims) => RedactedClaims(claims).fmt(f),
+            _ => Redacted.fmt(f),
+        }
+    }
+}
+
+impl Debug for Masked<Map<String, Value>> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        RedactedClaims(&self.0).fmt(f)
+    }
+}
+
+/// Printed in place of a secret.
+pub(crate) struct Redacted;
+
+impl Debug for Redacted {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+
+/// Registered claims printed in clear, as by the `Debug` of `Claims`.
+const CLEAR_CLAIMS: [&str; 6] = ["iss", "aud", "exp", "nbf", "iat", "jti"];
+
+/// The claims of a JSON payload, the values masked but the ones of `CLEAR_CLAIMS`.
+pub(crate) struct RedactedClaims<'a>(pub &'a Map<String, Value>);
+
+impl Debug for RedactedClaims<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(self.0.iter().map(|(name, value)| match CLEAR_CLAIMS.contains(&name.as_str()) {
+                true => (name, value as &dyn Debug),
+                false => (name, &Redacted as &dyn Debug),
+            }))
+            .finish()
+    }
+}
+
+/// `Some(<redacted>)` or `None`.
+#[inline]
+pub(crate) fn mask<T>(value: &Option<T>) -> Option<Redacted> {
+    value.as_ref().map(|_| Redacted)
+}
+
+impl Debug for Reveal<'_, Claims> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let claims = self.0;
+        f.debug_struct("Claims")
+            .field("iss", &claims.iss)
+            .field("sub", &claims.sub)
+            .field("aud", &claims.aud)
+            .field("exp", &claims.exp)
+            .field("nbf", &claims.nbf)
+            .field("iat", &claims.iat)
+            .field("jti", &claims.jti)
+            .finish()
+    }
+}
+
+impl Debug for Reveal<'_, Signature> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Signature").field(&self.0.as_slice()).finish()
+    }
+}
+
+impl<P> Debug for Reveal<'_, Token<P>>
+where
+    for<'a> Reveal<'a, P>: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Token")
+            .field("header", &self.0.header)
+            .field("payload", &Reveal(&self.0.payload))
+            .field("signature", &Reveal(&self.0.signature))
+            .finish()
+    }
+}
+
+impl Debug for Reveal<'_, Jwk> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let jwk = self.0;
+        f.debug_struct("Jwk")
+            .field("kty", &jwk.kty)
+            .field("use", &jwk.r#use)
+            .field("key_ops", &jwk.key_ops)
+            .field("alg", &jwk.alg)
+            .field("kid", &jwk.kid)
+            .field("x5u", &jwk.x5u)
+            .field("x5c", &jwk.x5c)
+            .field("x5t", &jwk.x5t)
+            .field("x5t_s256", &jwk.x5t_s256)
+            .field("crv", &jwk.crv)
+            .field("x", &jwk.x)
+            .field("y", &jwk.y)
+            .field("n", &jwk.n)
+            .field("e", &jwk.e)
+            .field("k", &jwk.k)
+            .field("extra", &jwk.extra)
+            .finish()
+    }
+}
+
+impl Debug for Reveal<'_, TokenPair> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenPair")
+            .field("access_token", &self.0.access_token)
+            .field("refresh_token", &self.0.refresh_token)
+            .finish()
+    }
+}
+
+impl Debug for Reveal<'_, TokenResponse> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenResponse")
+            .field("access_token", &self.0.access_token)
+            .field("token_type", &self.0.token_type)
+            .field("expires_in", &self.0.expires_in)
+            .field("refresh_token", &self.0.refresh_token)
+            .field("id_token", &self.0.id_token)
+            .field("scope", &self.0.scope)
+            .field("extra", &self.0.extra)
+            .finish()
+    }
+}
+
+impl Debug for Reveal<'_, Value> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Debug for Reveal<'_, Map<String, Value>> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T> Debug for Reveal<'_, Masked<T>>
+where
+    for<'a> Reveal<'a, T>: Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Reveal(&self.0.0).fmt(f)
+    }
+}
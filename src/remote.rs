@@ -0,0 +1,189 @@
+//! Remote key retrieval of JWK Sets, OpenID Connect discovery and the `jku` and `x5u` header parameters
+//!
+//! Following URLs from an unverified header lets the token choose its own key, so keys are only
+//! fetched from the HTTPS hosts explicitly allowed.
+//!
+//! Everything is fetched with an `HttpGet`, implemented for `reqwest::Client` with the feature
+//! `reqwest` and for the `hyper_util` legacy client with the feature `hyper`, so the crate does
+//! not depend on a specific HTTP stack. Keys referenced by a token are resolved by a
+//! `RemoteKeyStore` for `jws::decode_async`.
+//!
+//! ```rust
+//! use jwts::{Claims, Error, jws};
+//! use jwts::jws::alg::Ed25519;
+//! use jwts::remote::{HttpGet, RemoteKeyStore};
+//!
+//! # async fn run(http: &(impl HttpGet + Sync), token: &str) -> Result<(), Error> {
+//! let store = RemoteKeyStore::<Ed25519, _>::new(&["idp.example.com"], http);
+//! let token = jws::decode_async::<Ed25519, Claims>(token, store).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::future::Future;
+use std::marker::PhantomData;
+
+use serde_derive::{Deserialize, Serialize};
+use serde_json as json;
+use serde_json::{Map, Value};
+
+use crate::{der, pem};
+use crate::error::Error;
+use crate::jwk::JwkSet;
+use crate::jws::{Alg, Algorithm, Header};
+use crate::keystore::AsyncKeyStore;
+
+/// Asynchronous HTTP GET, the body of a successful response or else `Error::Fetch`.
+pub trait HttpGet {
+    fn get(&self, url: &str) -> impl Future<Output=Result<Vec<u8>, Error>> + Send;
+}
+
+impl<T: HttpGet + Sync> HttpGet for &T {
+    #[inline]
+    fn get(&self, url: &str) -> impl Future<Output=Result<Vec<u8>, Error>> + Send {
+        (**self).get(url)
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl HttpGet for reqwest::Client {
+    async fn get(&self, url: &str) -> Result<Vec<u8>, Error> {
+        let res = reqwest::Client::get(self, url).send().await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(Error::fetch)?;
+        Ok(res.bytes().await.map_err(Error::fetch)?.to_vec())
+    }
+}
+
+#[cfg(feature = "hyper")]
+impl<C, B> HttpGet for hyper_util::client::legacy::Client<C, B>
+where
+    C: hyper_util::client::legacy::connect::Connect + Clone + Send + Sync + 'static,
+    B: hyper::body::Body + Default + Send + Unpin + 'static,
+    B::Data: Send,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    async fn get(&self, url: &str) -> Result<Vec<u8>, Error> {
+        use http_body_util::BodyExt;
+
+        let uri = url.parse::<http::Uri>().map_err(Error::fetch)?;
+        let res = hyper_util::client::legacy::Client::get(self, uri).await.map_err(Error::fetch)?;
+        if !res.status().is_success() {
+            return Err(Error::fetch(format!("HTTP status {} of {}", res.status(), url)));
+        }
+        Ok(res.into_body().collect().await.map_err(Error::fetch)?.to_bytes().to_vec())
+    }
+}
+
+/// OpenID Provider Metadata, see https://openid.net/specs/openid-connect-discovery-1_0.html#ProviderMetadata
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ProviderMetadata {
+    pub issuer: String,
+    pub jwks_uri: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub id_token_signing_alg_values_supported: Vec<String>,
+    /// Other metadata
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Fetch the JWK Set at the URL.
+pub async fn fetch_jwks(http: &impl HttpGet, url: &str) -> Result<JwkSet, Error> {
+    jwks_from_json(&http.get(url).await?)
+}
+
+/// Fetch the metadata of the issuer from its `/.well-known/openid-configuration`, which must
+/// name the same issuer.
+pub async fn discover(http: &impl HttpGet, issuer: &str) -> Result<ProviderMetadata, Error> {
+    let url = format!("{}/.well-known/openid-configuration", issuer.trim_end_matches('/'));
+    let metadata: ProviderMetadata = json::from_slice(&http.get(&url).await?).map_err(Error::fetch)?;
+    if metadata.issuer != issuer {
+        return Err(Error::Rejected("issuer of the provider metadata mismatch"));
+    }
+    Ok(metadata)
+}
+
+/// Fetch the key referenced by `jku`, or else the leaf certificate referenced by `x5u`, the way
+/// `RemoteKeyStore` does.
+pub async fn fetch_key<A: Algorithm>(http: &impl HttpGet, hosts: &[&str], header: &Header) -> Result<Vec<u8>, Error> {
+    match (&header.jku, &header.x5u) {
+        (Some(jku), _) => key_from_jwks::<A>(&http.get(allowed(hosts, jku)?).await?, header),
+        (None, Some(x5u)) => key_from_pem_chain(&http.get(allowed(hosts, x5u)?).await?),
+        (None, None) => Err(Error::InvalidKey("missing jku or x5u")),
+    }
+}
+
+/// Resolves the key referenced by `jku`, or else the leaf certificate referenced by `x5u`, for
+/// `jws::decode_async`, fetched only when the URL is HTTPS and its host (with port, if any) is
+/// allowed.
+///
+/// Keys of a JWK Set are selected by `kid`, the leaf certificate from `x5u` is used as-is,
+/// trusting the allowed host.
+pub struct RemoteKeyStore<'a, A: Algorithm, H: HttpGet> {
+    hosts: &'a [&'a str],
+    http: H,
+    _alg: PhantomData<A>,
+}
+
+impl<'a, A: Algorithm, H: HttpGet> RemoteKeyStore<'a, A, H> {
+    #[inline]
+    pub fn new(hosts: &'a [&'a str], http: H) -> Self {
+        RemoteKeyStore {
+            hosts,
+            http,
+            _alg: PhantomData,
+        }
+    }
+}
+
+impl<'a, P, A: Algorithm, H: HttpGet + Sync> AsyncKeyStore<P> for RemoteKeyStore<'a, A, H> {
+    type Key = Vec<u8>;
+
+    #[inline]
+    fn resolve(&self, header: &Header, _payload: &P) -> impl Future<Output=Result<Vec<u8>, Error>> + Send {
+        fetch_key::<A>(&self.http, self.hosts, header)
+    }
+}
+
+fn jwks_from_json(json: &[u8]) -> Result<JwkSet, Error> {
+    json::from_slice(json).map_err(|_| Error::InvalidKey("malformed jwk set"))
+}
+
+/// The key of the JWK Set selected by the `kid` of the header, of the key type of `A`.
+fn key_from_jwks<A: Algorithm>(json: &[u8], header: &Header) -> Result<Vec<u8>, Error> {
+    let jwks = jwks_from_json(json)?;
+    let jwk = match &header.kid {
+        Some(kid) => jwks.find(kid),
+        None if jwks.keys.len() == 1 => jwks.keys.first(),
+        None => None,
+    };
+    jwk.filter(|x| x.alg.as_ref().is_none_or(|alg| alg.as_str() == A::name()))
+        .filter(|x| Alg::from(A::name()).kty().is_none_or(|kty| kty == x.kty))
+        .ok_or(Error::InvalidKey("no matching key"))?
+        .to_verify_key()
+}
+
+/// The public key of the leaf certificate of a PEM chain.
+fn key_from_pem_chain(chain: &[u8]) -> Result<Vec<u8>, Error> {
+    let chain = std::str::from_utf8(chain).map_err(|_| Error::InvalidKey("malformed PEM"))?;
+    let chain = pem::decode(chain, "CERTIFICATE")?;
+    let leaf = chain.first().ok_or(Error::InvalidKey("empty certificate chain"))?;
+    let key = der::spki_public_key(der::certificate_spki(leaf)?)?;
+    Ok(key.to_owned())
+}
+
+/// The URL if it is HTTPS and its host is allowed.
+fn allowed<'u>(hosts: &[&str], url: &'u str) -> Result<&'u str, Error> {
+    https_authority(url)
+        .filter(|x| hosts.iter().any(|host| host.eq_ignore_ascii_case(x)))
+        .ok_or(Error::Rejected("key URL not allowed"))?;
+    Ok(url)
+}
+
+/// The authority (host with optional port) of an HTTPS URL without userinfo.
+fn https_authority(url: &str) -> Option<&str> {
+    let rest = url.strip_prefix("https://")?;
+    let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let authority = &rest[..end];
+    (!authority.is_empty() && !authority.contains('@')).then_some(authority)
+}
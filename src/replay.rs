@@ -0,0 +1,136 @@
+//! Replay protection of one-time tokens by `jti`
+//!
+//! A `ReplayGuard` remembers the `jti` of the accepted tokens until they expire, `NotReplayed`
+//! validates with it. Add it as the last validation of a `Verifier`, so that a token rejected by
+//! another validation does not use up its `jti`.
+//!
+//! ```rust
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//! use jwts::{Claims, jws, Verifier};
+//! use jwts::jws::{Header, VerifyWith};
+//! use jwts::jws::alg::HS256;
+//! use jwts::replay::{MemoryReplayGuard, NotReplayed};
+//! use jwts::validate::ExpiredTime;
+//!
+//! let guard = Arc::new(MemoryReplayGuard::new());
+//! let verifier = Verifier::new(VerifyWith::<HS256>(b"secret"))
+//!     .with_validation(ExpiredTime)
+//!     .with_validation(NotReplayed(guard.clone()));
+//!
+//! let claims = Claims { jti: Some("once".to_owned()), ..Default::default() }.expired_in(Duration::from_secs(60));
+//! let token = jws::encode::<HS256>(Header::default(), &claims, b"secret").unwrap();
+//! assert!(verifier.verify::<Claims>(&token).is_ok());
+//! assert!(verifier.verify::<Claims>(&token).is_err());
+//! ```
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use serde_json as json;
+
+use crate::time;
+use crate::validate::{claim_string, ValidateError, Validation};
+
+/// Remembers the `jti` of used tokens.
+pub trait ReplayGuard {
+    /// Store the `jti` until `exp`, `false` if it is already stored.
+    fn check_and_store(&self, jti: &str, exp: u64) -> bool;
+}
+
+impl<G: ReplayGuard + ?Sized> ReplayGuard for &G {
+    #[inline]
+    fn check_and_store(&self, jti: &str, exp: u64) -> bool {
+        (**self).check_and_store(jti, exp)
+    }
+}
+
+impl<G: ReplayGuard + ?Sized> ReplayGuard for Arc<G> {
+    #[inline]
+    fn check_and_store(&self, jti: &str, exp: u64) -> bool {
+        (**self).check_and_store(jti, exp)
+    }
+}
+
+/// In-memory `ReplayGuard` of a single process, forgetting each `jti` once its token expired.
+/// Clones share the seen `jti`.
+#[derive(Debug, Default, Clone)]
+pub struct MemoryReplayGuard {
+    seen: Arc<Mutex<Seen>>,
+}
+
+#[derive(Debug, Default)]
+struct Seen {
+    /// jti -> exp
+    entries: HashMap<String, u64>,
+    /// (exp, jti)
+    expiry: BTreeSet<(u64, String)>,
+}
+
+impl MemoryReplayGuard {
+    /// Create an empty `MemoryReplayGuard`.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of stored `jti`, expired ones included until the next store.
+    pub fn len(&self) -> usize {
+        self.seen.lock().unwrap_or_else(|e| e.into_inner()).entries.len()
+    }
+
+    /// Whether no `jti` is stored.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl ReplayGuard for MemoryReplayGuard {
+    fn check_and_store(&self, jti: &str, exp: u64) -> bool {
+        let now = time::now_secs();
+        let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+        while let Some((exp, _)) = seen.expiry.first() {
+            if *exp > now {
+                break;
+            }
+            let (_, jti) = seen.expiry.pop_first().unwrap();
+            seen.entries.remove(&jti);
+        }
+        if seen.entries.contains_key(jti) {
+            return false;
+        }
+        if exp > now {
+            seen.entries.insert(jti.to_owned(), exp);
+            seen.expiry.insert((exp, jti.to_owned()));
+        }
+        true
+    }
+}
+
+/// Validate that the `jti` was not used by a previous token, storing it in the `ReplayGuard`.
+/// Tokens without `jti` or `exp` are rejected.
+pub struct NotReplayed<G>(pub G);
+
+impl<G: ReplayGuard, T: Serialize> Validation<T> for NotReplayed<G> {
+    type Error = ValidateError;
+
+    fn validate(&self, claims: &T) -> Result<(), Self::Error> {
+        let claims = json::to_value(claims).unwrap_or_default();
+        let jti = claims["jti"].as_str()
+            .filter(|x| !x.is_empty())
+            .ok_or_else(|| ValidateError::InvalidJti { expected: "any".to_owned(), actual: claim_string(&claims["jti"]) })?;
+        let exp = claims["exp"].as_u64()
+            .ok_or(ValidateError::TokenExpiredAt { now: time::now_secs(), actual: None })?;
+        if !self.0.check_and_store(jti, exp) {
+            return Err(ValidateError::Replayed { jti: jti.to_owned() });
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn validates_claim(&self, name: &str) -> bool {
+        name == "jti"
+    }
+}
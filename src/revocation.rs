@@ -0,0 +1,108 @@
+//! Revocation of tokens before their `exp`
+//!
+//! A `RevocationCheck` tells whether a token is revoked by its `jti` or `sub`, `NotRevoked`
+//! validates with it.
+//!
+//! ```rust
+//! use std::sync::Arc;
+//! use jwts::{Claims, jws, Verifier};
+//! use jwts::jws::{Header, VerifyWith};
+//! use jwts::jws::alg::HS256;
+//! use jwts::revocation::{MemoryRevocationList, NotRevoked};
+//!
+//! let revoked = Arc::new(MemoryRevocationList::new());
+//! let verifier = Verifier::new(VerifyWith::<HS256>(b"secret")).with_validation(NotRevoked(revoked.clone()));
+//!
+//! let claims = Claims { sub: Some("sea".to_owned()), ..Default::default() };
+//! let token = jws::encode::<HS256>(Header::default(), &claims, b"secret").unwrap();
+//! assert!(verifier.verify::<Claims>(&token).is_ok());
+//! revoked.revoke_sub("sea");
+//! assert!(verifier.verify::<Claims>(&token).is_err());
+//! ```
+
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+
+use serde::Serialize;
+use serde_json as json;
+
+use crate::validate::{ValidateError, Validation};
+
+/// Tells whether a token is revoked.
+pub trait RevocationCheck {
+    /// Whether the token of the `jti` and `sub` claims is revoked.
+    fn is_revoked(&self, jti: Option<&str>, sub: Option<&str>) -> bool;
+}
+
+impl<R: RevocationCheck + ?Sized> RevocationCheck for &R {
+    #[inline]
+    fn is_revoked(&self, jti: Option<&str>, sub: Option<&str>) -> bool {
+        (**self).is_revoked(jti, sub)
+    }
+}
+
+impl<R: RevocationCheck + ?Sized> RevocationCheck for Arc<R> {
+    #[inline]
+    fn is_revoked(&self, jti: Option<&str>, sub: Option<&str>) -> bool {
+        (**self).is_revoked(jti, sub)
+    }
+}
+
+/// In-memory sets of revoked `jti` and `sub`, shared by clones.
+#[derive(Debug, Default, Clone)]
+pub struct MemoryRevocationList {
+    jtis: Arc<RwLock<HashSet<String>>>,
+    subs: Arc<RwLock<HashSet<String>>>,
+}
+
+impl MemoryRevocationList {
+    /// Create an empty `MemoryRevocationList`.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Revoke the token of the `jti`.
+    pub fn revoke_jti(&self, jti: impl Into<String>) {
+        self.jtis.write().unwrap_or_else(|e| e.into_inner()).insert(jti.into());
+    }
+
+    /// Revoke all tokens of the `sub`.
+    pub fn revoke_sub(&self, sub: impl Into<String>) {
+        self.subs.write().unwrap_or_else(|e| e.into_inner()).insert(sub.into());
+    }
+
+    /// Accept the token of the `jti` again.
+    pub fn restore_jti(&self, jti: &str) {
+        self.jtis.write().unwrap_or_else(|e| e.into_inner()).remove(jti);
+    }
+
+    /// Accept the tokens of the `sub` again.
+    pub fn restore_sub(&self, sub: &str) {
+        self.subs.write().unwrap_or_else(|e| e.into_inner()).remove(sub);
+    }
+}
+
+impl RevocationCheck for MemoryRevocationList {
+    fn is_revoked(&self, jti: Option<&str>, sub: Option<&str>) -> bool {
+        jti.is_some_and(|x| self.jtis.read().unwrap_or_else(|e| e.into_inner()).contains(x))
+            || sub.is_some_and(|x| self.subs.read().unwrap_or_else(|e| e.into_inner()).contains(x))
+    }
+}
+
+/// Validate that the token is not revoked by the `RevocationCheck`.
+pub struct NotRevoked<R>(pub R);
+
+impl<R: RevocationCheck, T: Serialize> Validation<T> for NotRevoked<R> {
+    type Error = ValidateError;
+
+    fn validate(&self, claims: &T) -> Result<(), Self::Error> {
+        let claims = json::to_value(claims).unwrap_or_default();
+        let jti = claims["jti"].as_str();
+        let sub = claims["sub"].as_str();
+        if self.0.is_revoked(jti, sub) {
+            return Err(ValidateError::Revoked { jti: jti.map(str::to_owned), sub: sub.map(str::to_owned) });
+        }
+        Ok(())
+    }
+}
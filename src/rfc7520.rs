@@ -0,0 +1,145 @@
+//! Examples of RFC 7520, requires the feature `rfc7520`
+//!
+//! The JWS examples of the JOSE cookbook (https://tools.ietf.org/html/rfc7520#section-4) in the
+//! compact serialization, with a runner verifying them with the enabled crypto backend, so that
+//! dependent projects can assert the interoperability of their configuration.
+//!
+//! The examples of the JSON serializations (4.6 to 4.8) are not included, as the crate only
+//! supports the compact one, nor the ES512 one (4.3). The PS384 signature of 4.2 is randomized,
+//! so that example is verified only.
+//!
+//! ```rust
+//! jwts::rfc7520::run_all().unwrap();
+//! ```
+
+use serde_json as json;
+
+use crate::bs64;
+use crate::error::{Error, Segment};
+use crate::jwk::Jwk;
+use crate::jws::{Alg, Algorithm, Header};
+use crate::jws::alg::{HS256, HS384, HS512, RS256};
+use crate::jws::stream;
+use crate::key::AnySignKey;
+
+/// RSA public key of section 3.3.
+pub const RSA_PUBLIC_KEY: &str = r#"{"kty":"RSA","kid":"bilbo.baggins@hobbiton.example","use":"sig","n":"n4EPtAOCc9AlkeQHPzHStgAbgs7bTZLwUBZdR8_KuKPEHLd4rHVTeT-O-XV2jRojdNhxJWTDvNd7nqQ0VEiZQHz_AJmSCpMaJMRBSFKrKb2wqVwGU_NsYOYL-QtiWN2lbzcEe6XC0dApr5ydQLrHqkHHig3RBordaZ6Aj-oBHqFEHYpPe7Tpe-OfVfHd1E6cS6M1FZcD1NNLYD5lFHpPI9bTwJlsde3uhGqC0ZCuEHg8lhzwOHrtIQbS0FVbb9k3-tVTU4fg_3L_vniUFAKwuCLqKnS2BYwdq_mzSnbLY7h_qixoR7jig3__kRhuaxwUkRz5iaiQkqgc5gHdrNP5zw","e":"AQAB"}"#;
+
+/// RSA private key of section 3.4.
+pub const RSA_PRIVATE_KEY: &str = r#"{"kty":"RSA","kid":"bilbo.baggins@hobbiton.example","use":"sig","n":"n4EPtAOCc9AlkeQHPzHStgAbgs7bTZLwUBZdR8_KuKPEHLd4rHVTeT-O-XV2jRojdNhxJWTDvNd7nqQ0VEiZQHz_AJmSCpMaJMRBSFKrKb2wqVwGU_NsYOYL-QtiWN2lbzcEe6XC0dApr5ydQLrHqkHHig3RBordaZ6Aj-oBHqFEHYpPe7Tpe-OfVfHd1E6cS6M1FZcD1NNLYD5lFHpPI9bTwJlsde3uhGqC0ZCuEHg8lhzwOHrtIQbS0FVbb9k3-tVTU4fg_3L_vniUFAKwuCLqKnS2BYwdq_mzSnbLY7h_qixoR7jig3__kRhuaxwUkRz5iaiQkqgc5gHdrNP5zw","e":"AQAB","d":"bWUC9B-EFRIo8kpGfh0ZuyGPvMNKvYWNtB_ikiH9k20eT-O1q_I78eiZkpXxXQ0UTEs2LsNRS-8uJbvQ-A1irkwMSMkK1J3XTGgdrhCku9gRldY7sNA_AKZGh-Q661_42rINLRCe8W-nZ34ui_qOfkLnK9QWDDqpaIsA-bMwWWSDFu2MUBYwkHTMEzLYGqOe04noqeq1hExBTHBOBdkMXiuFhUq1BU6l-DqEiWxqg82sXt2h-LMnT3046AOYJoRioz75tSUQfGCshWTBnP5uDjd18kKhyv07lhfSJdrPdM5Plyl21hsFf4L_mHCuoFau7gdsPfHPxxjVOcOpBrQzwQ","p":"3Slxg_DwTXJcb6095RoXygQCAZ5RnAvZlno1yhHtnUex_fp7AZ_9nRaO7HX_-SFfGQeutao2TDjDAWU4Vupk8rw9JR0AzZ0N2fvuIAmr_WCsmGpeNqQnev1T7IyEsnh8UMt-n5CafhkikzhEsrmndH6LxOrvRJlsPp6Zv8bUq0k","q":"uKE2dh-cTf6ERF4k4e_jy78GfPYUIaUyoSSJuBzp3Cubk3OCqs6grT8bR_cu0Dm1MZwWmtdqDyI95HrUeq3MP15vMMON8lHTeZu2lmKvwqW7anV5UzhM1iZ7z4yMkuUwFWoBvyY898EXvRD-hdqRxHlSqAZ192zB3pVFJ0s7pFc","dp":"B8PVvXkvJrj2L-GYQ7v3y9r6Kw5g9SahXBwsWUzp19TVlgI-YV85q1NIb1rxQtD-IsXXR3-TanevuRPRt5OBOdiMGQp8pbt26gljYfKU_E9xn-RULHz0-ed9E9gXLKD4VGngpz-PfQ_q29pk5xWHoJp009Qf1HvChixRX59ehik","dq":"CLDmDGduhylc9o7r84rEUVn7pzQ6PF83Y-iBZx5NT-TpnOZKF1pErAMVeKzFEl41DlHHqqBLSM0W1sOFbwTxYWZDm6sI6og5iTbwQGIC3gnJKbi_7k_vJgGHwHxgPaX2PnvP-zyEkDERuf-ry4c_Z11Cq9AqC2yeL6kdKT1cYF8","qi":"3PiqvXQN0zwMeE-sBvZgi289XP9XCQF3VWqPzMKnIgQp7_Tugo6-NZBKCQsMf3HaEGBjTVJs_jcK8-TRXvaKe-7ZMaQj8VfBdYkssbu0NKDDhjJ-GtiseaDVWt7dcH0cfwxgFUHpQh7FoCrjFJ6h6ZEpMF6xmujs4qMpPz8aaI4"}"#;
+
+/// Symmetric key of section 3.5.
+pub const HMAC_KEY: &str = r#"{"kty":"oct","kid":"018c0ae5-4d9b-471b-bfd6-eef314bc7037","use":"sig","alg":"HS256","k":"hJtXIZ2uSN5kbQfbtTNWbpdmhkV8FJG-Onbc6mxCcYg"}"#;
+
+/// Payload of section 4, base64url encoded.
+pub const PAYLOAD: &str = "SXTigJlzIGEgZGFuZ2Vyb3VzIGJ1c2luZXNzLCBGcm9kbywgZ29pbmcgb3V0IHlvdXIgZG9vci4gWW91IHN0ZXAgb250byB0aGUgcm9hZCwgYW5kIGlmIHlvdSBkb24ndCBrZWVwIHlvdXIgZmVldCwgdGhlcmXigJlzIG5vIGtub3dpbmcgd2hlcmUgeW91IG1pZ2h0IGJlIHN3ZXB0IG9mZiB0by4";
+
+/// The JWS examples included.
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        section: "4.1",
+        title: "RSA v1.5 Signature",
+        jwk: RSA_PUBLIC_KEY,
+        protected: "eyJhbGciOiJSUzI1NiIsImtpZCI6ImJpbGJvLmJhZ2dpbnNAaG9iYml0b24uZXhhbXBsZSJ9",
+        payload: PAYLOAD,
+        signature: "MRjdkly7_-oTPTS3AXP41iQIGKa80A0ZmTuV5MEaHoxnW2e5CZ5NlKtainoFmKZopdHM1O2U4mwzJdQx996ivp83xuglII7PNDi84wnB-BDkoBwA78185hX-Es4JIwmDLJK3lfWRa-XtL0RnltuYv746iYTh_qHRD68BNt1uSNCrUCTJDt5aAE6x8wW1Kt9eRo4QPocSadnHXFxnt8Is9UzpERV0ePPQdLuW3IS_de3xyIrDaLGdjluPxUAhb6L2aXic1U12podGU0KLUQSE_oI-ZnmKJ3F4uOZDnd6QZWJushZ41Axf_fcIe8u9ipH84ogoree7vjbU5y18kDquDg",
+        detached: false,
+    },
+    Example {
+        section: "4.2",
+        title: "RSA-PSS Signature",
+        jwk: RSA_PUBLIC_KEY,
+        protected: "eyJhbGciOiJQUzM4NCIsImtpZCI6ImJpbGJvLmJhZ2dpbnNAaG9iYml0b24uZXhhbXBsZSJ9",
+        payload: PAYLOAD,
+        signature: "cu22eBqkYDKgIlTpzDXGvaFfz6WGoz7fUDcfT0kkOy42miAh2qyBzk1xEsnk2IpN6-tPid6VrklHkqsGqDqHCdP6O8TTB5dDDItllVo6_1OLPpcbUrhiUSMxbbXUvdvWXzg-UD8biiReQFlfz28zGWVsdiNAUf8ZnyPEgVFn442ZdNqiVJRmBqrYRXe8P_ijQ7p8Vdz0TTrxUeT3lm8d9shnr2lfJT8ImUjvAA2Xez2Mlp8cBE5awDzT0qI0n6uiP1aCN_2_jLAeQTlqRHtfa64QQSUmFAAjVKPbByi7xho0uTOcbH510a6GYmJUAfmWjwZ6oD4ifKo8DYM-X72Eaw",
+        detached: false,
+    },
+    Example {
+        section: "4.4",
+        title: "HMAC-SHA2 Integrity Protection",
+        jwk: HMAC_KEY,
+        protected: "eyJhbGciOiJIUzI1NiIsImtpZCI6IjAxOGMwYWU1LTRkOWItNDcxYi1iZmQ2LWVlZjMxNGJjNzAzNyJ9",
+        payload: PAYLOAD,
+        signature: "s0h6KThzkfBBBkLspW1h84VsJZFTsPPqMDA7g1Md7p0",
+        detached: false,
+    },
+    Example {
+        section: "4.5",
+        title: "Signature with Detached Content",
+        jwk: HMAC_KEY,
+        protected: "eyJhbGciOiJIUzI1NiIsImtpZCI6IjAxOGMwYWU1LTRkOWItNDcxYi1iZmQ2LWVlZjMxNGJjNzAzNyJ9",
+        payload: PAYLOAD,
+        signature: "s0h6KThzkfBBBkLspW1h84VsJZFTsPPqMDA7g1Md7p0",
+        detached: true,
+    },
+];
+
+/// A JWS example, the segments base64url encoded as in the RFC.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Example {
+    /// Section of the RFC
+    pub section: &'static str,
+    /// Title of the section
+    pub title: &'static str,
+    /// Verification key as JWK
+    pub jwk: &'static str,
+    /// Protected header
+    pub protected: &'static str,
+    /// Payload, also of the detached examples
+    pub payload: &'static str,
+    /// Signature
+    pub signature: &'static str,
+    /// Whether the token is in the detached form `header..signature`
+    pub detached: bool,
+}
+
+impl Example {
+    /// The token in the compact serialization.
+    pub fn token(&self) -> String {
+        let payload = if self.detached { "" } else { self.payload };
+        format!("{}.{}.{}", self.protected, payload, self.signature)
+    }
+
+    /// Check that the header is serialized as in the example and that the signature verifies,
+    /// and sign the example again if its algorithm is deterministic.
+    pub fn run(&self) -> Result<(), Error> {
+        let jwk: Jwk = json::from_str(self.jwk).map_err(|_| Error::InvalidKey("malformed jwk"))?;
+        let key = jwk.to_verify_key()?;
+        let decoded = bs64::to_bytes(self.protected).map_err(|e| Error::base64(Segment::Header, e))?;
+        let header: Header = json::from_slice(&decoded).map_err(|e| Error::json(Segment::Header, e))?;
+        if header.kid != jwk.kid {
+            return Err(Error::InvalidKey("kid mismatch"));
+        }
+        let encoded = json::to_vec(&header).map_err(|e| Error::json(Segment::Header, e))?;
+        if bs64::from_bytes(encoded) != self.protected {
+            return Err(Error::Rejected("header serialized differently"));
+        }
+
+        let alg = header.alg.clone().ok_or(Error::Rejected("missing alg"))?;
+        let signature = bs64::to_bytes(self.signature).map_err(|e| Error::base64(Segment::Signature, e))?;
+        alg.verify(format!("{}.{}", self.protected, self.payload), signature, &key)?;
+
+        let payload = bs64::to_bytes(self.payload).map_err(|e| Error::base64(Segment::Payload, e))?;
+        let signed = match alg {
+            Alg::HS256 => stream::sign_detached::<HS256>(header, payload.as_slice(), &key)?,
+            Alg::HS384 => stream::sign_detached::<HS384>(header, payload.as_slice(), &key)?,
+            Alg::HS512 => stream::sign_detached::<HS512>(header, payload.as_slice(), &key)?,
+            Alg::RS256 => {
+                let jwk: Jwk = json::from_str(RSA_PRIVATE_KEY).map_err(|_| Error::InvalidKey("malformed jwk"))?;
+                let AnySignKey::Rsa(key) = AnySignKey::from_jwk(&jwk)? else {
+                    return Err(Error::InvalidKey("not an RSA key"));
+                };
+                let signature = RS256::sign(format!("{}.{}", self.protected, self.payload), &key)?;
+                format!("{}..{}", self.protected, bs64::from_bytes(signature))
+            }
+            _ => return Ok(()),
+        };
+        let expected = format!("{}..{}", self.protected, self.signature);
+        (signed == expected).then_some(()).ok_or(Error::InvalidSignature)
+    }
+}
+
+/// Run all examples, the section of the first failed example with its error.
+pub fn run_all() -> Result<(), (&'static str, Error)> {
+    EXAMPLES.iter().try_for_each(|x| x.run().map_err(|e| (x.section, e)))
+}
@@ -0,0 +1,77 @@
+//! Secret key material wiped on drop, requires the feature `zeroize`
+//!
+//! `SecretKey` holds an HMAC secret or the DER of a private key in a buffer that is zeroed when
+//! dropped. It derefs to the bytes, so it is passed as the key of the HS and ES algorithms and
+//! to the `from_pkcs8` constructors of the key pairs as is. With the feature, the intermediate
+//! buffers of the crate holding secrets, such as decoded PEM blocks, Ed25519 seeds and PASETO
+//! subkeys, are wiped as well. The key pairs parsed from a `SecretKey` are owned by the crypto
+//! backend, which decides how they are cleared.
+//!
+//! ```rust
+//! use jwts::{Claims, jws};
+//! use jwts::jws::{Header, VerifyWith};
+//! use jwts::jws::alg::HS256;
+//! use jwts::secret::SecretKey;
+//!
+//! let key = SecretKey::new(b"secret".to_vec());
+//! let token = jws::encode::<HS256>(Header::default(), &Claims::default(), &key).unwrap();
+//! jws::decode::<Claims>(&token, VerifyWith::<HS256>(&key)).unwrap();
+//! assert_eq!(format!("{:?}", key), "SecretKey(<redacted>)");
+//! ```
+
+use std::fmt::{Debug, Formatter};
+use std::ops::Deref;
+
+pub use zeroize::{Zeroize, Zeroizing};
+
+use crate::error::Error;
+use crate::pem;
+use crate::redact::Redacted;
+
+/// Secret bytes, zeroed when dropped.
+#[derive(Clone)]
+pub struct SecretKey(Zeroizing<Vec<u8>>);
+
+impl SecretKey {
+    /// Take the bytes, they are zeroed along with the key.
+    #[inline]
+    pub fn new(bytes: Vec<u8>) -> Self {
+        SecretKey(Zeroizing::new(bytes))
+    }
+
+    /// Decode the first PEM block with the label, e.g. "PRIVATE KEY" for a PKCS#8 private key.
+    pub fn from_pem(pem: &str, label: &str) -> Result<Self, Error> {
+        let blocks: Vec<SecretKey> = pem::decode(pem, label)?.into_iter().map(SecretKey::new).collect();
+        blocks.into_iter().next().ok_or(Error::InvalidKey("no PEM block of the label"))
+    }
+}
+
+impl From<Vec<u8>> for SecretKey {
+    #[inline]
+    fn from(bytes: Vec<u8>) -> Self {
+        SecretKey::new(bytes)
+    }
+}
+
+impl Deref for SecretKey {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for SecretKey {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Debug for SecretKey {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("SecretKey").field(&Redacted).finish()
+    }
+}
@@ -0,0 +1,206 @@
+//! Sessions of access and refresh tokens
+//!
+//! `SessionManager` issues short-lived access tokens with long-lived refresh tokens. Each refresh
+//! rotates the refresh token, so that a refresh token is used only once; using an already rotated
+//! refresh token again means it leaked, and the whole session is revoked.
+//!
+//! Both are signed with the same key and told apart by the `typ` header, so verify the access
+//! tokens with `SessionManager::verify_access`, or check `typ` is `at+jwt` (RFC 9068) otherwise.
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use jwts::jws::alg::HS256;
+//! use jwts::session::SessionManager;
+//!
+//! let sessions = SessionManager::<HS256>::new(b"secret", b"secret", Duration::from_secs(300), Duration::from_secs(86400));
+//! let pair = sessions.start("sea").unwrap();
+//! let next = sessions.refresh(&pair.refresh_token).unwrap();
+//! assert_eq!(sessions.verify_access(&next.access_token).unwrap().payload.sub.as_deref(), Some("sea"));
+//! assert!(sessions.verify_access(&next.refresh_token).is_err());
+//!
+//! assert!(sessions.refresh(&pair.refresh_token).is_err()); // reused, the session is revoked
+//! assert!(sessions.refresh(&next.refresh_token).is_err());
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::{bs64, crypto, jws, time};
+use crate::claims::Claims;
+use crate::error::{Error, VerifyError};
+use crate::issuer::Issuer;
+use crate::jws::{Algorithm, Header, Token, VerifyWith};
+use crate::redact::Redacted;
+use crate::validate::{ExpiredTime, Validate};
+
+/// The `typ` header of access tokens, see https://tools.ietf.org/html/rfc9068#section-2.1
+pub const ACCESS_TYP: &str = "at+jwt";
+
+/// The `typ` header of refresh tokens.
+pub const REFRESH_TYP: &str = "refresh+jwt";
+
+/// Access and refresh token of a session.
+#[derive(Clone, Eq, PartialEq)]
+pub struct TokenPair {
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
+/// Masks the tokens, see `redact::Reveal` to print them.
+impl Debug for TokenPair {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenPair")
+            .field("access_token", &Redacted)
+            .field("refresh_token", &Redacted)
+            .finish()
+    }
+}
+
+/// Issue and rotate the tokens of sessions, keeping the state of the refresh tokens in memory
+/// until they expire.
+pub struct SessionManager<'a, A: Algorithm> {
+    access: Issuer<'a, A>,
+    refresh: Issuer<'a, A>,
+    verify_key: &'a A::VerifyKey,
+    refresh_tokens: Mutex<HashMap<String, RefreshToken>>,
+}
+
+/// State of a refresh token by `jti`.
+struct RefreshToken {
+    /// `jti` of the first refresh token of the session
+    session: String,
+    sub: Option<String>,
+    exp: u64,
+    state: RefreshState,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum RefreshState {
+    Active,
+    Rotated,
+    Revoked,
+}
+
+impl<'a, A: Algorithm> SessionManager<'a, A> {
+    /// Create a `SessionManager` signing and verifying with the keys, the access and refresh
+    /// tokens expire after their lifetimes.
+    pub fn new(sign_key: &'a A::SignKey, verify_key: &'a A::VerifyKey, access_lifetime: Duration, refresh_lifetime: Duration) -> Self {
+        SessionManager {
+            access: Issuer::new(sign_key, access_lifetime).with_header(Header::default().with_typ(ACCESS_TYP)),
+            refresh: Issuer::new(sign_key, refresh_lifetime).with_header(Header::default().with_typ(REFRESH_TYP)),
+            verify_key,
+            refresh_tokens: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The `iss` claim of the tokens.
+    #[inline]
+    pub fn with_iss(self, iss: impl Into<String>) -> Self {
+        let iss = iss.into();
+        SessionManager {
+            access: self.access.with_iss(iss.clone()),
+            refresh: self.refresh.with_iss(iss),
+            ..self
+        }
+    }
+
+    /// The `aud` claim of the tokens.
+    #[inline]
+    pub fn with_aud(self, aud: impl Into<String>) -> Self {
+        let aud = aud.into();
+        SessionManager {
+            access: self.access.with_aud(aud.clone()),
+            refresh: self.refresh.with_aud(aud),
+            ..self
+        }
+    }
+
+    /// Start a session of the subject.
+    pub fn start(&self, sub: impl Into<String>) -> Result<TokenPair, VerifyError> {
+        let jti = random_jti()?;
+        self.issue(jti.clone(), jti, Some(sub.into()))
+    }
+
+    /// Verify the access token, its signature, `typ` and `exp`; a refresh token is rejected.
+    pub fn verify_access(&self, access_token: &str) -> Result<Token<Claims>, VerifyError> {
+        let token = jws::decode::<Claims>(access_token, VerifyWith::<A>(self.verify_key))?;
+        if token.header.typ.as_deref() != Some(ACCESS_TYP) {
+            return Err(Error::Rejected("not an access token").into());
+        }
+        token.payload.validate(ExpiredTime)?;
+        Ok(token)
+    }
+
+    /// Verify the refresh token and rotate it, issuing a new pair of the same session.
+    ///
+    /// A refresh token that was already rotated is rejected and revokes its session.
+    pub fn refresh(&self, refresh_token: &str) -> Result<TokenPair, VerifyError> {
+        let jti = self.verify_refresh(refresh_token)?;
+        let (session, sub) = {
+            let mut refresh_tokens = self.lock();
+            let token = refresh_tokens.get_mut(&jti).ok_or(Error::Rejected("unknown refresh token"))?;
+            match token.state {
+                RefreshState::Active => token.state = RefreshState::Rotated,
+                RefreshState::Rotated => {
+                    let session = token.session.clone();
+                    revoke(&mut refresh_tokens, &session);
+                    return Err(Error::Rejected("refresh token reused").into());
+                }
+                RefreshState::Revoked => return Err(Error::Rejected("session revoked").into()),
+            }
+            (token.session.clone(), token.sub.clone())
+        };
+        self.issue(random_jti()?, session, sub)
+    }
+
+    /// Verify the refresh token and revoke its session.
+    pub fn end(&self, refresh_token: &str) -> Result<(), VerifyError> {
+        let jti = self.verify_refresh(refresh_token)?;
+        let mut refresh_tokens = self.lock();
+        let session = refresh_tokens.get(&jti).ok_or(Error::Rejected("unknown refresh token"))?.session.clone();
+        revoke(&mut refresh_tokens, &session);
+        Ok(())
+    }
+
+    fn issue(&self, jti: String, session: String, sub: Option<String>) -> Result<TokenPair, VerifyError> {
+        let access_token = self.access.issue(Claims { sub: sub.clone(), jti: Some(random_jti()?), ..Default::default() })?;
+        let refresh_token = self.refresh.issue(Claims { sub: sub.clone(), jti: Some(jti.clone()), ..Default::default() })?;
+        let exp = time::now_secs() + self.refresh.lifetime().as_secs();
+        let mut refresh_tokens = self.lock();
+        let now = time::now_secs();
+        refresh_tokens.retain(|_, x| x.exp > now);
+        refresh_tokens.insert(jti, RefreshToken { session, sub, exp, state: RefreshState::Active });
+        Ok(TokenPair { access_token, refresh_token })
+    }
+
+    /// The `jti` of the refresh token with a valid signature, `typ` and `exp`.
+    fn verify_refresh(&self, refresh_token: &str) -> Result<String, VerifyError> {
+        let Token { header, payload, .. } = jws::decode::<Claims>(refresh_token, VerifyWith::<A>(self.verify_key))?;
+        if header.typ.as_deref() != Some(REFRESH_TYP) {
+            return Err(Error::Rejected("not a refresh token").into());
+        }
+        payload.validate(ExpiredTime)?;
+        Ok(payload.jti.ok_or(Error::Rejected("unknown refresh token"))?)
+    }
+
+    #[inline]
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, RefreshToken>> {
+        self.refresh_tokens.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Revoke every refresh token of the session.
+fn revoke(refresh_tokens: &mut HashMap<String, RefreshToken>, session: &str) {
+    refresh_tokens.values_mut()
+        .filter(|x| x.session == session)
+        .for_each(|x| x.state = RefreshState::Revoked);
+}
+
+/// A random `jti` of 128 bits.
+fn random_jti() -> Result<String, Error> {
+    let mut buf = [0u8; 16];
+    crypto::random(&mut buf)?;
+    Ok(bs64::from_bytes(buf))
+}
@@ -0,0 +1,169 @@
+//! Deterministic fixtures for tests, requires the feature `test-utils`
+//!
+//! Fixed keys of every builtin algorithm, a `FixedClock` validating the time claims at a fixed
+//! time, and helpers to mint tokens with `exp` and `nbf` offsets from it. The keys are public,
+//! never use them outside of tests.
+//!
+//! ```rust
+//! use jwts::{jws, Verifier};
+//! use jwts::jws::VerifyWith;
+//! use jwts::jws::alg::HS256;
+//! use jwts::testing::{self, FixedClock, HS_SECRET};
+//!
+//! let clock = FixedClock(1_700_000_000);
+//! let verifier = Verifier::new(VerifyWith::<HS256>(HS_SECRET)).with_validation(clock.expired_time());
+//!
+//! let token = testing::mint::<HS256>(HS_SECRET, &clock.claims(60));
+//! assert!(verifier.verify::<jwts::Claims>(&token).is_ok());
+//! let token = testing::mint::<HS256>(HS_SECRET, &clock.claims(-60));
+//! assert!(verifier.verify::<jwts::Claims>(&token).is_err());
+//! ```
+
+use std::time::{Duration, SystemTime};
+
+use serde::Serialize;
+
+use crate::claims::Claims;
+#[cfg(feature = "alg-eddsa")]
+use crate::crypto::Ed25519KeyPair;
+#[cfg(feature = "alg-rs")]
+use crate::crypto::RsaKeyPair;
+#[cfg(feature = "alg-es")]
+use crate::der;
+use crate::jws::{self, Algorithm, Header};
+use crate::time;
+use crate::validate::{validate_time, TimeClaim, ValidateError, Validation};
+
+/// Secret of the HS algorithms.
+pub const HS_SECRET: &[u8] = b"jwts-test-secret-of-at-least-32-bytes";
+
+/// Public key of `rsa_key_pair`, DER-encoded RSAPublicKey (PKCS#1).
+#[cfg(feature = "alg-rs")]
+pub const RSA_PUBLIC_KEY: &[u8] = include_bytes!("../tests/rsa-pub.der");
+
+/// Private key of ES256, PKCS#8 DER.
+#[cfg(feature = "alg-es")]
+pub const EC_P256_PRIVATE_KEY: &[u8] = include_bytes!("../tests/ecdsa-pri.pk8");
+
+/// Private key of ES384, PKCS#8 DER.
+#[cfg(feature = "alg-es")]
+pub const EC_P384_PRIVATE_KEY: &[u8] = include_bytes!("../tests/ecdsa-pri384.pk8");
+
+/// Public key of `ed25519_key_pair`.
+#[cfg(feature = "alg-eddsa")]
+pub const ED25519_PUBLIC_KEY: &[u8] = include_bytes!("../tests/eddsa-pub.der");
+
+/// 2048-bit key pair of the RS and PS algorithms.
+#[cfg(feature = "alg-rs")]
+pub fn rsa_key_pair() -> RsaKeyPair {
+    RsaKeyPair::from_der(include_bytes!("../tests/rsa-pri.der")).expect("valid test key")
+}
+
+/// Public point of `EC_P256_PRIVATE_KEY`.
+#[cfg(feature = "alg-es")]
+pub fn ec_p256_public_key() -> &'static [u8] {
+    der::spki_public_key(include_bytes!("../tests/ecdsa-pub.der")).expect("valid test key")
+}
+
+/// Public point of `EC_P384_PRIVATE_KEY`.
+#[cfg(feature = "alg-es")]
+pub fn ec_p384_public_key() -> &'static [u8] {
+    der::spki_public_key(include_bytes!("../tests/ecdsa-pub384.der")).expect("valid test key")
+}
+
+/// Key pair of the EdDSA algorithm.
+#[cfg(feature = "alg-eddsa")]
+pub fn ed25519_key_pair() -> Ed25519KeyPair {
+    Ed25519KeyPair::from_pkcs8(include_bytes!("../tests/eddsa-pri-rfc5958.pk8")).expect("valid test key")
+}
+
+/// Encode and sign a token of the claims with the default header, panicking on failure.
+pub fn mint<A: Algorithm>(key: &A::SignKey, claims: &impl Serialize) -> String {
+    jws::encode::<A>(Header::default(), claims, key).expect("token signed")
+}
+
+/// A clock stopped at a time, in seconds since UNIX_EPOCH.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct FixedClock(pub u64);
+
+impl FixedClock {
+    /// Stop the clock at the current time.
+    #[inline]
+    pub fn now() -> Self {
+        FixedClock(time::now_secs())
+    }
+
+    /// Stop the clock at the time.
+    #[inline]
+    pub fn at(time: SystemTime) -> Self {
+        FixedClock(time::since_unix_epoch_secs(time))
+    }
+
+    /// The clock moved forward by the duration.
+    #[inline]
+    pub fn advance(self, duration: Duration) -> Self {
+        FixedClock(self.0 + duration.as_secs())
+    }
+
+    /// The time offset by seconds, negative in the past.
+    #[inline]
+    pub fn offset(&self, secs: i64) -> u64 {
+        self.0.saturating_add_signed(secs)
+    }
+
+    /// Claims issued at the time of the clock, expiring `exp` seconds after it, negative if expired.
+    pub fn claims(&self, exp: i64) -> Claims {
+        Claims {
+            iat: Some(self.0),
+            exp: Some(self.offset(exp)),
+            ..Claims::default()
+        }
+    }
+
+    /// Claims like `claims`, not valid before `nbf` seconds after the time of the clock.
+    pub fn claims_with_nbf(&self, exp: i64, nbf: i64) -> Claims {
+        Claims {
+            nbf: Some(self.offset(nbf)),
+            ..self.claims(exp)
+        }
+    }
+
+    /// `IssuedAtTime` at the time of the clock.
+    #[inline]
+    pub fn issued_at_time(&self) -> ClockValidation {
+        ClockValidation { claim: TimeClaim::Iat, now: self.0 }
+    }
+
+    /// `NotBeforeTime` at the time of the clock.
+    #[inline]
+    pub fn not_before_time(&self) -> ClockValidation {
+        ClockValidation { claim: TimeClaim::Nbf, now: self.0 }
+    }
+
+    /// `ExpiredTime` at the time of the clock.
+    #[inline]
+    pub fn expired_time(&self) -> ClockValidation {
+        ClockValidation { claim: TimeClaim::Exp, now: self.0 }
+    }
+}
+
+/// Validation of a time claim at the time of a `FixedClock`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ClockValidation {
+    claim: TimeClaim,
+    now: u64,
+}
+
+impl<T: Serialize> Validation<T> for ClockValidation {
+    type Error = ValidateError;
+
+    #[inline]
+    fn validate(&self, claims: &T) -> Result<(), Self::Error> {
+        validate_time(claims, self.claim, self.now)
+    }
+
+    #[inline]
+    fn validates_claim(&self, name: &str) -> bool {
+        name == self.claim.name()
+    }
+}
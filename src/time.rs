@@ -1,8 +1,146 @@
 //! Timestamp functions
 
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, AddAssign, Sub, SubAssign};
 use std::time;
 use std::time::{Duration, SystemTime};
 
+use serde_derive::{Deserialize, Serialize};
+
+/// Seconds since UNIX_EPOCH, the NumericDate of `exp`, `nbf` and `iat`, for the time claims of
+/// custom claims structs, see https://tools.ietf.org/html/rfc7519#section-2
+///
+/// ```rust
+/// use std::time::Duration;
+/// use jwts::NumericDate;
+/// use jwts::validate::{ExpiredTime, Validate};
+/// use serde_derive::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Session {
+///     user: String,
+///     exp: NumericDate,
+/// }
+///
+/// let session = Session { user: "sea".to_owned(), exp: NumericDate::now() + Duration::from_secs(60) };
+/// assert_eq!(serde_json::to_value(&session).unwrap()["exp"], session.exp.as_secs());
+/// session.validate(ExpiredTime).unwrap();
+/// ```
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct NumericDate(pub u64);
+
+impl NumericDate {
+    /// UNIX_EPOCH
+    pub const UNIX_EPOCH: NumericDate = NumericDate(0);
+
+    /// The current system time, truncated to seconds.
+    #[inline]
+    pub fn now() -> Self {
+        NumericDate(now_secs())
+    }
+
+    /// Create a `NumericDate` of seconds since UNIX_EPOCH.
+    #[inline]
+    pub const fn from_secs(secs: u64) -> Self {
+        NumericDate(secs)
+    }
+
+    /// Seconds since UNIX_EPOCH.
+    #[inline]
+    pub const fn as_secs(self) -> u64 {
+        self.0
+    }
+
+    /// The duration since an earlier date, zero if it is later.
+    #[inline]
+    pub fn saturating_duration_since(self, earlier: NumericDate) -> Duration {
+        Duration::from_secs(self.0.saturating_sub(earlier.0))
+    }
+
+    /// Add the duration truncated to seconds, `None` on overflow.
+    #[inline]
+    pub fn checked_add(self, duration: Duration) -> Option<Self> {
+        self.0.checked_add(duration.as_secs()).map(NumericDate)
+    }
+
+    /// Subtract the duration truncated to seconds, `None` before UNIX_EPOCH.
+    #[inline]
+    pub fn checked_sub(self, duration: Duration) -> Option<Self> {
+        self.0.checked_sub(duration.as_secs()).map(NumericDate)
+    }
+}
+
+/// Times before UNIX_EPOCH are UNIX_EPOCH.
+impl From<SystemTime> for NumericDate {
+    #[inline]
+    fn from(time: SystemTime) -> Self {
+        NumericDate(since_unix_epoch_secs(time))
+    }
+}
+
+impl From<NumericDate> for SystemTime {
+    #[inline]
+    fn from(date: NumericDate) -> Self {
+        time::UNIX_EPOCH + Duration::from_secs(date.0)
+    }
+}
+
+impl From<u64> for NumericDate {
+    #[inline]
+    fn from(secs: u64) -> Self {
+        NumericDate(secs)
+    }
+}
+
+impl From<NumericDate> for u64 {
+    #[inline]
+    fn from(date: NumericDate) -> Self {
+        date.0
+    }
+}
+
+/// Saturates at the maximum.
+impl Add<Duration> for NumericDate {
+    type Output = NumericDate;
+
+    #[inline]
+    fn add(self, duration: Duration) -> Self::Output {
+        NumericDate(self.0.saturating_add(duration.as_secs()))
+    }
+}
+
+impl AddAssign<Duration> for NumericDate {
+    #[inline]
+    fn add_assign(&mut self, duration: Duration) {
+        *self = *self + duration;
+    }
+}
+
+/// Saturates at UNIX_EPOCH.
+impl Sub<Duration> for NumericDate {
+    type Output = NumericDate;
+
+    #[inline]
+    fn sub(self, duration: Duration) -> Self::Output {
+        NumericDate(self.0.saturating_sub(duration.as_secs()))
+    }
+}
+
+impl SubAssign<Duration> for NumericDate {
+    #[inline]
+    fn sub_assign(&mut self, duration: Duration) {
+        *self = *self - duration;
+    }
+}
+
+impl Display for NumericDate {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 #[inline]
 pub fn since_unix_epoch_secs(time: SystemTime) -> u64 {
     time.duration_since(time::UNIX_EPOCH)
@@ -11,7 +149,113 @@ pub fn since_unix_epoch_secs(time: SystemTime) -> u64 {
 }
 
 /// System time since UNIX_EPOCH as seconds.
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
 #[inline]
 pub fn now_secs() -> u64 {
     since_unix_epoch_secs(SystemTime::now())
 }
+
+/// JavaScript `Date.now()` as seconds, `SystemTime::now` panics on wasm32-unknown-unknown.
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+#[inline]
+pub fn now_secs() -> u64 {
+    (js_sys::Date::now() / 1000.0) as u64
+}
+
+/// Measures the elapsed time from its start.
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub struct Stopwatch(time::Instant);
+
+/// Measures the elapsed time from its start with JavaScript `Date.now()`, `Instant::now` panics
+/// on wasm32-unknown-unknown.
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub struct Stopwatch(f64);
+
+impl Stopwatch {
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    #[inline]
+    pub fn start() -> Self {
+        Stopwatch(time::Instant::now())
+    }
+
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    #[inline]
+    pub fn start() -> Self {
+        Stopwatch(js_sys::Date::now())
+    }
+
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    #[inline]
+    pub fn elapsed(&self) -> Duration {
+        self.0.elapsed()
+    }
+
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    #[inline]
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_secs_f64((js_sys::Date::now() - self.0).max(0.0) / 1000.0)
+    }
+}
+
+/// Format seconds since UNIX_EPOCH as an RFC 3339 date-time in UTC, `1970-01-01T00:00:00Z`.
+#[cfg(feature = "paseto")]
+pub fn to_rfc3339(secs: u64) -> String {
+    // civil from days, see http://howardhinnant.github.io/date_algorithms.html
+    let (days, rem) = ((secs / 86400) as i64, secs % 86400);
+    let z = days + 719468;
+    let (era, doe) = (z.div_euclid(146097), z.rem_euclid(146097));
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, rem / 3600, rem / 60 % 60, rem % 60)
+}
+
+/// Parse an RFC 3339 date-time as seconds since UNIX_EPOCH, fractional seconds truncated,
+/// `None` if malformed or before UNIX_EPOCH.
+#[cfg(feature = "paseto")]
+pub fn from_rfc3339(s: &str) -> Option<u64> {
+    let b = s.as_bytes();
+    let num = |from: usize, len: usize| -> Option<i64> {
+        let digits = b.get(from..from + len)?;
+        digits.iter().all(u8::is_ascii_digit).then(|| digits.iter().fold(0, |n, d| n * 10 + (d - b'0') as i64))
+    };
+    let sep = |at: usize, c: &[u8]| b.get(at).is_some_and(|x| c.contains(&x.to_ascii_uppercase()));
+    if !(sep(4, b"-") && sep(7, b"-") && sep(10, b"T ") && sep(13, b":") && sep(16, b":")) {
+        return None;
+    }
+    let (year, month, day) = (num(0, 4)?, num(5, 2)?, num(8, 2)?);
+    let (hour, minute, second) = (num(11, 2)?, num(14, 2)?, num(17, 2)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+    let mut rest = &s[19..];
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 {
+            return None;
+        }
+        rest = &fraction[digits..];
+    }
+    let offset = match rest.as_bytes() {
+        [b'Z' | b'z'] => 0,
+        [sign @ (b'+' | b'-'), h1, h2, b':', m1, m2] if [h1, h2, m1, m2].iter().all(|x| x.is_ascii_digit()) => {
+            let (h, m) = (((h1 - b'0') * 10 + (h2 - b'0')) as i64, ((m1 - b'0') * 10 + (m2 - b'0')) as i64);
+            if h > 23 || m > 59 {
+                return None;
+            }
+            if *sign == b'+' { h * 3600 + m * 60 } else { -(h * 3600 + m * 60) }
+        }
+        _ => return None,
+    };
+    // days from civil
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+    u64::try_from(days * 86400 + hour * 3600 + minute * 60 + second - offset).ok()
+}
@@ -0,0 +1,157 @@
+//! Layer of tower, requires the feature `tower`
+//!
+//! `JwtLayer` verifies the bearer token of every `http::Request` with a `Verifier` and inserts the
+//! claims into the request extensions, so that hyper, tonic and axum services share one
+//! implementation. Requests without a valid token are answered with 401, or 400, 403, 500 and
+//! 503 according to `ErrorKind`, and an empty body.
+//!
+//! ```rust,no_run
+//! use jwts::{Claims, Verifier};
+//! use jwts::jws::VerifyWith;
+//! use jwts::jws::alg::HS256;
+//! use jwts::tower::JwtLayer;
+//! use jwts::validate::ExpiredTime;
+//!
+//! let verifier = Verifier::new(VerifyWith::<HS256>(b"secret")).with_validation(ExpiredTime);
+//! let layer = JwtLayer::<_, Claims>::new(verifier).skip("/health");
+//! // axum: Router::new().route(..).layer(layer)
+//! // handlers take the claims with `Extension<Claims>`
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use http::{header, HeaderValue, Request, Response, StatusCode};
+use pin_project_lite::pin_project;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::auth::Authenticator;
+use crate::extract::TokenExtractor;
+use crate::jws::Verify;
+use crate::verifier::Verifier;
+
+/// Layer verifying the bearer token and inserting the claims `C` into the request extensions.
+pub struct JwtLayer<V, C = Value> {
+    auth: Authenticator<V, C>,
+}
+
+impl<V: Verify<Value>, C: DeserializeOwned> JwtLayer<V, C> {
+    /// Create a `JwtLayer` verifying with the `Verifier`, no path skipped.
+    #[inline]
+    pub fn new(verifier: Verifier<V>) -> Self {
+        JwtLayer { auth: Authenticator::new(verifier) }
+    }
+
+    /// Let requests to the path, or below it, pass without a token; `/` lets the root alone pass.
+    pub fn skip(mut self, path: impl Into<String>) -> Self {
+        self.auth.skip(path.into());
+        self
+    }
+
+    /// Look for the token with the `TokenExtractor`, the bearer `Authorization` header by default.
+    pub fn with_extractor(mut self, extractor: TokenExtractor) -> Self {
+        self.auth.extractor(extractor);
+        self
+    }
+}
+
+impl<V, C> Clone for JwtLayer<V, C> {
+    #[inline]
+    fn clone(&self) -> Self {
+        JwtLayer { auth: self.auth.clone() }
+    }
+}
+
+impl<S, V, C> Layer<S> for JwtLayer<V, C> {
+    type Service = JwtService<S, V, C>;
+
+    #[inline]
+    fn layer(&self, service: S) -> Self::Service {
+        JwtService {
+            service,
+            auth: self.auth.clone(),
+        }
+    }
+}
+
+/// Service created by `JwtLayer`.
+pub struct JwtService<S, V, C> {
+    service: S,
+    auth: Authenticator<V, C>,
+}
+
+impl<S: Clone, V, C> Clone for JwtService<S, V, C> {
+    #[inline]
+    fn clone(&self) -> Self {
+        JwtService {
+            service: self.service.clone(),
+            auth: self.auth.clone(),
+        }
+    }
+}
+
+impl<S, ReqBody, ResBody, V, C> Service<Request<ReqBody>> for JwtService<S, V, C>
+where
+    S: Service<Request<ReqBody>, Response=Response<ResBody>>,
+    ResBody: Default,
+    V: Verify<Value>,
+    C: DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    type Response = Response<ResBody>;
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future, ResBody>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<ReqBody>) -> Self::Future {
+        if !self.auth.skipped(req.uri().path()) {
+            let headers = req.headers();
+            let authorization = headers.get(header::AUTHORIZATION).map(HeaderValue::as_bytes);
+            let cookies = headers.get_all(header::COOKIE).iter().map(HeaderValue::as_bytes);
+            match self.auth.authenticate(authorization, cookies, req.uri().query()) {
+                Ok(claims) => {
+                    req.extensions_mut().insert(claims);
+                }
+                Err(status) => {
+                    let mut res = Response::new(ResBody::default());
+                    *res.status_mut() = StatusCode::from_u16(status).unwrap_or(StatusCode::UNAUTHORIZED);
+                    if res.status() == StatusCode::UNAUTHORIZED {
+                        res.headers_mut().insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+                    }
+                    return ResponseFuture::Rejected { response: Some(res) };
+                }
+            }
+        }
+        ResponseFuture::Inner { future: self.service.call(req) }
+    }
+}
+
+pin_project! {
+    /// Response future of `JwtService`.
+    #[project = ResponseFutureProj]
+    pub enum ResponseFuture<F, B> {
+        Inner { #[pin] future: F },
+        Rejected { response: Option<Response<B>> },
+    }
+}
+
+impl<F, B, E> Future for ResponseFuture<F, B>
+where
+    F: Future<Output=Result<Response<B>, E>>,
+{
+    type Output = Result<Response<B>, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project() {
+            ResponseFutureProj::Inner { future } => future.poll(cx),
+            ResponseFutureProj::Rejected { response } => Poll::Ready(Ok(response.take().expect("polled after completion"))),
+        }
+    }
+}
@@ -1,147 +1,666 @@
 //! Claims Validate
 
+use std::any::{Any, TypeId};
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::error::Error;
-use std::fmt::{Display, Formatter};
+use std::fmt::{Debug, Display, Formatter};
+use std::time::Duration;
 
 use serde::Serialize;
 use serde_json as json;
 
+use crate::error::ErrorKind;
 use crate::time;
 
 pub struct IssuedAtTime;
 
+/// `IssuedAtTime` accepting an `iat` up to the skew in the future, as the clocks of issuers
+/// often run slightly ahead, see `IssuedAtTime::with_future_skew`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct IssuedAtTimeWithSkew(pub Duration);
+
 pub struct NotBeforeTime;
 
 pub struct ExpiredTime;
 
 pub struct ExpectIss<'a>(pub &'a str);
 
+/// Expect the `iss` claim as an issuer URL compared by the mode, e.g. accepting
+/// `https://idp.example.com/` for `https://idp.example.com`.
+///
+/// ```rust
+/// use jwts::Claims;
+/// use jwts::validate::{ExpectIssUrl, IssComparison, Validate};
+///
+/// let claims = Claims { iss: Some("https://IdP.example.com/".to_owned()), ..Default::default() };
+/// claims.validate(ExpectIssUrl("https://idp.example.com", IssComparison::Normalized)).unwrap();
+/// assert!(claims.validate(ExpectIssUrl("https://idp.example.com", IssComparison::IgnoreTrailingSlash)).is_err());
+/// ```
+pub struct ExpectIssUrl<'a>(pub &'a str, pub IssComparison);
+
+/// How issuer URLs are compared, see `ExpectIssUrl`.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum IssComparison {
+    /// Byte for byte, as `ExpectIss`
+    #[default]
+    Exact,
+    /// Ignoring a trailing slash
+    IgnoreTrailingSlash,
+    /// Ignoring a trailing slash and the case of the scheme and the host
+    Normalized,
+}
+
 pub struct ExpectSub<'a>(pub &'a str);
 
+/// How the string claims of `Compared` validations are compared.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum StrComparison {
+    /// Byte for byte
+    #[default]
+    Exact,
+    /// Ignoring the case
+    IgnoreCase,
+    /// Ignoring leading and trailing whitespace
+    Trimmed,
+    /// Ignoring leading and trailing whitespace and the case
+    TrimmedIgnoreCase,
+}
+
+/// An `ExpectIss`, `ExpectSub`, `ExpectAud`, `ExpectJti` or `ExpectClaimContains` comparing the
+/// claim by the mode, for issuers inconsistent about casing.
+///
+/// ```rust
+/// use jwts::Claims;
+/// use jwts::validate::{Compared, ExpectAud, StrComparison, Validate};
+///
+/// let claims = Claims { aud: Some(" API://Orders".to_owned()), ..Default::default() };
+/// assert!(claims.validate(ExpectAud("api://orders")).is_err());
+/// claims.validate(Compared(ExpectAud("api://orders"), StrComparison::TrimmedIgnoreCase)).unwrap();
+/// ```
+pub struct Compared<V>(pub V, pub StrComparison);
+
 pub struct ExpectAud<'a>(pub &'a str);
 
 pub struct ExpectJti<'a>(pub &'a str);
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+/// Expect the audience set of the `aud` claim, a string or an array, to intersect the accepted
+/// resources, as of RFC 8707 resource indicators. `matched` returns the audience matched.
+///
+/// ```rust
+/// use jwts::validate::{ExpectAudAny, Validate};
+///
+/// let claims = serde_json::json!({ "aud": ["https://users.example.com", "https://orders.example.com"] });
+/// let resources = ["https://orders.example.com", "https://billing.example.com"];
+/// claims.validate(ExpectAudAny(&resources)).unwrap();
+/// assert_eq!(ExpectAudAny(&resources).matched(&claims).unwrap(), "https://orders.example.com");
+/// ```
+pub struct ExpectAudAny<'a>(pub &'a [&'a str]);
+
+/// Expect the claim of the name, an array of strings or a single string, to contain the value,
+/// e.g. `ExpectClaimContains("amr", "mfa")`.
+///
+/// ```rust
+/// use jwts::validate::{ExpectClaimContains, Validate};
+///
+/// let claims = serde_json::json!({ "aud": ["api://orders", "api://users"], "amr": ["pwd"] });
+/// claims.validate(ExpectClaimContains("aud", "api://orders")).unwrap();
+/// assert!(claims.validate(ExpectClaimContains("amr", "mfa")).is_err());
+/// ```
+pub struct ExpectClaimContains<'a>(pub &'a str, pub &'a str);
+
+/// The owned `iss`, `aud`, time and required claims expectations of `VerifierConfig`,
+/// `Verifier::strict` and `Verifier::from_preset`. `exp` is always required, `nbf` and `iat` are validated if present.
+pub(crate) struct ClaimsPolicy {
+    pub(crate) issuer: Option<String>,
+    pub(crate) audience: Option<String>,
+    /// Clock skew tolerated in seconds
+    pub(crate) leeway: u64,
+    pub(crate) required_claims: Vec<String>,
+}
+
+/// A failed claim validation, with the expected value and the value found in the claims.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
 pub enum ValidateError {
     /// Claim "iss" does not match
-    InvalidIss,
+    InvalidIss { expected: String, actual: Option<String> },
     /// Claim "sub" does not match
-    InvalidSub,
+    InvalidSub { expected: String, actual: Option<String> },
     /// Claim "aud" does not match
-    InvalidAud,
+    InvalidAud { expected: String, actual: Option<String> },
     /// Claim "jti" does not match
-    InvalidJti,
+    InvalidJti { expected: String, actual: Option<String> },
     /// Now before the issued time
-    InvalidIat,
+    InvalidIat { now: u64, actual: Option<u64> },
     /// Token not active
-    NotBefore,
+    NotBefore { now: u64, actual: Option<u64> },
     /// Token expired
-    TokenExpiredAt(u64),
+    TokenExpiredAt { now: u64, actual: Option<u64> },
+    /// Claim "jti" already used
+    Replayed { jti: String },
+    /// Token revoked by its "jti" or "sub"
+    Revoked { jti: Option<String>, sub: Option<String> },
+    /// A required claim is absent
+    MissingClaim { name: String },
+    /// Claim "aud" contains none of the accepted audiences
+    AudNotAccepted { accepted: Vec<String>, actual: Option<String> },
+    /// Claim `name` does not contain the expected value
+    NotContained { name: String, expected: String, actual: Option<String> },
+}
+
+impl ValidateError {
+    /// The category of the error, `Expired` only if the token has an `exp` in the past.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            ValidateError::TokenExpiredAt { actual: Some(_), .. } => ErrorKind::Expired,
+            _ => ErrorKind::ClaimMismatch,
+        }
+    }
 }
 
 impl Display for ValidateError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            ValidateError::InvalidIss => f.write_str("Invalid iss"),
-            ValidateError::InvalidSub => f.write_str("Invalid sub"),
-            ValidateError::InvalidAud => f.write_str("Invalid aud"),
-            ValidateError::InvalidJti => f.write_str("Invalid jti"),
-            ValidateError::InvalidIat => f.write_str("Invalid iat"),
-            ValidateError::NotBefore => f.write_str("Used before nbf"),
-            ValidateError::TokenExpiredAt(time) => write!(f, "Token expired at {}", time),
+            ValidateError::InvalidIss { expected, actual } => write!(f, "Invalid iss: expected {}, found {}", expected, Found(actual)),
+            ValidateError::InvalidSub { expected, actual } => write!(f, "Invalid sub: expected {}, found {}", expected, Found(actual)),
+            ValidateError::InvalidAud { expected, actual } => write!(f, "Invalid aud: expected {}, found {}", expected, Found(actual)),
+            ValidateError::InvalidJti { expected, actual } => write!(f, "Invalid jti: expected {}, found {}", expected, Found(actual)),
+            ValidateError::InvalidIat { now, actual } => write!(f, "Invalid iat {} at {}", Found(actual), now),
+            ValidateError::NotBefore { now, actual } => write!(f, "Used before nbf {} at {}", Found(actual), now),
+            ValidateError::TokenExpiredAt { now, actual } => write!(f, "Token expired at {} (now {})", Found(actual), now),
+            ValidateError::Replayed { jti } => write!(f, "Replayed jti {}", jti),
+            ValidateError::Revoked { jti, sub } => write!(f, "Revoked token of jti {} and sub {}", Found(jti), Found(sub)),
+            ValidateError::MissingClaim { name } => write!(f, "Missing claim {}", name),
+            ValidateError::AudNotAccepted { accepted, actual } => {
+                write!(f, "Invalid aud: expected one of {}, found {}", accepted.join(", "), Found(actual))
+            }
+            ValidateError::NotContained { name, expected, actual } => {
+                write!(f, "Invalid {}: expected to contain {}, found {}", name, expected, Found(actual))
+            }
+        }
+    }
+}
+
+/// Display an optional claim value, or "none" if absent.
+struct Found<'a, T>(&'a Option<T>);
+
+impl<T: Display> Display for Found<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(x) => x.fmt(f),
+            None => f.write_str("none"),
         }
     }
 }
 
+/// The claim value as a string, non-string values in their JSON representation.
+pub(crate) fn claim_string(value: &json::Value) -> Option<String> {
+    match value {
+        json::Value::Null => None,
+        json::Value::String(x) => Some(x.clone()),
+        x => Some(x.to_string()),
+    }
+}
+
 impl Error for ValidateError {}
 
 pub trait Validation<C: ?Sized> {
     type Error;
 
     fn validate(&self, claims: &C) -> Result<(), Self::Error>;
+
+    /// Validate the claims with the request-scoped data of the context, such as the expected
+    /// nonce, see `Verifier::verify_with_context`. Ignores the context by default.
+    #[inline]
+    fn validate_with(&self, claims: &C, _context: &ValidationContext) -> Result<(), Self::Error> {
+        self.validate(claims)
+    }
+
+    /// Whether the validation checks the claim of the name, rejecting claims without it,
+    /// see `Verifier::audit`.
+    #[inline]
+    fn validates_claim(&self, _name: &str) -> bool {
+        false
+    }
 }
 
 impl<T: Serialize> Validation<T> for IssuedAtTime {
     type Error = ValidateError;
 
+    #[inline]
+    fn validate(&self, claims: &T) -> Result<(), Self::Error> {
+        validate_time(claims, TimeClaim::Iat, time::now_secs())
+    }
+
+    #[inline]
+    fn validates_claim(&self, name: &str) -> bool {
+        name == "iat"
+    }
+}
+
+impl IssuedAtTime {
+    /// Accept an `iat` up to the skew in the future.
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use jwts::Claims;
+    /// use jwts::validate::{IssuedAtTime, Validate};
+    ///
+    /// let claims = Claims::default().issued_now();
+    /// let ahead = Claims { iat: claims.iat.map(|x| x + 30), ..claims };
+    /// assert!(ahead.validate(IssuedAtTime).is_err());
+    /// ahead.validate(IssuedAtTime::with_future_skew(Duration::from_secs(60))).unwrap();
+    /// ```
+    #[inline]
+    pub fn with_future_skew(skew: Duration) -> IssuedAtTimeWithSkew {
+        IssuedAtTimeWithSkew(skew)
+    }
+}
+
+impl<T: Serialize> Validation<T> for IssuedAtTimeWithSkew {
+    type Error = ValidateError;
+
     fn validate(&self, claims: &T) -> Result<(), Self::Error> {
-        let claims = json::to_value(claims).ok();
-        claims.and_then(|x| x["iat"].as_u64())
-            .filter(|&x| x <= time::now_secs())
-            .ok_or(ValidateError::InvalidIat)
-            .map(|_| ())
+        let now = time::now_secs();
+        validate_time(claims, TimeClaim::Iat, now.saturating_add(self.0.as_secs())).map_err(|e| match e {
+            ValidateError::InvalidIat { actual, .. } => ValidateError::InvalidIat { now, actual },
+            e => e,
+        })
+    }
+
+    #[inline]
+    fn validates_claim(&self, name: &str) -> bool {
+        name == "iat"
     }
 }
 
 impl<T: Serialize> Validation<T> for NotBeforeTime {
     type Error = ValidateError;
 
+    #[inline]
     fn validate(&self, claims: &T) -> Result<(), Self::Error> {
-        let claims = json::to_value(claims).ok();
-        claims.and_then(|x| x["nbf"].as_u64())
-            .filter(|&x| x <= time::now_secs())
-            .ok_or(ValidateError::NotBefore)
-            .map(|_| ())
+        validate_time(claims, TimeClaim::Nbf, time::now_secs())
+    }
+
+    #[inline]
+    fn validates_claim(&self, name: &str) -> bool {
+        name == "nbf"
     }
 }
 
 impl<T: Serialize> Validation<T> for ExpiredTime {
     type Error = ValidateError;
 
+    #[inline]
     fn validate(&self, claims: &T) -> Result<(), Self::Error> {
-        let claims = json::to_value(claims).ok();
-        claims.and_then(|x| x["exp"].as_u64())
-            .ok_or(ValidateError::TokenExpiredAt(0))
-            .and_then(|x| if x <= time::now_secs() { Err(ValidateError::TokenExpiredAt(x)) } else { Ok(x) })
-            .map(|_| ())
+        validate_time(claims, TimeClaim::Exp, time::now_secs())
+    }
+
+    #[inline]
+    fn validates_claim(&self, name: &str) -> bool {
+        name == "exp"
+    }
+}
+
+/// A time claim of `IssuedAtTime`, `NotBeforeTime` and `ExpiredTime`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum TimeClaim {
+    Iat,
+    Nbf,
+    Exp,
+}
+
+impl TimeClaim {
+    /// Name of the claim.
+    #[inline]
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            TimeClaim::Iat => "iat",
+            TimeClaim::Nbf => "nbf",
+            TimeClaim::Exp => "exp",
+        }
+    }
+}
+
+/// Validate the time claim at `now`, in seconds since UNIX_EPOCH.
+pub(crate) fn validate_time(claims: &impl Serialize, claim: TimeClaim, now: u64) -> Result<(), ValidateError> {
+    let actual = json::to_value(claims).ok().and_then(|x| x[claim.name()].as_u64());
+    match claim {
+        TimeClaim::Iat => actual.filter(|&x| x <= now).ok_or(ValidateError::InvalidIat { now, actual }),
+        TimeClaim::Nbf => actual.filter(|&x| x <= now).ok_or(ValidateError::NotBefore { now, actual }),
+        TimeClaim::Exp => actual.filter(|&x| x > now).ok_or(ValidateError::TokenExpiredAt { now, actual }),
+    }.map(|_| ())
+}
+
+impl IssComparison {
+    /// Whether the issuers are equal in the mode.
+    #[inline]
+    pub fn matches(self, expected: &str, actual: &str) -> bool {
+        self.canonical(expected) == self.canonical(actual)
+    }
+
+    /// The form of the issuer compared in the mode.
+    fn canonical(self, iss: &str) -> Cow<'_, str> {
+        let iss = match self {
+            IssComparison::Exact => return Cow::Borrowed(iss),
+            _ => iss.strip_suffix('/').unwrap_or(iss),
+        };
+        if self != IssComparison::Normalized {
+            return Cow::Borrowed(iss);
+        }
+        let (authority, path) = match iss.find("://") {
+            Some(x) => iss.split_at(iss[x + 3..].find(['/', '?', '#']).map_or(iss.len(), |y| x + 3 + y)),
+            None => return Cow::Borrowed(iss),
+        };
+        match authority.bytes().any(|x| x.is_ascii_uppercase()) {
+            true => Cow::Owned(authority.to_ascii_lowercase() + path),
+            false => Cow::Borrowed(iss),
+        }
+    }
+}
+
+impl<C: Serialize> Validation<C> for ExpectIssUrl<'_> {
+    type Error = ValidateError;
+
+    fn validate(&self, claims: &C) -> Result<(), Self::Error> {
+        let ExpectIssUrl(expected, comparison) = *self;
+        let claims = json::to_value(claims).unwrap_or_default();
+        match claims["iss"].as_str() {
+            Some(x) if comparison.matches(expected, x) => Ok(()),
+            _ => Err(ValidateError::InvalidIss { expected: expected.to_owned(), actual: claim_string(&claims["iss"]) }),
+        }
+    }
+
+    #[inline]
+    fn validates_claim(&self, name: &str) -> bool {
+        name == "iss"
+    }
+}
+
+impl ExpectAudAny<'_> {
+    /// The first audience of the `aud` claim accepted.
+    pub fn matched(&self, claims: &impl Serialize) -> Result<String, ValidateError> {
+        let claims = json::to_value(claims).unwrap_or_default();
+        let aud = &claims["aud"];
+        let auds = match aud.as_array() {
+            Some(auds) => auds.as_slice(),
+            None => std::slice::from_ref(aud),
+        };
+        auds.iter()
+            .filter_map(json::Value::as_str)
+            .find(|x| self.0.contains(x))
+            .map(str::to_owned)
+            .ok_or_else(|| ValidateError::AudNotAccepted {
+                accepted: self.0.iter().map(|x| x.to_string()).collect(),
+                actual: claim_string(aud),
+            })
+    }
+}
+
+impl<C: Serialize> Validation<C> for ExpectAudAny<'_> {
+    type Error = ValidateError;
+
+    #[inline]
+    fn validate(&self, claims: &C) -> Result<(), Self::Error> {
+        self.matched(claims).map(|_| ())
+    }
+
+    #[inline]
+    fn validates_claim(&self, name: &str) -> bool {
+        name == "aud"
+    }
+}
+
+impl StrComparison {
+    /// Whether the strings are equal in the mode.
+    pub fn matches(self, expected: &str, actual: &str) -> bool {
+        let (expected, actual) = match self {
+            StrComparison::Exact | StrComparison::IgnoreCase => (expected, actual),
+            StrComparison::Trimmed | StrComparison::TrimmedIgnoreCase => (expected.trim(), actual.trim()),
+        };
+        match self {
+            StrComparison::Exact | StrComparison::Trimmed => expected == actual,
+            StrComparison::IgnoreCase | StrComparison::TrimmedIgnoreCase => {
+                expected.chars().flat_map(char::to_lowercase).eq(actual.chars().flat_map(char::to_lowercase))
+            }
+        }
+    }
+}
+
+impl<C: Serialize> Validation<C> for ExpectClaimContains<'_> {
+    type Error = ValidateError;
+
+    #[inline]
+    fn validate(&self, claims: &C) -> Result<(), Self::Error> {
+        validate_contains(self, claims, StrComparison::Exact)
+    }
+
+    #[inline]
+    fn validates_claim(&self, name: &str) -> bool {
+        name == self.0
+    }
+}
+
+impl<C: Serialize> Validation<C> for Compared<ExpectClaimContains<'_>> {
+    type Error = ValidateError;
+
+    #[inline]
+    fn validate(&self, claims: &C) -> Result<(), Self::Error> {
+        validate_contains(&self.0, claims, self.1)
+    }
+
+    #[inline]
+    fn validates_claim(&self, name: &str) -> bool {
+        name == self.0.0
+    }
+}
+
+/// Validate the array or string claim of the expectation in the mode.
+fn validate_contains(expect: &ExpectClaimContains<'_>, claims: &impl Serialize, comparison: StrComparison) -> Result<(), ValidateError> {
+    let ExpectClaimContains(name, expected) = *expect;
+    let claims = json::to_value(claims).unwrap_or_default();
+    let claim = &claims[name];
+    let matches = |x: &json::Value| x.as_str().is_some_and(|x| comparison.matches(expected, x));
+    let contains = match claim.as_array() {
+        Some(values) => values.iter().any(matches),
+        None => matches(claim),
+    };
+    match contains {
+        true => Ok(()),
+        false => Err(ValidateError::NotContained { name: name.to_owned(), expected: expected.to_owned(), actual: claim_string(claim) }),
+    }
+}
+
+impl Validation<json::Value> for ClaimsPolicy {
+    type Error = ValidateError;
+
+    fn validate(&self, claims: &json::Value) -> Result<(), Self::Error> {
+        if let Some(name) = self.required_claims.iter().find(|x| claims[x.as_str()].is_null()) {
+            return Err(ValidateError::MissingClaim { name: name.clone() });
+        }
+        if let Some(issuer) = &self.issuer {
+            if claims["iss"].as_str() != Some(issuer) {
+                return Err(ValidateError::InvalidIss { expected: issuer.clone(), actual: claim_string(&claims["iss"]) });
+            }
+        }
+        if let Some(audience) = &self.audience {
+            let aud = &claims["aud"];
+            let matches = match aud.as_array() {
+                Some(auds) => auds.iter().any(|x| x.as_str() == Some(audience)),
+                None => aud.as_str() == Some(audience),
+            };
+            if !matches {
+                return Err(ValidateError::InvalidAud { expected: audience.clone(), actual: claim_string(aud) });
+            }
+        }
+        let now = time::now_secs();
+        validate_time(claims, TimeClaim::Exp, now.saturating_sub(self.leeway))?;
+        for claim in [TimeClaim::Nbf, TimeClaim::Iat] {
+            if !claims[claim.name()].is_null() {
+                validate_time(claims, claim, now.saturating_add(self.leeway))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn validates_claim(&self, name: &str) -> bool {
+        match name {
+            "exp" => true,
+            "iss" => self.issuer.is_some(),
+            "aud" => self.audience.is_some(),
+            _ => self.required_claims.iter().any(|x| x == name),
+        }
     }
 }
 
+/// Build the error from the expected and the actual value.
+type Mismatch = fn(String, Option<String>) -> ValidateError;
+
 trait ExpectValidation<'a> {
     /// (claim_name, expected_value, error)
-    fn expect(&self) -> (&'static str, &'a str, ValidateError);
+    fn expect(&self) -> (&'static str, &'a str, Mismatch);
 }
 
 impl<'a, T: ExpectValidation<'a>, C: Serialize> Validation<C> for T {
     type Error = ValidateError;
 
+    #[inline]
+    fn validate(&self, claims: &C) -> Result<(), Self::Error> {
+        validate_expect(self, claims, StrComparison::Exact)
+    }
+
+    #[inline]
+    fn validates_claim(&self, name: &str) -> bool {
+        name == self.expect().0
+    }
+}
+
+impl<'a, T: ExpectValidation<'a>, C: Serialize> Validation<C> for Compared<T> {
+    type Error = ValidateError;
+
+    #[inline]
     fn validate(&self, claims: &C) -> Result<(), Self::Error> {
-        let (claim_name, expected_value, error) = self.expect();
-        let claims = json::to_value(claims).ok();
-        claims.as_ref()
-            .and_then(|x| x[claim_name].as_str())
-            .filter(|x| x == &expected_value)
-            .ok_or(error)
-            .map(|_| ())
+        validate_expect(&self.0, claims, self.1)
+    }
+
+    #[inline]
+    fn validates_claim(&self, name: &str) -> bool {
+        name == self.0.expect().0
+    }
+}
+
+/// Validate the string claim of the expectation in the mode.
+fn validate_expect<'a>(expect: &impl ExpectValidation<'a>, claims: &impl Serialize, comparison: StrComparison) -> Result<(), ValidateError> {
+    let (claim_name, expected_value, error) = expect.expect();
+    let claims = json::to_value(claims).unwrap_or_default();
+    let claim = &claims[claim_name];
+    match claim.as_str() {
+        Some(x) if comparison.matches(expected_value, x) => Ok(()),
+        _ => Err(error(expected_value.to_owned(), claim_string(claim))),
     }
 }
 
 impl<'a> ExpectValidation<'a> for ExpectIss<'a> {
     #[inline]
-    fn expect(&self) -> (&'static str, &'a str, ValidateError) {
-        ("iss", self.0, ValidateError::InvalidIss)
+    fn expect(&self) -> (&'static str, &'a str, Mismatch) {
+        ("iss", self.0, |expected, actual| ValidateError::InvalidIss { expected, actual })
     }
 }
 
 impl<'a> ExpectValidation<'a> for ExpectSub<'a> {
     #[inline]
-    fn expect(&self) -> (&'static str, &'a str, ValidateError) {
-        ("sub", self.0, ValidateError::InvalidSub)
+    fn expect(&self) -> (&'static str, &'a str, Mismatch) {
+        ("sub", self.0, |expected, actual| ValidateError::InvalidSub { expected, actual })
     }
 }
 
 impl<'a> ExpectValidation<'a> for ExpectAud<'a> {
     #[inline]
-    fn expect(&self) -> (&'static str, &'a str, ValidateError) {
-        ("aud", self.0, ValidateError::InvalidAud)
+    fn expect(&self) -> (&'static str, &'a str, Mismatch) {
+        ("aud", self.0, |expected, actual| ValidateError::InvalidAud { expected, actual })
     }
 }
 
 impl<'a> ExpectValidation<'a> for ExpectJti<'a> {
     #[inline]
-    fn expect(&self) -> (&'static str, &'a str, ValidateError) {
-        ("jti", self.0, ValidateError::InvalidJti)
+    fn expect(&self) -> (&'static str, &'a str, Mismatch) {
+        ("jti", self.0, |expected, actual| ValidateError::InvalidJti { expected, actual })
+    }
+}
+
+/// Request-scoped data of `Validation::validate_with`, one value per type.
+///
+/// ```rust
+/// use jwts::{Claims, jws, Verifier};
+/// use jwts::jws::{Header, VerifyWith};
+/// use jwts::jws::alg::HS256;
+/// use jwts::validate::{ValidateError, Validation, ValidationContext};
+///
+/// /// The `jti` of the request, e.g. a nonce issued to the client.
+/// struct Nonce(String);
+///
+/// struct ExpectNonce;
+///
+/// impl Validation<serde_json::Value> for ExpectNonce {
+///     type Error = ValidateError;
+///
+///     fn validate(&self, _claims: &serde_json::Value) -> Result<(), Self::Error> {
+///         Err(ValidateError::MissingClaim { name: "jti".to_owned() })
+///     }
+///
+///     fn validate_with(&self, claims: &serde_json::Value, context: &ValidationContext) -> Result<(), Self::Error> {
+///         let expected = context.get::<Nonce>().map(|x| x.0.as_str()).unwrap_or_default();
+///         match claims["jti"].as_str() {
+///             Some(x) if x == expected => Ok(()),
+///             x => Err(ValidateError::InvalidJti { expected: expected.to_owned(), actual: x.map(str::to_owned) }),
+///         }
+///     }
+/// }
+///
+/// let verifier = Verifier::new(VerifyWith::<HS256>(b"secret")).with_validation(ExpectNonce);
+/// let claims = Claims { jti: Some("n-0S6_WzA2Mj".to_owned()), ..Default::default() };
+/// let token = jws::encode::<HS256>(Header::default(), &claims, b"secret").unwrap();
+/// let context = ValidationContext::new().with(Nonce("n-0S6_WzA2Mj".to_owned()));
+/// verifier.verify_with_context::<Claims>(&token, &context).unwrap();
+/// assert!(verifier.verify::<Claims>(&token).is_err());
+/// ```
+#[derive(Default)]
+pub struct ValidationContext {
+    values: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl ValidationContext {
+    /// Create an empty `ValidationContext`.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the value, replacing the one of the same type.
+    #[inline]
+    pub fn with<T: Any + Send + Sync>(mut self, value: T) -> Self {
+        self.insert(value);
+        self
+    }
+
+    /// Insert the value, returning the one of the same type replaced.
+    pub fn insert<T: Any + Send + Sync>(&mut self, value: T) -> Option<T> {
+        self.values.insert(TypeId::of::<T>(), Box::new(value))
+            .and_then(|x| x.downcast().ok())
+            .map(|x| *x)
+    }
+
+    /// The value of the type.
+    #[inline]
+    pub fn get<T: Any + Send + Sync>(&self) -> Option<&T> {
+        self.values.get(&TypeId::of::<T>()).and_then(|x| x.downcast_ref())
+    }
+}
+
+impl Debug for ValidationContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ValidationContext").field("len", &self.values.len()).finish()
     }
 }
 
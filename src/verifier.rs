@@ -0,0 +1,533 @@
+//! Verifier
+
+use std::cell::Cell;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde_json as json;
+use serde_json::Value;
+
+use crate::audit::Finding;
+use crate::claims;
+use crate::error::{Error, ErrorKind, Segment, VerifyError};
+use crate::jws::{Alg, Algorithm, DecodeContext, Header, Token, Verify};
+use crate::jws::codec::{self, AnyCodec};
+use crate::time::{NumericDate, Stopwatch};
+use crate::validate::{ClaimsPolicy, ValidateError, Validation, ValidationContext};
+
+pub(crate) type SharedValidation = Arc<dyn Validation<Value, Error=ValidateError> + Send + Sync>;
+type SharedObserver = Arc<dyn VerifyObserver + Send + Sync>;
+
+thread_local! {
+    /// The decode buffers reused by the verifications on this thread.
+    static DECODE_CONTEXT: Cell<DecodeContext> = Cell::new(DecodeContext::new());
+}
+
+/// Observes the outcomes of `Verifier::verify`, e.g. to count the rejected tokens by cause.
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::time::Duration;
+/// use jwts::{ErrorKind, Verifier, VerifyObserver};
+/// use jwts::jws::{Header, VerifyWith};
+/// use jwts::jws::alg::HS256;
+///
+/// #[derive(Default)]
+/// struct Rejected(AtomicUsize);
+///
+/// impl VerifyObserver for Rejected {
+///     fn on_verify_failure(&self, _kind: ErrorKind, _elapsed: Duration) {
+///         self.0.fetch_add(1, Ordering::Relaxed);
+///     }
+/// }
+///
+/// let rejected = Arc::new(Rejected::default());
+/// let verifier = Verifier::new(VerifyWith::<HS256>(b"secret")).with_observer(rejected.clone());
+/// assert!(verifier.verify::<serde_json::Value>("malformed").is_err());
+/// assert_eq!(rejected.0.load(Ordering::Relaxed), 1);
+/// ```
+pub trait VerifyObserver {
+    /// A token was verified and its claims validated, taking the elapsed time.
+    #[inline]
+    fn on_verify_success(&self, _header: &Header, _elapsed: Duration) {}
+
+    /// A token was rejected for the kind of error, taking the elapsed time.
+    #[inline]
+    fn on_verify_failure(&self, _kind: ErrorKind, _elapsed: Duration) {}
+}
+
+impl<O: VerifyObserver + ?Sized> VerifyObserver for Arc<O> {
+    #[inline]
+    fn on_verify_success(&self, header: &Header, elapsed: Duration) {
+        (**self).on_verify_success(header, elapsed)
+    }
+
+    #[inline]
+    fn on_verify_failure(&self, kind: ErrorKind, elapsed: Duration) {
+        (**self).on_verify_failure(kind, elapsed)
+    }
+}
+
+/// Verify the signature of a token and validate its claims in one step.
+///
+/// A `Verifier` is `Send + Sync`, and `Clone` if the verification is, so it can be built once
+/// and stored in the state of a web framework. Clones share the validations and observers.
+#[derive(Clone)]
+pub struct Verifier<V> {
+    verify: V,
+    validations: Vec<SharedValidation>,
+    observers: Vec<SharedObserver>,
+    lenient_numeric_dates: bool,
+    lenient_string_claims: bool,
+    lenient_parsing: bool,
+    strict_header: Option<Arc<[String]>>,
+    crit_extensions: Option<Arc<[String]>>,
+    algorithms: Vec<Alg>,
+    alg_aliases: Vec<(Alg, Alg)>,
+    typ: Option<String>,
+    max_token_len: Option<usize>,
+    claim_mappings: Vec<(String, String)>,
+    leeway: Duration,
+    #[cfg(feature = "gzip")]
+    gzip_max_len: Option<usize>,
+}
+
+/// A token verified by `Verifier::verify_audited`, with what was checked, e.g. for the audit
+/// logs of regulated environments.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct VerifiedClaims<C> {
+    /// The validated claims
+    pub claims: C,
+    /// The verified header
+    pub header: Header,
+    /// The registered claims checked by the validations, of `iss`, `sub`, `aud`, `exp`, `nbf`,
+    /// `iat` and `jti`
+    pub validated: Vec<&'static str>,
+    /// The clock skew tolerated for the time claims
+    pub leeway: Duration,
+    /// The time the token was verified at
+    pub verified_at: NumericDate,
+}
+
+impl<C> VerifiedClaims<C> {
+    /// The algorithm of the signature.
+    #[inline]
+    pub fn alg(&self) -> Option<&Alg> {
+        self.header.alg.as_ref()
+    }
+
+    /// The ID of the key of the signature.
+    #[inline]
+    pub fn kid(&self) -> Option<&str> {
+        self.header.kid.as_deref()
+    }
+}
+
+/// The registered claims reported in `VerifiedClaims::validated`.
+const AUDITED_CLAIMS: [&str; 7] = ["iss", "sub", "aud", "exp", "nbf", "iat", "jti"];
+
+/// The longest token accepted by `Verifier::strict`, in bytes.
+pub const STRICT_MAX_TOKEN_LEN: usize = 8 * 1024;
+
+impl<V: Debug> Debug for Verifier<V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("Verifier");
+        f
+            .field("verify", &self.verify)
+            .field("validations", &self.validations.len())
+            .field("observers", &self.observers.len())
+            .field("lenient_numeric_dates", &self.lenient_numeric_dates)
+            .field("lenient_string_claims", &self.lenient_string_claims)
+            .field("lenient_parsing", &self.lenient_parsing)
+            .field("strict_header", &self.strict_header)
+            .field("crit_extensions", &self.crit_extensions)
+            .field("algorithms", &self.algorithms)
+            .field("alg_aliases", &self.alg_aliases)
+            .field("typ", &self.typ)
+            .field("max_token_len", &self.max_token_len)
+            .field("claim_mappings", &self.claim_mappings)
+            .field("leeway", &self.leeway);
+        #[cfg(feature = "gzip")]
+        f.field("gzip_max_len", &self.gzip_max_len);
+        f.finish()
+    }
+}
+
+impl<V: Verify<Value>> Verifier<V> {
+    /// Create a `Verifier` with the signature verification and no claims validation.
+    #[inline]
+    pub fn new(verify: V) -> Self {
+        Verifier {
+            verify,
+            validations: Vec::new(),
+            observers: Vec::new(),
+            lenient_numeric_dates: false,
+            lenient_string_claims: false,
+            lenient_parsing: false,
+            strict_header: None,
+            crit_extensions: None,
+            algorithms: Vec::new(),
+            alg_aliases: Vec::new(),
+            typ: None,
+            max_token_len: None,
+            claim_mappings: Vec::new(),
+            leeway: Duration::ZERO,
+            #[cfg(feature = "gzip")]
+            gzip_max_len: None,
+        }
+    }
+
+    /// Create a `Verifier` of the strict profile of the JWT best current practices, RFC 8725:
+    ///
+    /// - only the algorithms given are accepted in the `alg` header, never `none`
+    /// - `exp` and `iat` are required and validated, `nbf` is validated if present
+    /// - `iss` and `aud` must match the expected values
+    /// - the `typ` header must be `JWT`, change it with `with_typ`, e.g. to `at+jwt`
+    /// - tokens longer than `STRICT_MAX_TOKEN_LEN` are rejected before decoding
+    ///
+    /// ```rust
+    /// use jwts::{Claims, jws, Verifier};
+    /// use jwts::jws::{Alg, Header, VerifyWith};
+    /// use jwts::jws::alg::HS256;
+    ///
+    /// let key = [7; 32];
+    /// let verifier = Verifier::strict(VerifyWith::<HS256>(&key), [Alg::HS256], "https://idp.example.com", "api");
+    /// assert!(verifier.audit().is_empty());
+    ///
+    /// let claims = Claims {
+    ///     iss: Some("https://idp.example.com".to_owned()),
+    ///     aud: Some("api".to_owned()),
+    ///     ..Default::default()
+    /// }.issued_now().expired_in(std::time::Duration::from_secs(60));
+    /// let token = jws::encode::<HS256>(Header::default(), &claims, &key).unwrap();
+    /// verifier.verify::<Claims>(&token).unwrap();
+    /// ```
+    pub fn strict(verify: V, algorithms: impl IntoIterator<Item=Alg>, iss: impl Into<String>, aud: impl Into<String>) -> Self {
+        Verifier::new(verify)
+            .with_algorithms(algorithms)
+            .with_typ("JWT")
+            .with_max_token_len(STRICT_MAX_TOKEN_LEN)
+            .with_claims_policy(ClaimsPolicy {
+                issuer: Some(iss.into()),
+                audience: Some(aud.into()),
+                leeway: 0,
+                required_claims: vec!["iat".to_owned()],
+            })
+    }
+
+    /// Add the claims validation of the policy, recording its leeway for `VerifiedClaims`.
+    #[inline]
+    pub(crate) fn with_claims_policy(self, policy: ClaimsPolicy) -> Self {
+        let leeway = Duration::from_secs(policy.leeway);
+        Verifier { leeway, ..self }.with_validation(policy)
+    }
+
+    /// Add a claims validation, validations run in the order added.
+    #[inline]
+    pub fn with_validation(mut self, validation: impl Validation<Value, Error=ValidateError> + Send + Sync + 'static) -> Self {
+        self.validations.push(Arc::new(validation));
+        self
+    }
+
+    /// Add an observer of the outcomes and durations of `verify`.
+    #[inline]
+    pub fn with_observer(mut self, observer: impl VerifyObserver + Send + Sync + 'static) -> Self {
+        self.observers.push(Arc::new(observer));
+        self
+    }
+
+    /// Rename the claim `from` to `to` before the claims are normalized, validated and
+    /// deserialized, e.g. `https://example.com/roles` to `roles`, so that the tokens of several
+    /// issuers fit one claims type. Mappings apply in the order added; a claim `to` present in
+    /// the token is kept, and `from` is left as it is.
+    ///
+    /// ```rust
+    /// use jwts::{jws, Verifier};
+    /// use jwts::jws::{Header, VerifyWith};
+    /// use jwts::jws::alg::HS256;
+    /// use serde_derive::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct User {
+    ///     email: String,
+    ///     roles: Vec<String>,
+    /// }
+    ///
+    /// let verifier = Verifier::new(VerifyWith::<HS256>(b"secret"))
+    ///     .with_claim_mapping("upn", "email")
+    ///     .with_claim_mapping("https://example.com/roles", "roles");
+    /// let claims = serde_json::json!({ "upn": "sea@example.com", "https://example.com/roles": ["admin"] });
+    /// let token = jws::encode::<HS256>(Header::default(), &claims, b"secret").unwrap();
+    /// let user = verifier.verify::<User>(&token).unwrap().payload;
+    /// assert_eq!((user.email.as_str(), user.roles.as_slice()), ("sea@example.com", ["admin".to_owned()].as_slice()));
+    /// ```
+    #[inline]
+    pub fn with_claim_mapping(mut self, from: impl Into<String>, to: impl Into<String>) -> Self {
+        self.claim_mappings.push((from.into(), to.into()));
+        self
+    }
+
+    /// Accept `exp`, `nbf` and `iat` as floats or numeric strings, normalized to whole seconds
+    /// before the claims are validated and deserialized.
+    #[inline]
+    pub fn with_lenient_numeric_dates(self) -> Self {
+        Verifier {
+            lenient_numeric_dates: true,
+            ..self
+        }
+    }
+
+    /// Accept `iss`, `sub`, `aud` and `jti` as numbers, converted to strings before the claims
+    /// are validated and deserialized.
+    #[inline]
+    pub fn with_lenient_string_claims(self) -> Self {
+        Verifier {
+            lenient_string_claims: true,
+            ..self
+        }
+    }
+
+    /// Ignore whitespace around the token and a `Bearer` scheme before it, see `bearer::strip`.
+    #[inline]
+    pub fn with_lenient_parsing(self) -> Self {
+        Verifier {
+            lenient_parsing: true,
+            ..self
+        }
+    }
+
+    /// Reject headers with parameters other than the registered ones and the allowed, see
+    /// `DecodeContext::with_strict_header`.
+    #[inline]
+    pub fn with_strict_header(self, allowed: impl IntoIterator<Item=impl Into<String>>) -> Self {
+        Verifier {
+            strict_header: Some(allowed.into_iter().map(Into::into).collect()),
+            ..self
+        }
+    }
+
+    /// Accept the extensions in the `crit` header as understood, see
+    /// `DecodeContext::with_crit_extensions`.
+    #[inline]
+    pub fn with_crit_extensions(self, extensions: impl IntoIterator<Item=impl Into<String>>) -> Self {
+        Verifier {
+            crit_extensions: Some(extensions.into_iter().map(Into::into).collect()),
+            ..self
+        }
+    }
+
+    /// Accept payloads compressed by `Encoder::gzip_payload`, decompressed to at most `max_len`
+    /// bytes, see `DecodeContext::with_gzip`.
+    #[cfg(feature = "gzip")]
+    #[inline]
+    pub fn with_gzip(self, max_len: usize) -> Self {
+        Verifier {
+            gzip_max_len: Some(max_len),
+            ..self
+        }
+    }
+
+    /// Accept only the algorithms in the `alg` header, checked before the signature. `none` is
+    /// never accepted once the algorithms are pinned.
+    #[inline]
+    pub fn with_algorithms(self, algorithms: impl IntoIterator<Item=Alg>) -> Self {
+        Verifier {
+            algorithms: algorithms.into_iter().filter(|x| *x != Alg::None).collect(),
+            ..self
+        }
+    }
+
+    /// Accept the algorithm of an `Algorithm` implementation by its name, e.g. of an
+    /// unregistered algorithm, in addition to the pinned ones, see `with_algorithms`.
+    ///
+    /// ```rust
+    /// use jwts::{Claims, Error, jws, Verifier};
+    /// use jwts::jws::{Algorithm, Header, VerifyWith};
+    /// use jwts::jws::alg::HS256;
+    ///
+    /// /// HS256 by a private name.
+    /// struct HmacSha256;
+    ///
+    /// impl Algorithm for HmacSha256 {
+    ///     type SignKey = [u8];
+    ///     type VerifyKey = [u8];
+    ///
+    ///     fn name() -> &'static str {
+    ///         "HMAC-SHA256"
+    ///     }
+    ///
+    ///     fn sign(data: impl AsRef<[u8]>, key: &[u8]) -> Result<Vec<u8>, Error> {
+    ///         HS256::sign(data, key)
+    ///     }
+    ///
+    ///     fn verify(data: impl AsRef<[u8]>, sig: impl AsRef<[u8]>, key: &[u8]) -> Result<(), Error> {
+    ///         HS256::verify(data, sig, key)
+    ///     }
+    /// }
+    ///
+    /// let verifier = Verifier::new(VerifyWith::<HmacSha256>(b"secret")).with_algorithm::<HmacSha256>();
+    /// let token = jws::encode::<HmacSha256>(Header::default(), &Claims::default(), b"secret").unwrap();
+    /// verifier.verify::<Claims>(&token).unwrap();
+    /// let token = jws::encode::<HS256>(Header::default(), &Claims::default(), b"secret").unwrap();
+    /// assert!(verifier.verify::<Claims>(&token).is_err());
+    /// ```
+    #[inline]
+    pub fn with_algorithm<A: Algorithm>(mut self) -> Self {
+        let alg = Alg::from(A::name());
+        if alg != Alg::None && !self.algorithms.contains(&alg) {
+            self.algorithms.push(alg);
+        }
+        self
+    }
+
+    /// Accept the `alias` in the `alg` header as the pinned algorithm `alg`, e.g. `Ed25519` as
+    /// `EdDSA`. The alias is accepted only while `alg` is pinned.
+    #[inline]
+    pub fn with_alg_alias(mut self, alias: impl Into<Alg>, alg: Alg) -> Self {
+        self.alg_aliases.push((alias.into(), alg));
+        self
+    }
+
+    /// Require the `typ` header of the media type without the `application/` prefix, e.g. `JWT`,
+    /// compared case-insensitively, see RFC 8725 section 3.11.
+    #[inline]
+    pub fn with_typ(self, typ: impl Into<String>) -> Self {
+        Verifier {
+            typ: Some(typ.into()),
+            ..self
+        }
+    }
+
+    /// Reject tokens longer than `len` bytes before decoding them.
+    #[inline]
+    pub fn with_max_token_len(self, len: usize) -> Self {
+        Verifier {
+            max_token_len: Some(len),
+            ..self
+        }
+    }
+
+    /// The weaknesses of the configuration by the best current practices of RFC 8725, empty if
+    /// none is found, see `jwts::audit`.
+    pub fn audit(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        self.verify.audit(&mut findings);
+        if !self.validations.iter().any(|x| x.validates_claim("exp")) {
+            findings.push(Finding::NoExpirationRequired);
+        }
+        findings
+    }
+
+    /// Decode and verify the token, validate the claims, then deserialize the payload as `C`.
+    /// Payloads are decoded by their `cty` header, see `codec::AnyCodec`.
+    #[inline]
+    pub fn verify<C: DeserializeOwned>(&self, token: &str) -> Result<Token<C>, VerifyError> {
+        self.verify_with_context(token, &ValidationContext::new())
+    }
+
+    /// Verify the token like `verify`, validating the claims with the request-scoped data of the
+    /// context, see `Validation::validate_with`.
+    pub fn verify_with_context<C: DeserializeOwned>(&self, token: &str, context: &ValidationContext) -> Result<Token<C>, VerifyError> {
+        if self.observers.is_empty() {
+            return self.verify_unobserved(token, context);
+        }
+        let stopwatch = Stopwatch::start();
+        let result = self.verify_unobserved(token, context);
+        let elapsed = stopwatch.elapsed();
+        for observer in &self.observers {
+            match &result {
+                Ok(token) => observer.on_verify_success(&token.header, elapsed),
+                Err(err) => observer.on_verify_failure(err.kind(), elapsed),
+            }
+        }
+        result
+    }
+
+    /// Verify the token like `verify`, with what was checked for audit logs, see `VerifiedClaims`.
+    ///
+    /// ```rust
+    /// use jwts::{Claims, jws, Verifier};
+    /// use jwts::jws::{Alg, Header, VerifyWith};
+    /// use jwts::jws::alg::HS256;
+    /// use jwts::validate::{ExpectIss, ExpiredTime};
+    ///
+    /// let verifier = Verifier::new(VerifyWith::<HS256>(b"secret")).with_validation(ExpiredTime).with_validation(ExpectIss("sea"));
+    /// let claims = Claims { iss: Some("sea".to_owned()), ..Default::default() }.expired_in(std::time::Duration::from_secs(60));
+    /// let token = jws::encode::<HS256>(Header::default().with_kid("key-1"), &claims, b"secret").unwrap();
+    /// let verified = verifier.verify_audited::<Claims>(&token).unwrap();
+    /// assert_eq!((verified.alg(), verified.kid()), (Some(&Alg::HS256), Some("key-1")));
+    /// assert_eq!(verified.validated, ["iss", "exp"]);
+    /// ```
+    pub fn verify_audited<C: DeserializeOwned>(&self, token: &str) -> Result<VerifiedClaims<C>, VerifyError> {
+        let verified_at = NumericDate::now();
+        let Token { header, payload, .. } = self.verify(token)?;
+        let validated = AUDITED_CLAIMS.into_iter()
+            .filter(|x| self.validations.iter().any(|y| y.validates_claim(x)))
+            .collect();
+        Ok(VerifiedClaims { claims: payload, header, validated, leeway: self.leeway, verified_at })
+    }
+
+    fn verify_unobserved<C: DeserializeOwned>(&self, token: &str, validation_context: &ValidationContext) -> Result<Token<C>, VerifyError> {
+        if self.max_token_len.is_some_and(|x| token.len() > x) {
+            return Err(Error::Rejected("token too large").into());
+        }
+        // taken rather than borrowed, so that a verification verifying another token gets a new one
+        let context = DECODE_CONTEXT.take().reset();
+        let context = if self.lenient_parsing { context.with_lenient_parsing() } else { context };
+        let context = match &self.strict_header {
+            Some(allowed) => context.with_strict_header_shared(allowed.clone()),
+            None => context,
+        };
+        let mut context = match &self.crit_extensions {
+            Some(extensions) => context.with_crit_extensions_shared(extensions.clone()),
+            None => context,
+        };
+        #[cfg(feature = "gzip")]
+        if let Some(max_len) = self.gzip_max_len {
+            context = context.with_gzip(max_len);
+        }
+        let decoded = context.decode_with::<Value, _>(token, &AnyCodec, CheckHeader(self));
+        DECODE_CONTEXT.set(context);
+        let Token { header, mut payload, signature } = decoded?;
+        claims::remap_claims(&mut payload, &self.claim_mappings);
+        if self.lenient_numeric_dates {
+            claims::normalize_numeric_dates(&mut payload);
+        }
+        if self.lenient_string_claims {
+            claims::normalize_string_claims(&mut payload);
+        }
+        for validation in &self.validations {
+            validation.validate_with(&payload, validation_context)?;
+        }
+        let payload = json::from_value(payload).map_err(|e| Error::json(Segment::Payload, e))?;
+        Ok(Token { header, payload, signature })
+    }
+}
+
+impl<V> Verifier<V> {
+    /// Whether the `alg` header is a pinned algorithm or an alias of one.
+    fn allows(&self, alg: &Alg) -> bool {
+        *alg != Alg::None && (self.algorithms.contains(alg) || self.alg_aliases.iter().any(|(alias, x)| alias == alg && self.algorithms.contains(x)))
+    }
+}
+
+/// The header checks of the `Verifier`, before its verification.
+struct CheckHeader<'a, V>(&'a Verifier<V>);
+
+impl<V: Verify<Value>> Verify<Value> for CheckHeader<'_, V> {
+    fn verify(&self, f2s: &str, signature: &[u8], header: &Header, payload: &Value) -> Result<(), Error> {
+        let verifier = self.0;
+        if !verifier.algorithms.is_empty() && !header.alg.as_ref().is_some_and(|x| verifier.allows(x)) {
+            return Err(Error::Rejected("algorithm not allowed"));
+        }
+        if let Some(typ) = &verifier.typ {
+            if !header.typ.as_deref().is_some_and(|x| codec::is_media_type(x, typ)) {
+                return Err(Error::Rejected("unexpected typ"));
+            }
+        }
+        verifier.verify.verify(f2s, signature, header, payload)
+    }
+}
@@ -0,0 +1,176 @@
+//! Sign and verify with the WebCrypto API
+//!
+//! On `wasm32` in browsers and workers, tokens can be signed and verified by the `SubtleCrypto`
+//! of the runtime instead of a crypto backend compiled into the binary. The HS, RS, PS and ES
+//! algorithms are supported, with keys imported once as a `WebCryptoKey`.
+//!
+//! ```rust,no_run
+//! use jwts::{Claims, jws};
+//! use jwts::jws::Header;
+//! use jwts::jws::alg::HS256;
+//! use jwts::webcrypto::{self, WebCryptoKey};
+//!
+//! # async fn run() -> Result<(), jwts::Error> {
+//! let key = WebCryptoKey::import_hmac::<HS256>(b"secret").await?;
+//! let token = webcrypto::encode(Header::default(), &Claims::default(), &key).await?;
+//! let decoded = webcrypto::decode::<Claims>(&token, &key).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use js_sys::{Array, Object, Promise, Reflect, Uint8Array};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{CryptoKey, SubtleCrypto};
+
+use crate::bs64;
+use crate::error::Error;
+use crate::jwk::Jwk;
+use crate::jws::{self, Alg, Algorithm, DangerousNoVerify, Header, Token};
+
+/// A key imported into the WebCrypto API for an algorithm.
+#[derive(Debug, Clone)]
+pub struct WebCryptoKey {
+    key: CryptoKey,
+    alg: Alg,
+}
+
+impl WebCryptoKey {
+    /// Import the HMAC secret for `A` of the HS algorithms, to sign and verify.
+    pub async fn import_hmac<A: Algorithm>(secret: &[u8]) -> Result<Self, Error> {
+        let alg = Alg::from(A::name());
+        if !is_hmac(&alg) {
+            return Err(Error::InvalidKey("not an HMAC algorithm"));
+        }
+        let params = import_params(&alg)?;
+        let data = Uint8Array::from(secret);
+        let usages = Array::of2(&"sign".into(), &"verify".into());
+        let promise = subtle()?.import_key_with_object("raw", &data, &params, false, &usages)
+            .map_err(|_| Error::InvalidKey("key rejected by WebCrypto"))?;
+        WebCryptoKey::imported(promise, alg).await
+    }
+
+    /// Import the JWK for `A`, a private key to sign or a public key to verify.
+    pub async fn import_jwk<A: Algorithm>(jwk: &Jwk) -> Result<Self, Error> {
+        let alg = Alg::from(A::name());
+        let params = import_params(&alg)?;
+        let usages = match (is_hmac(&alg), jwk.extra.contains_key("d")) {
+            (true, _) => Array::of2(&"sign".into(), &"verify".into()),
+            (false, true) => Array::of1(&"sign".into()),
+            (false, false) => Array::of1(&"verify".into()),
+        };
+        let jwk = serde_json::to_string(jwk).map_err(|_| Error::InvalidKey("invalid JWK"))?;
+        let data = js_sys::JSON::parse(&jwk).map_err(|_| Error::InvalidKey("invalid JWK"))?;
+        let promise = subtle()?.import_key_with_object("jwk", data.unchecked_ref(), &params, false, &usages)
+            .map_err(|_| Error::InvalidKey("key rejected by WebCrypto"))?;
+        WebCryptoKey::imported(promise, alg).await
+    }
+
+    /// The algorithm of the key.
+    #[inline]
+    pub fn alg(&self) -> &Alg {
+        &self.alg
+    }
+
+    async fn imported(promise: Promise, alg: Alg) -> Result<Self, Error> {
+        let key = JsFuture::from(promise).await.map_err(|_| Error::InvalidKey("key rejected by WebCrypto"))?;
+        Ok(WebCryptoKey { key: key.unchecked_into(), alg })
+    }
+}
+
+/// Sign the data with the key.
+pub async fn sign(data: &[u8], key: &WebCryptoKey) -> Result<Vec<u8>, Error> {
+    let params = sign_params(&key.alg)?;
+    let promise = subtle()?.sign_with_object_and_u8_array(&params, &key.key, data)
+        .map_err(|_| Error::Crypto)?;
+    let signature = JsFuture::from(promise).await.map_err(|_| Error::Crypto)?;
+    Ok(Uint8Array::new(&signature).to_vec())
+}
+
+/// Verify the signature of the data with the key.
+pub async fn verify(data: &[u8], signature: &[u8], key: &WebCryptoKey) -> Result<(), Error> {
+    let params = sign_params(&key.alg)?;
+    let promise = subtle()?.verify_with_object_and_u8_array_and_u8_array(&params, &key.key, signature, data)
+        .map_err(|_| Error::Crypto)?;
+    let valid = JsFuture::from(promise).await.map_err(|_| Error::Crypto)?;
+    valid.as_bool().filter(|x| *x).map(|_| ()).ok_or(Error::InvalidSignature)
+}
+
+/// Encode and sign a token with the key, setting the `alg` header of the key.
+pub async fn encode(header: Header, payload: &impl Serialize, key: &WebCryptoKey) -> Result<String, Error> {
+    let header = Header { alg: Some(key.alg.clone()), ..header };
+    let input = jws::signing_input(header, payload)?;
+    let signature = sign(input.as_bytes(), key).await?;
+    Ok(format!("{}.{}", input, bs64::from_bytes(signature)))
+}
+
+/// Decode a token and verify it with the key, rejecting tokens of another `alg`.
+pub async fn decode<P: DeserializeOwned>(token: &str, key: &WebCryptoKey) -> Result<Token<P>, Error> {
+    let decoded = jws::decode::<P>(token, DangerousNoVerify)?;
+    if decoded.header.alg.as_ref() != Some(&key.alg) {
+        return Err(Error::Rejected("unexpected alg"));
+    }
+    let (f2s, _) = token.rsplit_once('.').ok_or(Error::Malformed)?;
+    verify(f2s.as_bytes(), decoded.signature.as_ref(), key).await?;
+    Ok(decoded)
+}
+
+/// The `SubtleCrypto` of the global scope, of a window or a worker.
+fn subtle() -> Result<SubtleCrypto, Error> {
+    let crypto = Reflect::get(&js_sys::global(), &"crypto".into()).map_err(|_| Error::Crypto)?;
+    match crypto.is_undefined() {
+        true => Err(Error::Crypto),
+        false => Ok(crypto.unchecked_into::<web_sys::Crypto>().subtle()),
+    }
+}
+
+fn is_hmac(alg: &Alg) -> bool {
+    matches!(alg, Alg::HS256 | Alg::HS384 | Alg::HS512)
+}
+
+fn hash(alg: &Alg) -> Result<&'static str, Error> {
+    match alg {
+        Alg::HS256 | Alg::RS256 | Alg::PS256 | Alg::ES256 => Ok("SHA-256"),
+        Alg::HS384 | Alg::RS384 | Alg::PS384 | Alg::ES384 => Ok("SHA-384"),
+        Alg::HS512 | Alg::RS512 | Alg::PS512 => Ok("SHA-512"),
+        _ => Err(Error::Rejected("algorithm not supported by WebCrypto")),
+    }
+}
+
+fn object(members: &[(&str, JsValue)]) -> Object {
+    let object = Object::new();
+    for (name, value) in members {
+        // setting a member of a plain object never fails
+        let _ = Reflect::set(&object, &(*name).into(), value);
+    }
+    object
+}
+
+/// The algorithm parameters of `importKey`.
+fn import_params(alg: &Alg) -> Result<Object, Error> {
+    let hash = hash(alg)?.into();
+    Ok(match alg {
+        Alg::HS256 | Alg::HS384 | Alg::HS512 => object(&[("name", "HMAC".into()), ("hash", hash)]),
+        Alg::RS256 | Alg::RS384 | Alg::RS512 => object(&[("name", "RSASSA-PKCS1-v1_5".into()), ("hash", hash)]),
+        Alg::PS256 | Alg::PS384 | Alg::PS512 => object(&[("name", "RSA-PSS".into()), ("hash", hash)]),
+        Alg::ES256 => object(&[("name", "ECDSA".into()), ("namedCurve", "P-256".into())]),
+        Alg::ES384 => object(&[("name", "ECDSA".into()), ("namedCurve", "P-384".into())]),
+        _ => return Err(Error::Rejected("algorithm not supported by WebCrypto")),
+    })
+}
+
+/// The algorithm parameters of `sign` and `verify`.
+fn sign_params(alg: &Alg) -> Result<Object, Error> {
+    let hash: JsValue = hash(alg)?.into();
+    Ok(match alg {
+        Alg::HS256 | Alg::HS384 | Alg::HS512 => object(&[("name", "HMAC".into())]),
+        Alg::RS256 | Alg::RS384 | Alg::RS512 => object(&[("name", "RSASSA-PKCS1-v1_5".into())]),
+        Alg::PS256 => object(&[("name", "RSA-PSS".into()), ("saltLength", 32.into())]),
+        Alg::PS384 => object(&[("name", "RSA-PSS".into()), ("saltLength", 48.into())]),
+        Alg::PS512 => object(&[("name", "RSA-PSS".into()), ("saltLength", 64.into())]),
+        Alg::ES256 | Alg::ES384 => object(&[("name", "ECDSA".into()), ("hash", hash)]),
+        _ => return Err(Error::Rejected("algorithm not supported by WebCrypto")),
+    })
+}
@@ -0,0 +1,97 @@
+//! Certificate chain verification
+
+use std::marker::PhantomData;
+
+use rustls_pki_types::{CertificateDer, TrustAnchor, UnixTime};
+use webpki::{EndEntityCert, KeyUsage};
+
+use crate::{bs64, der};
+use crate::error::Error;
+use crate::jws::{Alg, Algorithm, Header, Verify};
+
+use super::verify_thumbprints;
+
+/// Trusted root certificates.
+#[derive(Debug, Clone)]
+pub struct TrustAnchors(Vec<TrustAnchor<'static>>);
+
+impl TrustAnchors {
+    /// Load the trust anchors from DER encoded root certificates.
+    pub fn from_der(certs: &[impl AsRef<[u8]>]) -> Result<Self, Error> {
+        certs.iter()
+            .map(|x| {
+                let cert = CertificateDer::from(x.as_ref());
+                webpki::anchor_from_trusted_cert(&cert)
+                    .map(|x| x.to_owned())
+                    .map_err(|_| Error::InvalidKey("invalid root certificate"))
+            })
+            .collect::<Result<_, _>>()
+            .map(TrustAnchors)
+    }
+}
+
+/// Verify with the public key of the leaf certificate in `x5c`,
+/// after validating the certificate chain against the trust anchors for the extended key usage.
+/// The leaf certificate is checked against `x5t` and `x5t#S256` if present, and its key must be
+/// of the type of `A`.
+pub struct VerifyWithX5c<'a, A: Algorithm> {
+    anchors: &'a TrustAnchors,
+    usage: KeyUsage,
+    _alg: PhantomData<A>,
+}
+
+impl<'a, A: Algorithm> VerifyWithX5c<'a, A> {
+    /// Verify with the trust anchors for the extended key usage, e.g. `KeyUsage::client_auth()`
+    /// or `KeyUsage::required(oid)` of a signing purpose. A leaf without the extension is accepted
+    /// for any usage.
+    #[inline]
+    pub fn new(anchors: &'a TrustAnchors, usage: KeyUsage) -> Self {
+        VerifyWithX5c {
+            anchors,
+            usage,
+            _alg: PhantomData,
+        }
+    }
+}
+
+impl<'a, P, A: Algorithm<VerifyKey=[u8]>> Verify<P> for VerifyWithX5c<'a, A> {
+    fn verify(&self, f2s: &str, signature: &[u8], header: &Header, _payload: &P) -> Result<(), Error> {
+        let chain = header.x5c.as_ref().ok_or(Error::InvalidKey("missing x5c"))?;
+        let chain = chain.iter()
+            .map(|x| bs64::std_to_bytes(x).map(CertificateDer::from))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| Error::InvalidKey("malformed x5c"))?;
+        let (leaf, intermediates) = chain.split_first().ok_or(Error::InvalidKey("empty x5c"))?;
+        if header.x5t.is_some() || header.x5t_s256.is_some() {
+            verify_thumbprints(header, leaf)?;
+        }
+
+        let leaf = EndEntityCert::try_from(leaf).map_err(|_| Error::InvalidKey("invalid certificate"))?;
+        leaf.verify_for_usage(
+            webpki::ALL_VERIFICATION_ALGS,
+            &self.anchors.0,
+            intermediates,
+            UnixTime::now(),
+            self.usage,
+            None,
+            None,
+        ).map_err(|_| Error::Rejected("untrusted certificate chain"))?;
+
+        let spki = leaf.subject_public_key_info();
+        if !key_type_matches(&Alg::from(A::name()), der::spki_algorithm(spki.as_ref())?) {
+            return Err(Error::InvalidKey("certificate key type mismatch"));
+        }
+        A::verify(f2s, signature, der::spki_public_key(spki.as_ref())?)
+    }
+}
+
+/// Whether the AlgorithmIdentifier of a public key is of the key type, and curve, of the algorithm.
+fn key_type_matches(alg: &Alg, algorithm: &[u8]) -> bool {
+    match alg {
+        Alg::RS256 | Alg::RS384 | Alg::RS512 | Alg::PS256 | Alg::PS384 | Alg::PS512 => algorithm.starts_with(der::RSA_OID),
+        Alg::ES256 => algorithm == [der::EC_OID, der::P256_OID].concat(),
+        Alg::ES384 => algorithm == [der::EC_OID, der::P384_OID].concat(),
+        Alg::EdDSA | Alg::Ed25519 => algorithm == der::ED25519_OID,
+        _ => false,
+    }
+}
@@ -0,0 +1,38 @@
+//! X.509 certificate helpers for the `x5c`, `x5t` and `x5t#S256` headers
+//!
+//! Certificate chain verification requires the feature `x5c`.
+
+use crate::{bs64, crypto};
+use crate::error::Error;
+use crate::jws::Header;
+
+#[cfg(feature = "x5c")]
+pub use self::chain::{TrustAnchors, VerifyWithX5c};
+#[cfg(feature = "x5c")]
+pub use webpki::KeyUsage;
+
+#[cfg(feature = "x5c")]
+mod chain;
+
+/// SHA-1 thumbprint of the DER encoded certificate, as the `x5t` header expects.
+#[inline]
+pub fn thumbprint_sha1(cert: impl AsRef<[u8]>) -> String {
+    bs64::from_bytes(crypto::sha1(cert.as_ref()))
+}
+
+/// SHA-256 thumbprint of the DER encoded certificate, as the `x5t#S256` header expects.
+#[inline]
+pub fn thumbprint_sha256(cert: impl AsRef<[u8]>) -> String {
+    bs64::from_bytes(crypto::sha256(cert.as_ref()))
+}
+
+/// Check the DER encoded certificate against the `x5t` and `x5t#S256` headers,
+/// at least one of them must be present and all present ones must match.
+pub fn verify_thumbprints(header: &Header, cert: impl AsRef<[u8]>) -> Result<(), Error> {
+    if header.x5t.is_none() && header.x5t_s256.is_none() {
+        return Err(Error::InvalidKey("missing x5t"));
+    }
+    let matches = header.x5t.as_ref().is_none_or(|x| *x == thumbprint_sha1(&cert))
+        && header.x5t_s256.as_ref().is_none_or(|x| *x == thumbprint_sha256(&cert));
+    matches.then_some(()).ok_or(Error::InvalidKey("certificate thumbprint mismatch"))
+}
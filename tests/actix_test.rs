@@ -0,0 +1,73 @@
+//! Tests of the `actix-web` feature.
+
+#![cfg(all(feature = "actix-web", feature = "alg-hs"))]
+
+use std::time::{Duration, SystemTime};
+
+use actix_web::{test, web, App};
+use actix_web::http::StatusCode;
+use actix_web::http::header;
+
+use jwts::{Claims, jws, Verifier};
+use jwts::actix::JwtAuth;
+use jwts::jws::{Header, VerifyWith};
+use jwts::jws::alg::HS256;
+use jwts::validate::ExpiredTime;
+
+async fn whoami(claims: web::ReqData<Claims>) -> String {
+    claims.sub.clone().unwrap_or_default()
+}
+
+#[actix_web::test]
+async fn test_jwt_auth() {
+    let verifier = Verifier::new(VerifyWith::<HS256>(b"secret")).with_validation(ExpiredTime);
+    let auth = JwtAuth::<_, Claims>::new(verifier).skip("/public/");
+    let app = test::init_service(
+        App::new()
+            .wrap(auth)
+            .route("/public", web::get().to(|| async { "public" }))
+            .route("/public/page", web::get().to(|| async { "public" }))
+            .route("/publicity", web::get().to(|| async { "private" }))
+            .route("/whoami", web::get().to(whoami))
+    ).await;
+
+    let claims = Claims {
+        sub: Some("sea".to_owned()),
+        ..Default::default()
+    };
+    let valid = jws::encode::<HS256>(Header::default(), &claims.clone().expired_in(Duration::from_secs(60)), b"secret").unwrap();
+    let expired = jws::encode::<HS256>(Header::default(), &claims.clone().expired_at(SystemTime::now() - Duration::from_secs(60)), b"secret").unwrap();
+    let forged = jws::encode::<HS256>(Header::default(), &claims.expired_in(Duration::from_secs(60)), b"another").unwrap();
+
+    let get = |path: &str, auth: Option<String>| {
+        let req = test::TestRequest::get().uri(path);
+        match auth {
+            Some(auth) => req.insert_header((header::AUTHORIZATION, auth)),
+            None => req,
+        }.to_request()
+    };
+
+    let res = test::call_service(&app, get("/public", None)).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let res = test::call_service(&app, get("/public/page", None)).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let res = test::call_service(&app, get("/publicity", None)).await;
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    let res = test::call_service(&app, get("/whoami", None)).await;
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(res.headers().get(header::WWW_AUTHENTICATE).unwrap(), "Bearer");
+
+    let res = test::call_service(&app, get("/whoami", Some(format!("bearer {}", valid)))).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(test::read_body(res).await, "sea");
+
+    let res = test::call_service(&app, get("/whoami", Some(format!("Basic {}", valid)))).await;
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    let res = test::call_service(&app, get("/whoami", Some(format!("Bearer {}", expired)))).await;
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    let res = test::call_service(&app, get("/whoami", Some(format!("Bearer {}", forged)))).await;
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    let res = test::call_service(&app, get("/whoami", Some("Bearer malformed".to_owned()))).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
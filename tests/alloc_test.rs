@@ -0,0 +1,98 @@
+//! Tests of the allocations of decoding, counted by the global allocator.
+
+#![cfg(feature = "alg-hs")]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::thread;
+
+use serde_derive::Deserialize;
+use serde_json as json;
+
+use jwts::{bs64, Claims, jws, Verifier};
+use jwts::jws::{DecodeContext, Header, Signature, Verify, VerifyWith};
+use jwts::jws::alg::HS256;
+
+/// The system allocator, counting the allocations of the current thread.
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|x| x.set(x.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.with(|x| x.set(x.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// The allocations of the current thread made by `f`.
+fn allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (result, ALLOCATIONS.with(Cell::get) - before)
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Exp {
+    exp: u64,
+}
+
+#[test]
+fn test_decode_context_allocates_only_token() {
+    let claims = Claims { exp: Some(4102444800), ..Default::default() };
+    let token = jws::encode::<HS256>(Header::default(), &claims, b"secret").unwrap();
+    let verify = VerifyWith::<HS256>(b"secret");
+
+    // the allocations of the token itself, its header and payload parsed from the decoded bytes
+    let (f2s, signature) = token.rsplit_once('.').unwrap();
+    let (header, payload) = f2s.split_once('.').unwrap();
+    let (header, payload, signature) = (bs64::to_bytes(header).unwrap(), bs64::to_bytes(payload).unwrap(), bs64::to_bytes(signature).unwrap());
+    let (_, expected) = allocations(|| {
+        let header: Header = json::from_slice(&header).unwrap();
+        let payload: Exp = json::from_slice(&payload).unwrap();
+        verify.verify(f2s, &signature, &header, &payload).unwrap();
+        (header, payload, Signature::from(signature.as_slice()))
+    });
+
+    let mut context = DecodeContext::new();
+    let (_, cold) = allocations(|| context.decode::<Exp>(&token, &verify).unwrap());
+    assert!(cold > expected);
+    for _ in 0..3 {
+        let (decoded, warm) = allocations(|| context.decode::<Exp>(&token, &verify).unwrap());
+        assert_eq!(decoded.payload, Exp { exp: 4102444800 });
+        assert_eq!(warm, expected);
+    }
+}
+
+#[test]
+fn test_verifier_reuses_decode_buffers() {
+    let claims = Claims { exp: Some(4102444800), ..Default::default() };
+    let token = jws::encode::<HS256>(Header::default(), &claims, b"secret").unwrap();
+    let verifier = Verifier::new(VerifyWith::<HS256>(b"secret"));
+
+    // on a new thread, so that the first verification is cold
+    thread::spawn(move || {
+        let (_, cold) = allocations(|| verifier.verify::<Exp>(&token).unwrap());
+        let (_, warm) = allocations(|| verifier.verify::<Exp>(&token).unwrap());
+        assert!(warm + 3 <= cold, "{warm} allocations after warm-up, {cold} before");
+        for _ in 0..3 {
+            let (verified, allocated) = allocations(|| verifier.verify::<Exp>(&token).unwrap());
+            assert_eq!(verified.payload.exp, 4102444800);
+            assert_eq!(allocated, warm);
+        }
+    }).join().unwrap();
+}
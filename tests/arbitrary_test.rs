@@ -0,0 +1,25 @@
+//! Tests of the `arbitrary` feature.
+
+#![cfg(feature = "arbitrary")]
+
+use arbitrary::{Arbitrary, Unstructured};
+
+use jwts::Claims;
+use jwts::jws::{DangerousNoVerify, DecodeContext, Token};
+
+#[test]
+fn test_arbitrary_token() {
+    let mut seed = 0x2545f4914f6cdd1du64;
+    for len in 0..256 {
+        let data: Vec<u8> = (0..len * 4).map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed as u8
+        }).collect();
+        let token = Token::<Claims>::arbitrary(&mut Unstructured::new(&data)).unwrap();
+        let crit = token.header.crit.clone().unwrap_or_default();
+        let decoded = DecodeContext::new().with_crit_extensions(crit).decode::<Claims>(&token.to_compact().unwrap(), DangerousNoVerify).unwrap();
+        assert_eq!(decoded, token);
+    }
+}
@@ -0,0 +1,23 @@
+use jwts::bearer;
+
+#[test]
+fn test_parse() {
+    assert_eq!(bearer::parse("Bearer xxx.yyy.zzz"), Some("xxx.yyy.zzz"));
+    assert_eq!(bearer::parse("BEARER xxx.yyy.zzz"), Some("xxx.yyy.zzz"));
+    assert_eq!(bearer::parse(" bearer \t xxx.yyy.zzz  "), Some("xxx.yyy.zzz"));
+    assert_eq!(bearer::parse("Bearer"), None);
+    assert_eq!(bearer::parse("Bearer   "), None);
+    assert_eq!(bearer::parse("Bearerxxx.yyy.zzz"), None);
+    assert_eq!(bearer::parse("Basic dXNlcjpwYXNz"), None);
+    assert_eq!(bearer::parse(&bearer::format("xxx.yyy.zzz")), Some("xxx.yyy.zzz"));
+}
+
+#[cfg(feature = "http")]
+#[test]
+fn test_header() {
+    let value = bearer::to_header("xxx.yyy.zzz").unwrap();
+    assert_eq!(value, "Bearer xxx.yyy.zzz");
+    assert!(value.is_sensitive());
+    assert_eq!(bearer::from_header(&value), Some("xxx.yyy.zzz"));
+    assert!(bearer::to_header("xxx\nyyy").is_err());
+}
@@ -0,0 +1,103 @@
+//! Tests of the `cli` feature.
+
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+use jwts::crypto::RsaKeyPair;
+use jwts::jwk::{Jwk, JwkSet};
+use jwts::jws::{Alg, Algorithm};
+use jwts::jws::alg::RS256;
+
+fn jwts(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_jwts")).args(args).output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn temp_file(name: &str, content: impl AsRef<[u8]>) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("jwts-cli-test-{}-{}", std::process::id(), name));
+    fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn test_cli_sign_decode_verify() {
+    let claims = temp_file("claims.json", r#"{"sub":"user"}"#);
+    let claims = claims.to_str().unwrap();
+    let output = jwts(&["sign", "--alg", "ES256", "--key", "tests/ecdsa-pri.pk8", "--kid", "key-1", "--exp", "60", claims]);
+    assert!(output.status.success());
+    let token = stdout(&output);
+    let token = token.trim();
+
+    let output = jwts(&["decode", token]);
+    assert!(output.status.success());
+    let decoded = stdout(&output);
+    assert!(decoded.contains(r#""kid": "key-1""#));
+    assert!(decoded.contains(r#""sub": "user""#));
+    assert!(decoded.contains("(valid for 1m"));
+
+    let output = jwts(&["verify", "--alg", "ES256", "--key", "tests/ecdsa-pub.pem", token]);
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("signature valid"));
+    let output = jwts(&["verify", "--alg", "ES256", "--key", "tests/ecdsa-pub384.der", token]);
+    assert_eq!(output.status.code(), Some(1));
+    let output = jwts(&["verify", "--alg", "RS256", "--key", "tests/rsa-pub.pem", token]);
+    assert_eq!(output.status.code(), Some(1));
+
+    let output = jwts(&["sign", "--alg", "RS256", "--key", "tests/rsa-pri.pem", claims]);
+    let token = stdout(&output);
+    let output = jwts(&["verify", "--alg", "RS256", "--key", "tests/rsa-pub.der", token.trim()]);
+    assert!(output.status.success());
+}
+
+#[test]
+fn test_cli_verify_jwks() {
+    let secret = temp_file("secret", b"secret");
+    let jwks = JwkSet { keys: vec![Jwk { kid: Some("key-1".to_owned()), ..Jwk::oct(b"secret") }] };
+    let jwks = temp_file("jwks.json", serde_json::to_vec(&jwks).unwrap());
+    let claims = temp_file("expired.json", r#"{"exp":1000}"#);
+    let output = jwts(&["sign", "--alg", "HS256", "--key", secret.to_str().unwrap(), "--kid", "key-1", claims.to_str().unwrap()]);
+    let token = stdout(&output);
+
+    let output = jwts(&["decode", token.trim()]);
+    assert!(stdout(&output).contains("exp: 1970-01-01 00:16:40 UTC (expired"));
+    let output = jwts(&["verify", "--jwks", jwks.to_str().unwrap(), token.trim()]);
+    assert_eq!(output.status.code(), Some(2));
+    let output = jwts(&["verify", "--alg", "HS256", "--jwks", jwks.to_str().unwrap(), token.trim()]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("signature valid, but expired"));
+
+    let jwks = JwkSet { keys: vec![Jwk { kid: Some("key-1".to_owned()), alg: Some(Alg::HS256), ..Jwk::oct(b"secret") }] };
+    let jwks = temp_file("jwks-alg.json", serde_json::to_vec(&jwks).unwrap());
+    let output = jwts(&["verify", "--jwks", jwks.to_str().unwrap(), token.trim()]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("signature valid, but expired"));
+}
+
+#[test]
+fn test_cli_verify_jwks_alg_confusion() {
+    let rsa = RS256::public_jwk(&RsaKeyPair::from_der(include_bytes!("rsa-pri.der")).unwrap()).unwrap();
+    let jwks = temp_file("jwks-rsa.json", serde_json::to_vec(&JwkSet { keys: vec![rsa.clone()] }).unwrap());
+    let secret = temp_file("rsa-as-secret", rsa.to_verify_key().unwrap());
+    let claims = temp_file("forged.json", r#"{"sub":"admin"}"#);
+    let output = jwts(&["sign", "--alg", "HS256", "--key", secret.to_str().unwrap(), claims.to_str().unwrap()]);
+    let forged = stdout(&output);
+
+    for args in [&["verify", "--alg", "RS256"][..], &["verify", "--alg", "HS256"][..]] {
+        let output = jwts(&[args, &["--jwks", jwks.to_str().unwrap(), forged.trim()]].concat());
+        assert_eq!(output.status.code(), Some(1));
+        assert!(!stdout(&output).contains("signature valid"));
+    }
+}
+
+#[test]
+fn test_cli_usage() {
+    assert_eq!(jwts(&[]).status.code(), Some(2));
+    assert_eq!(jwts(&["sign", "--alg", "HS256", "claims.json"]).status.code(), Some(2));
+    assert_eq!(jwts(&["decode", "--key", "key", "token"]).status.code(), Some(2));
+    assert_eq!(jwts(&["decode", "not-a-token"]).status.code(), Some(1));
+}
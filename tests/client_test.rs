@@ -0,0 +1,67 @@
+//! Tests of the `reqwest-middleware` feature.
+
+#![cfg(all(feature = "reqwest-middleware", feature = "alg-hs"))]
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use reqwest_middleware::ClientBuilder;
+use reqwest_middleware::reqwest::Client;
+
+use jwts::{bearer, Claims, Issuer, jws};
+use jwts::client::BearerAuth;
+use jwts::jws::VerifyWith;
+use jwts::jws::alg::HS256;
+
+/// Serve the requests, responding with their `Authorization` header value.
+fn serve(requests: usize) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        for stream in listener.incoming().take(requests) {
+            let mut stream = stream.unwrap();
+            let authorization = BufReader::new(&stream).lines()
+                .map(Result::unwrap)
+                .take_while(|x| !x.is_empty())
+                .find_map(|x| x.strip_prefix("authorization: ").map(str::to_owned))
+                .unwrap_or_default();
+            write!(stream, "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}", authorization.len(), authorization).unwrap();
+        }
+    });
+    format!("http://{}", addr)
+}
+
+#[tokio::test]
+async fn test_bearer_auth() {
+    let url = serve(4);
+    let issuer = Issuer::<HS256>::new(b"secret", Duration::from_secs(3600));
+    let claims = Claims {
+        sub: Some("sea".to_owned()),
+        ..Default::default()
+    };
+    let client = ClientBuilder::new(Client::new())
+        .with(BearerAuth::from_issuer(issuer.clone(), claims.clone()))
+        .build();
+
+    let first = client.get(&url).send().await.unwrap().text().await.unwrap();
+    let token = bearer::parse(&first).unwrap();
+    let token = jws::decode::<Claims>(token, VerifyWith::<HS256>(b"secret")).unwrap();
+    assert_eq!(token.payload.sub.as_deref(), Some("sea"));
+    let second = client.get(&url).send().await.unwrap().text().await.unwrap();
+    assert_eq!(first, second);
+
+    let minted = Arc::new(AtomicUsize::new(0));
+    let counter = minted.clone();
+    let auth = BearerAuth::new(move || {
+        counter.fetch_add(1, Ordering::SeqCst);
+        std::future::ready(issuer.issue(claims.clone()))
+    }).refresh_before(Duration::from_secs(7200));
+    let client = ClientBuilder::new(Client::new()).with(auth).build();
+    client.get(&url).send().await.unwrap();
+    client.get(&url).send().await.unwrap();
+    assert_eq!(minted.load(Ordering::SeqCst), 2);
+}
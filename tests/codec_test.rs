@@ -0,0 +1,58 @@
+//! Tests of the `cbor` and `msgpack` features.
+
+#![cfg(all(feature = "cbor", feature = "msgpack", feature = "alg-hs"))]
+
+use jwts::{Claims, Error, ErrorKind, Verifier};
+use jwts::jws::{self, DangerousNoVerify, DecodeContext, Encoder, Header, VerifyWith};
+use jwts::jws::alg::HS256;
+use jwts::jws::codec::{AnyCodec, Cbor, Json, MessagePack, PayloadCodec};
+use jwts::validate::ExpiredTime;
+
+fn claims() -> Claims {
+    Claims {
+        iss: Some("sea".to_owned()),
+        exp: Some(4102444800),
+        ..Default::default()
+    }
+}
+
+fn roundtrip(codec: impl PayloadCodec, cty: Option<&str>) {
+    let token = Encoder::new().encode_with::<HS256, _>(&codec, Header::default(), &claims(), b"secret").unwrap();
+    let decoded = DecodeContext::new().decode_with::<Claims, _>(&token, &codec, VerifyWith::<HS256>(b"secret")).unwrap();
+    assert_eq!(decoded.header.cty.as_deref(), cty);
+    assert_eq!(decoded.payload, claims());
+    let decoded = DecodeContext::new().decode_with::<Claims, _>(&token, &AnyCodec, VerifyWith::<HS256>(b"secret")).unwrap();
+    assert_eq!(decoded.payload, claims());
+
+    let verifier = Verifier::new(VerifyWith::<HS256>(b"secret")).with_validation(ExpiredTime);
+    assert_eq!(verifier.verify::<Claims>(&token).unwrap().payload, claims());
+}
+
+#[test]
+fn test_codecs() {
+    roundtrip(Json, None);
+    roundtrip(Cbor, Some("cbor"));
+    roundtrip(MessagePack, Some("msgpack"));
+}
+
+#[test]
+fn test_codec_cty() {
+    let token = Encoder::new().encode_with::<HS256, _>(&Cbor, Header::default(), &claims(), b"secret").unwrap();
+    let err = DecodeContext::new().decode_with::<Claims, _>(&token, &MessagePack, DangerousNoVerify).unwrap_err();
+    assert_eq!(err, Error::Rejected("unexpected cty"));
+    assert_eq!(jws::decode::<Claims>(&token, DangerousNoVerify).unwrap_err().kind(), ErrorKind::Malformed);
+
+    let header = Header { cty: Some("application/CBOR".to_owned()), ..Default::default() };
+    let token = jws::encode::<HS256>(header, &claims(), b"secret").unwrap();
+    let err = DecodeContext::new().decode_with::<Claims, _>(&token, &AnyCodec, DangerousNoVerify).unwrap_err();
+    assert!(matches!(err, Error::InvalidPayload(_)));
+    assert_eq!(err.kind(), ErrorKind::Malformed);
+}
+
+#[test]
+fn test_codec_compact() {
+    let json = Encoder::new().encode::<HS256>(Header::default(), &claims(), b"secret").unwrap();
+    let cbor = Encoder::new().encode_with::<HS256, _>(&Cbor, Header::default(), &claims(), b"secret").unwrap();
+    let payload = |token: &str| token.split('.').nth(1).unwrap().len();
+    assert!(payload(&cbor) < payload(&json));
+}
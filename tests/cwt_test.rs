@@ -0,0 +1,71 @@
+//! Tests of the `cwt` feature.
+
+#![cfg(all(feature = "cwt", feature = "alg-es", feature = "alg-eddsa"))]
+
+use jwts::{Claims, Error};
+use jwts::crypto::Ed25519KeyPair;
+use jwts::cwt;
+use jwts::jws::alg::{Ed25519, ES256, ES384};
+use jwts::key::EcPublicKey;
+
+/// The claims set of RFC 8392 appendix A.1.
+const RFC8392_CLAIMS: &str = "a70175636f61703a2f2f61732e6578616d706c652e636f6d02656572696b77037818636f61703a2f2f6c696768742e6578616d706c652e636f6d041a5612aeb0051a5610d9f0061a5610d9f007420b71";
+
+fn hex(s: &str) -> Vec<u8> {
+    (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+}
+
+#[test]
+fn test_cwt_claims() {
+    let claims = cwt::decode_claims(&hex(RFC8392_CLAIMS)).unwrap();
+    assert_eq!(claims, Claims {
+        iss: Some("coap://as.example.com".to_owned()),
+        sub: Some("erikw".to_owned()),
+        aud: Some("coap://light.example.com".to_owned()),
+        exp: Some(1444064944),
+        nbf: Some(1443944944),
+        iat: Some(1443944944),
+        jti: Some("C3E".to_owned()),
+    });
+    assert_eq!(cwt::encode_claims(&claims).unwrap(), hex(RFC8392_CLAIMS));
+    assert_eq!(cwt::decode_claims(&hex("a10101")).unwrap_err(), Error::Malformed);
+    assert_eq!(cwt::decode_claims(&hex("a1046161")).unwrap_err(), Error::Malformed);
+}
+
+#[test]
+fn test_cwt_sign1() {
+    let claims = Claims {
+        iss: Some("sea".to_owned()),
+        exp: Some(4102444800),
+        ..Default::default()
+    };
+    let key = include_bytes!("ecdsa-pri.pk8");
+    let token = cwt::encode::<ES256>(Some(b"key-1"), &claims, key).unwrap();
+    assert_eq!(token[0], 0xd2); // tag 18
+    let public = EcPublicKey::from_spki_der(include_bytes!("ecdsa-pub.der")).unwrap();
+    let decoded = cwt::decode::<ES256>(&token, &public).unwrap();
+    assert_eq!(decoded.alg, -7);
+    assert_eq!(decoded.kid.as_deref(), Some(&b"key-1"[..]));
+    assert_eq!(decoded.claims, claims);
+    assert_eq!(cwt::decode_unverified(&token).unwrap(), decoded);
+
+    let mut tampered = token.clone();
+    *tampered.last_mut().unwrap() ^= 1;
+    assert_eq!(cwt::decode::<ES256>(&tampered, &public).unwrap_err(), Error::InvalidSignature);
+    assert_eq!(cwt::decode::<ES384>(&token, &public).unwrap_err(), Error::Rejected("unexpected alg"));
+    assert_eq!(cwt::decode::<ES256>(&token[1..20], &public).unwrap_err(), Error::Malformed);
+
+    let tagged = [&[0xd8, 0x3d][..], &token].concat(); // tag 61
+    assert_eq!(cwt::decode::<ES256>(&tagged, &public).unwrap(), decoded);
+}
+
+#[test]
+fn test_cwt_eddsa() {
+    let key = Ed25519KeyPair::from_pkcs8(include_bytes!("eddsa-pri-rfc5958.pk8")).unwrap();
+    let claims = Claims::new().issued_now();
+    let token = cwt::encode::<Ed25519>(None, &claims, &key).unwrap();
+    let decoded = cwt::decode::<Ed25519>(&token, include_bytes!("eddsa-pub.der")).unwrap();
+    assert_eq!(decoded.alg, -8);
+    assert_eq!(decoded.kid, None);
+    assert_eq!(decoded.claims, claims);
+}
@@ -0,0 +1,38 @@
+use jwts::extract::{cookie, query_param, TokenExtractor};
+
+#[test]
+fn test_cookie() {
+    assert_eq!(cookie("session=xxx.yyy.zzz", "session"), Some("xxx.yyy.zzz"));
+    assert_eq!(cookie("theme=dark;  session=\"xxx.yyy.zzz\"; lang=en", "session"), Some("xxx.yyy.zzz"));
+    assert_eq!(cookie("mysession=xxx.yyy.zzz", "session"), None);
+    assert_eq!(cookie("session=", "session"), None);
+}
+
+#[test]
+fn test_query_param() {
+    assert_eq!(query_param("token=xxx.yyy.zzz", "token"), Some("xxx.yyy.zzz"));
+    assert_eq!(query_param("?page=2&token=xxx.yyy.zzz&flag", "token"), Some("xxx.yyy.zzz"));
+    assert_eq!(query_param("access_token=xxx.yyy.zzz", "token"), None);
+    assert_eq!(query_param("token", "token"), None);
+}
+
+#[test]
+fn test_token_extractor() {
+    let bearer = Some("Bearer xxx.yyy.zzz");
+    let cookies = ["session=aaa.bbb.ccc"];
+    let query = Some("token=ddd.eee.fff");
+
+    let extractor = TokenExtractor::new();
+    assert_eq!(extractor.extract(bearer, cookies, query), Some("xxx.yyy.zzz"));
+    assert_eq!(extractor.extract(None, cookies, query), None);
+
+    let extractor = extractor.with_cookie("session");
+    assert_eq!(extractor.extract(bearer, cookies, query), Some("xxx.yyy.zzz"));
+    assert_eq!(extractor.extract(Some("Basic dXNlcjpwYXNz"), ["theme=dark", "session=aaa.bbb.ccc"], query), Some("aaa.bbb.ccc"));
+    assert_eq!(extractor.extract(None, [], query), None);
+
+    let extractor = extractor.with_query("token").without_header();
+    assert_eq!(extractor.extract(bearer, [], query), Some("ddd.eee.fff"));
+    assert_eq!(extractor.extract(bearer, cookies, query), Some("aaa.bbb.ccc"));
+    assert_eq!(extractor.extract(bearer, [], None), None);
+}
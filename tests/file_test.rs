@@ -0,0 +1,49 @@
+//! Tests of the keys loaded from files.
+
+#![cfg(feature = "alg-hs")]
+
+use std::fs;
+use std::fs::File;
+use std::time::{Duration, SystemTime};
+
+use serde_json as json;
+
+use jwts::{Claims, jws};
+use jwts::file::FileJwks;
+use jwts::jwk::{Jwk, JwkSet};
+use jwts::jws::Header;
+use jwts::jws::alg::HS256;
+use jwts::keystore::VerifyWithKeyStore;
+
+fn write_jwks(path: &std::path::Path, kid: &str, key: &[u8], modified: SystemTime) {
+    let jwks = JwkSet { keys: vec![Jwk { kid: Some(kid.to_owned()), ..Jwk::oct(key) }] };
+    fs::write(path, json::to_vec(&jwks).unwrap()).unwrap();
+    File::options().write(true).open(path).unwrap().set_modified(modified).unwrap();
+}
+
+#[test]
+fn test_file_jwks() {
+    let path = std::env::temp_dir().join(format!("jwts-file-test-{}.json", std::process::id()));
+    let now = SystemTime::now();
+    write_jwks(&path, "key-1", b"secret-1", now - Duration::from_secs(60));
+    let jwks = FileJwks::open(&path).unwrap().with_interval(Duration::ZERO);
+
+    let token1 = jws::encode::<HS256>(Header::default().with_kid("key-1"), &Claims::default(), b"secret-1").unwrap();
+    let token2 = jws::encode::<HS256>(Header::default().with_kid("key-2"), &Claims::default(), b"secret-2").unwrap();
+    assert!(jws::decode::<Claims>(&token1, VerifyWithKeyStore::<HS256, _>::new(&jwks)).is_ok());
+    assert!(jws::decode::<Claims>(&token2, VerifyWithKeyStore::<HS256, _>::new(&jwks)).is_err());
+
+    let shared = jwks.clone();
+    write_jwks(&path, "key-2", b"secret-2", now);
+    assert!(jws::decode::<Claims>(&token2, VerifyWithKeyStore::<HS256, _>::new(&jwks)).is_ok());
+    assert!(jws::decode::<Claims>(&token2, VerifyWithKeyStore::<HS256, _>::new(std::thread::spawn(move || shared).join().unwrap())).is_ok());
+    assert!(jws::decode::<Claims>(&token1, VerifyWithKeyStore::<HS256, _>::new(&jwks)).is_err());
+
+    // a broken file keeps the keys loaded before
+    fs::write(&path, "{").unwrap();
+    assert!(jwks.reload().is_err());
+    assert!(jws::decode::<Claims>(&token2, VerifyWithKeyStore::<HS256, _>::new(&jwks)).is_ok());
+
+    fs::remove_file(&path).unwrap();
+    assert!(FileJwks::open(&path).is_err());
+}
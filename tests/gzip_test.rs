@@ -0,0 +1,56 @@
+//! Tests of the `gzip` feature.
+
+#![cfg(all(feature = "gzip", feature = "alg-hs"))]
+
+use jwts::{Claims, Error, jws, Verifier};
+use jwts::jws::{DecodeContext, Encoder, Header, VerifyWith};
+use jwts::jws::alg::HS256;
+use jwts::jws::codec::ZIP_PARAM;
+
+#[test]
+fn test_gzip_payload() {
+    let claims = Claims {
+        sub: Some("sea".repeat(1000)),
+        ..Default::default()
+    };
+    let plain = jws::encode::<HS256>(Header::default(), &claims, b"secret").unwrap();
+    let token = Encoder::new().gzip_payload(true).encode::<HS256>(Header::default(), &claims, b"secret").unwrap();
+    assert!(token.len() < plain.len() / 10);
+    assert_eq!(jws::decode_header(&token).unwrap().extra[ZIP_PARAM], "gzip");
+
+    let decoded = DecodeContext::new().with_gzip(4096).decode::<Claims>(&token, VerifyWith::<HS256>(b"secret")).unwrap();
+    assert_eq!(decoded.payload, claims);
+    let decoded = DecodeContext::new().with_gzip(4096).decode::<Claims>(&plain, VerifyWith::<HS256>(b"secret")).unwrap();
+    assert_eq!(decoded.payload, claims);
+
+    assert!(matches!(
+        jws::decode::<Claims>(&token, VerifyWith::<HS256>(b"secret")),
+        Err(Error::Rejected("compressed payload not accepted")),
+    ));
+    assert!(matches!(
+        DecodeContext::new().with_gzip(1024).decode::<Claims>(&token, VerifyWith::<HS256>(b"secret")),
+        Err(Error::Rejected("decompressed payload too large")),
+    ));
+    assert!(matches!(
+        DecodeContext::new().with_gzip(4096).decode::<Claims>(&token, VerifyWith::<HS256>(b"another")),
+        Err(Error::InvalidSignature),
+    ));
+
+    let mut header = Header::default();
+    header.extra.insert(ZIP_PARAM.to_owned(), "deflate".into());
+    let token = jws::encode::<HS256>(header, &claims, b"secret").unwrap();
+    assert!(matches!(
+        DecodeContext::new().with_gzip(4096).decode::<Claims>(&token, VerifyWith::<HS256>(b"secret")),
+        Err(Error::Rejected("unsupported payload compression")),
+    ));
+}
+
+#[test]
+fn test_verifier_gzip() {
+    let claims = Claims::default().issued_now();
+    let token = Encoder::new().gzip_payload(true).encode::<HS256>(Header::default(), &claims, b"secret").unwrap();
+    let verifier = Verifier::new(VerifyWith::<HS256>(b"secret")).with_strict_header(Vec::<String>::new());
+    assert!(verifier.verify::<Claims>(&token).is_err());
+    let verifier = verifier.with_gzip(1024);
+    assert_eq!(verifier.verify::<Claims>(&token).unwrap().payload, claims);
+}
@@ -0,0 +1,35 @@
+//! Tests of the `lenient-claims` feature.
+
+#![cfg(feature = "lenient-claims")]
+
+use serde_json::json;
+
+use jwts::Claims;
+
+#[test]
+fn test_claim_aliases() {
+    let claims: Claims = serde_json::from_value(json!({
+        "issuer": "sea",
+        "subject": "subject",
+        "audience": "audience",
+        "expires_at": 3,
+        "not_before": 2,
+        "issued_at": 1,
+        "jwt_id": "id",
+    })).unwrap();
+    assert_eq!(claims, Claims {
+        iss: Some("sea".to_owned()),
+        sub: Some("subject".to_owned()),
+        aud: Some("audience".to_owned()),
+        exp: Some(3),
+        nbf: Some(2),
+        iat: Some(1),
+        jti: Some("id".to_owned()),
+    });
+    assert_eq!(serde_json::to_value(&claims).unwrap()["iss"], "sea");
+
+    let claims: Claims = serde_json::from_value(json!({ "iss": "sea", "expires_in": 60 })).unwrap();
+    assert_eq!(claims.iss.as_deref(), Some("sea"));
+    assert_eq!(claims.exp, None);
+    assert!(serde_json::from_value::<Claims>(json!({ "iss": "sea", "issuer": "sea" })).is_err());
+}
@@ -0,0 +1,155 @@
+//! Tests of the `paseto` feature.
+
+#![cfg(feature = "paseto")]
+
+use serde_json::{json, Value};
+
+use jwts::{Claims, Error, Segment};
+use jwts::crypto::Ed25519KeyPair;
+use jwts::paseto::{self, Paseto};
+use jwts::validate::{ExpiredTime, Validate, ValidateError};
+
+const KEY: [u8; 32] = [
+    0x70, 0x71, 0x72, 0x73, 0x74, 0x75, 0x76, 0x77, 0x78, 0x79, 0x7a, 0x7b, 0x7c, 0x7d, 0x7e, 0x7f,
+    0x80, 0x81, 0x82, 0x83, 0x84, 0x85, 0x86, 0x87, 0x88, 0x89, 0x8a, 0x8b, 0x8c, 0x8d, 0x8e, 0x8f,
+];
+
+/// Encrypted with `KEY`, the footer `{"kid":"k"}` and the implicit assertion `assert`.
+const LOCAL_TOKEN: &str = "v4.local.sN-lK5ooPcjcbK0xeD0-i-sMrfwhxhcfn-RPh7sL_EHSmyehVHJ9tW0WdY8Jq2bIy8aBQtDR9ZoSrnFdxj2DXR9YjFIS7RhJ0WK9z6BSgsudGhGGT2b7Zpr8YwUh1JJAd6l_Gp5Hh5zlcxub473QBAavpsx4rDo1YJZWsgo4-VE.eyJraWQiOiJrIn0";
+
+/// Signed with `eddsa-pri-rfc5958.pk8`.
+const PUBLIC_TOKEN: &str = "v4.public.eyJkYXRhIjoidGhpcyBpcyBhIHNlY3JldCBtZXNzYWdlIiwiZXhwIjoiMjAyMi0wMS0wMVQwMDowMDowMFoifZ4-cL725ZJgKCROvR3yZbOZe2sEGIPcRuDB0VvqCZ0xNFJVTqlmfmPBz8tgGPNcMBnAuuiP8gLT9BBbC1e-_gc";
+
+fn payload() -> Value {
+    json!({"data": "this is a secret message", "exp": 1640995200})
+}
+
+#[test]
+fn test_paseto_local() {
+    let decrypted = paseto::decrypt::<Value>(LOCAL_TOKEN, &KEY, b"assert").unwrap();
+    assert_eq!(decrypted, Paseto { payload: payload(), footer: br#"{"kid":"k"}"#.to_vec() });
+    assert_eq!(paseto::footer(LOCAL_TOKEN).unwrap(), br#"{"kid":"k"}"#);
+    assert_eq!(paseto::decrypt::<Value>(LOCAL_TOKEN, &KEY, b"").unwrap_err(), Error::InvalidSignature);
+    assert_eq!(paseto::decrypt::<Value>(LOCAL_TOKEN, &[0; 32], b"assert").unwrap_err(), Error::InvalidSignature);
+    let tampered = LOCAL_TOKEN.replace("eyJraWQiOiJrIn0", "eyJraWQiOiJsIn0");
+    assert_eq!(paseto::decrypt::<Value>(&tampered, &KEY, b"assert").unwrap_err(), Error::InvalidSignature);
+
+    let token = paseto::encrypt(&KEY, &payload(), b"", b"").unwrap();
+    assert_ne!(token, paseto::encrypt(&KEY, &payload(), b"", b"").unwrap());
+    assert_eq!(paseto::decrypt::<Value>(&token, &KEY, b"").unwrap().payload, payload());
+    assert_eq!(paseto::verify::<Value>(&token, &[0; 32], b"").unwrap_err(), Error::Rejected("unexpected version or purpose"));
+}
+
+#[test]
+fn test_paseto_public() {
+    let key = Ed25519KeyPair::from_pkcs8(include_bytes!("eddsa-pri-rfc5958.pk8")).unwrap();
+    let public = include_bytes!("eddsa-pub.der");
+    assert_eq!(paseto::sign(&key, &payload(), b"", b"").unwrap(), PUBLIC_TOKEN);
+    let verified = paseto::verify::<Value>(PUBLIC_TOKEN, public, b"").unwrap();
+    assert_eq!(verified, Paseto { payload: payload(), footer: Vec::new() });
+    assert_eq!(paseto::verify::<Value>(PUBLIC_TOKEN, public, b"assert").unwrap_err(), Error::InvalidSignature);
+
+    let token = paseto::sign(&key, &payload(), b"footer", b"assert").unwrap();
+    assert_eq!(paseto::verify::<Value>(&token, public, b"assert").unwrap().footer, b"footer");
+    assert_eq!(paseto::decrypt::<Value>(&token, &KEY, b"assert").unwrap_err(), Error::Rejected("unexpected version or purpose"));
+    assert!(paseto::verify::<Value>("v4.public.AAAA", public, b"").is_err());
+}
+
+#[test]
+fn test_paseto_claims() {
+    let claims = Claims {
+        iss: Some("sea".to_owned()),
+        exp: Some(1640995200),
+        ..Default::default()
+    };
+    let token = paseto::encrypt(&KEY, &claims, b"", b"").unwrap();
+    let decrypted = paseto::decrypt::<Claims>(&token, &KEY, b"").unwrap();
+    assert_eq!(decrypted.payload, claims);
+    assert!(matches!(decrypted.payload.validate(ExpiredTime), Err(ValidateError::TokenExpiredAt { actual: Some(1640995200), .. })));
+
+    let key = Ed25519KeyPair::from_pkcs8(include_bytes!("eddsa-pri-rfc5958.pk8")).unwrap();
+    let public = include_bytes!("eddsa-pub.der");
+    let token = paseto::sign(&key, &json!({"exp": "2022-01-01T01:30:00.123+01:30", "iat": "2021-12-31T23:59:59z"}), b"", b"").unwrap();
+    let verified = paseto::verify::<Claims>(&token, public, b"").unwrap();
+    assert_eq!(verified.payload.exp, Some(1640995200));
+    assert_eq!(verified.payload.iat, Some(1640995199));
+
+    for exp in ["2022-01-01", "2022-13-01T00:00:00Z", "2022-01-01T00:00:00", "2022-01-01T00:00:00+1:00"] {
+        let token = paseto::sign(&key, &json!({"exp": exp}), b"", b"").unwrap();
+        let err = paseto::verify::<Claims>(&token, public, b"").unwrap_err();
+        assert!(matches!(err, Error::InvalidJson { segment: Segment::Payload, .. }), "{}", exp);
+    }
+}
+
+/// The ones of https://github.com/paseto-standard/test-vectors/blob/master/v4.json, as
+/// (name, token, payload, footer, implicit assertion).
+const LOCAL_VECTORS: [(&str, &str, &str, &str, &str); 2] = [
+    (
+        "4-E-1",
+        "v4.local.AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAQAr68PS4AXe7If_ZgesdkUMvSwscFlAl1pk5HC0e8kApeaqMfGo_7OpBnwJOAbY9V7WU6abu74MmcUE8YWAiaArVI8XJ5hOb_4v9RmDkneN0S92dx0OW4pgy7omxgf3S8c3LlQg",
+        r#"{"data":"this is a secret message","exp":"2022-01-01T00:00:00+00:00"}"#,
+        "",
+        "",
+    ),
+    (
+        "4-E-2",
+        "v4.local.AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAQAr68PS4AXe7If_ZgesdkUMvS2csCgglvpk5HC0e8kApeaqMfGo_7OpBnwJOAbY9V7WU6abu74MmcUE8YWAiaArVI8XIemu9chy3WVKvRBfg6t8wwYHK0ArLxxfZP73W_vfwt5A",
+        r#"{"data":"this is a hidden message","exp":"2022-01-01T00:00:00+00:00"}"#,
+        "",
+        "",
+    ),
+];
+
+const PUBLIC_VECTORS: [(&str, &str, &str, &str, &str); 3] = [
+    (
+        "4-S-1",
+        "v4.public.eyJkYXRhIjoidGhpcyBpcyBhIHNpZ25lZCBtZXNzYWdlIiwiZXhwIjoiMjAyMi0wMS0wMVQwMDowMDowMCswMDowMCJ9bg_XBBzds8lTZShVlwwKSgeKpLT3yukTw6JUz3W4h_ExsQV-P0V54zemZDcAxFaSeef1QlXEFtkqxT1ciiQEDA",
+        r#"{"data":"this is a signed message","exp":"2022-01-01T00:00:00+00:00"}"#,
+        "",
+        "",
+    ),
+    (
+        "4-S-2",
+        "v4.public.eyJkYXRhIjoidGhpcyBpcyBhIHNpZ25lZCBtZXNzYWdlIiwiZXhwIjoiMjAyMi0wMS0wMVQwMDowMDowMCswMDowMCJ9v3Jt8mx_TdM2ceTGoqwrh4yDFn0XsHvvV_D0DtwQxVrJEBMl0F2caAdgnpKlt4p7xBnx1HcO-SPo8FPp214HDw.eyJraWQiOiJ6VmhNaVBCUDlmUmYyc25FY1Q3Z0ZUaW9lQTlDT2NOeTlEZmdMMVc2MGhhTiJ9",
+        r#"{"data":"this is a signed message","exp":"2022-01-01T00:00:00+00:00"}"#,
+        r#"{"kid":"zVhMiPBP9fRf2snEcT7gFTioeA9COcNy9DfgL1W60haN"}"#,
+        "",
+    ),
+    (
+        "4-S-3",
+        "v4.public.eyJkYXRhIjoidGhpcyBpcyBhIHNpZ25lZCBtZXNzYWdlIiwiZXhwIjoiMjAyMi0wMS0wMVQwMDowMDowMCswMDowMCJ9NPWciuD3d0o5eXJXG5pJy-DiVEoyPYWs1YSTwWHNJq6DZD3je5gf-0M4JR9ipdUSJbIovzmBECeaWmaqcaP0DQ.eyJraWQiOiJ6VmhNaVBCUDlmUmYyc25FY1Q3Z0ZUaW9lQTlDT2NOeTlEZmdMMVc2MGhhTiJ9",
+        r#"{"data":"this is a signed message","exp":"2022-01-01T00:00:00+00:00"}"#,
+        r#"{"kid":"zVhMiPBP9fRf2snEcT7gFTioeA9COcNy9DfgL1W60haN"}"#,
+        r#"{"test-vector":"4-S-3"}"#,
+    ),
+];
+
+/// The Ed25519 secret key of the public vectors, with its public key appended.
+const VECTOR_SECRET_KEY: &str = "b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2";
+
+#[test]
+fn test_paseto_vectors() {
+    for (name, token, payload, footer, implicit) in LOCAL_VECTORS {
+        let decrypted = paseto::decrypt::<Value>(token, &KEY, implicit.as_bytes()).expect(name);
+        let mut expected: Value = serde_json::from_str(payload).unwrap();
+        expected["exp"] = json!(1640995200);
+        assert_eq!(decrypted, Paseto { payload: expected, footer: footer.as_bytes().to_vec() }, "{}", name);
+    }
+
+    let secret = hex(VECTOR_SECRET_KEY);
+    let (seed, public) = secret.split_at(32);
+    // the PKCS#8 v2 of RFC 5958, with the public key
+    let pk8 = [&hex("3051020101300506032b657004220420"), seed, &hex("812100"), public].concat();
+    let key = Ed25519KeyPair::from_pkcs8(&pk8).unwrap();
+    for (name, token, payload, footer, implicit) in PUBLIC_VECTORS {
+        let payload: Value = serde_json::from_str(payload).unwrap();
+        assert_eq!(paseto::sign(&key, &payload, footer.as_bytes(), implicit.as_bytes()).unwrap(), token, "{}", name);
+        let verified = paseto::verify::<Value>(token, public, implicit.as_bytes()).expect(name);
+        assert_eq!(verified.payload["exp"], 1640995200, "{}", name);
+        assert_eq!(verified.footer, footer.as_bytes(), "{}", name);
+    }
+}
+
+fn hex(s: &str) -> Vec<u8> {
+    (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+}
@@ -0,0 +1,150 @@
+//! Tests of the asynchronous remote key retrieval.
+
+#![cfg(feature = "alg-eddsa")]
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use jwts::{bs64, Claims, Error, jws, Verifier, VerifierConfig, VerifyError};
+use jwts::crypto::Ed25519KeyPair;
+use jwts::jwk::{Jwk, JwkSet};
+use jwts::jws::{Alg, Header, VerifyWith};
+use jwts::keystore::KeyStore;
+use jwts::jws::alg::Ed25519;
+use jwts::remote::{self, HttpGet, RemoteKeyStore};
+
+/// Serves the resources by URL.
+struct Resources(HashMap<&'static str, Vec<u8>>);
+
+impl HttpGet for Resources {
+    async fn get(&self, url: &str) -> Result<Vec<u8>, Error> {
+        self.0.get(url).cloned().ok_or_else(|| Error::fetch(format!("HTTP status 404 of {}", url)))
+    }
+}
+
+fn resources() -> Resources {
+    let jwk = Jwk {
+        kid: Some("key-1".to_owned()),
+        ..Jwk::okp("Ed25519", include_bytes!("eddsa-pub.der"))
+    };
+    let jwks = serde_json::to_vec(&JwkSet { keys: vec![jwk] }).unwrap();
+    let metadata = br#"{"issuer":"https://example.com","jwks_uri":"https://example.com/jwks.json","response_types_supported":["code"]}"#;
+    Resources(HashMap::from([
+        ("https://example.com/jwks.json", jwks),
+        ("https://example.com/.well-known/openid-configuration", metadata.to_vec()),
+    ]))
+}
+
+#[tokio::test]
+async fn test_discover() {
+    let http = resources();
+    let metadata = remote::discover(&http, "https://example.com").await.unwrap();
+    assert_eq!(metadata.jwks_uri, "https://example.com/jwks.json");
+    assert_eq!(metadata.extra["response_types_supported"], serde_json::json!(["code"]));
+    let jwks = remote::fetch_jwks(&http, &metadata.jwks_uri).await.unwrap();
+    assert!(jwks.find("key-1").is_some());
+
+    let result = remote::discover(&http, "https://example.com/").await;
+    assert_eq!(result, Err(Error::Rejected("issuer of the provider metadata mismatch")));
+    let result = remote::discover(&http, "https://another.com").await;
+    assert_eq!(result, Err(Error::fetch("HTTP status 404 of https://another.com/.well-known/openid-configuration")));
+}
+
+#[tokio::test]
+async fn test_fetch_key() {
+    let http = resources();
+    let hosts = ["example.com"];
+    let key = Ed25519KeyPair::from_pkcs8(include_bytes!("eddsa-pri-rfc5958.pk8")).unwrap();
+    let header = Header::default().with_jku("https://example.com/jwks.json").with_kid("key-1");
+    let token = jws::encode::<Ed25519>(header.clone(), &Claims::default(), &key).unwrap();
+
+    let verify_key = remote::fetch_key::<Ed25519>(&http, &hosts, &header).await.unwrap();
+    assert!(jws::decode::<Claims>(&token, VerifyWith::<Ed25519>(&verify_key)).is_ok());
+
+    let header = Header::default().with_jku("https://evil.com/jwks.json");
+    assert_eq!(remote::fetch_key::<Ed25519>(&http, &hosts, &header).await, Err(Error::Rejected("key URL not allowed")));
+}
+
+#[tokio::test]
+async fn test_remote_key_store() {
+    let http = resources();
+    let hosts = ["example.com"];
+    let key = Ed25519KeyPair::from_pkcs8(include_bytes!("eddsa-pri-rfc5958.pk8")).unwrap();
+    let store = || RemoteKeyStore::<Ed25519, _>::new(&hosts, &http);
+
+    let header = Header::default().with_jku("https://example.com/jwks.json").with_kid("key-1");
+    let token = jws::encode::<Ed25519>(header, &Claims::default(), &key).unwrap();
+    assert!(jws::decode_async::<Ed25519, Claims>(&token, store()).await.is_ok());
+
+    let header = Header::default().with_jku("https://example.com/jwks.json").with_kid("key-2");
+    let token = jws::encode::<Ed25519>(header, &Claims::default(), &key).unwrap();
+    assert_eq!(jws::decode_async::<Ed25519, Claims>(&token, store()).await.unwrap_err(), Error::InvalidKey("no matching key"));
+
+    for jku in ["http://example.com/jwks.json", "https://evil.com/jwks.json", "https://example.com@evil.com/"] {
+        let token = jws::encode::<Ed25519>(Header::default().with_jku(jku), &Claims::default(), &key).unwrap();
+        assert_eq!(jws::decode_async::<Ed25519, Claims>(&token, store()).await.unwrap_err(), Error::Rejected("key URL not allowed"));
+    }
+}
+
+#[cfg(feature = "alg-es")]
+#[tokio::test]
+async fn test_remote_key_store_x5u() {
+    use base64::Engine;
+    use jwts::jws::alg::ES256;
+
+    let pem = format!(
+        "-----BEGIN CERTIFICATE-----\n{}\n-----END CERTIFICATE-----\n",
+        base64::engine::general_purpose::STANDARD.encode(include_bytes!("x5c-leaf.der")),
+    );
+    let http = Resources(HashMap::from([("https://example.com/cert.pem", pem.into_bytes())]));
+    let header = Header::default().with_x5u("https://example.com/cert.pem");
+    let token = jws::encode::<ES256>(header, &Claims::default(), include_bytes!("x5c-leaf.pk8")).unwrap();
+    let store = RemoteKeyStore::<ES256, _>::new(&["example.com"], &http);
+    assert!(jws::decode_async::<ES256, Claims>(&token, store).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_decode_async() {
+    let http = resources();
+    let store = |header: &Header, payload: &Claims| {
+        let (header, payload) = (header.clone(), payload.clone());
+        let http = &http;
+        async move {
+            let jwks = remote::fetch_jwks(http, "https://example.com/jwks.json").await?;
+            jwks.key_for(&header, &payload).ok_or(Error::InvalidKey("no matching key"))
+        }
+    };
+    let key = Ed25519KeyPair::from_pkcs8(include_bytes!("eddsa-pri-rfc5958.pk8")).unwrap();
+    let claims = Claims { iss: Some("https://example.com".to_owned()), ..Default::default() };
+    let token = jws::encode::<Ed25519>(Header::default().with_kid("key-1"), &claims, &key).unwrap();
+    let decoded = jws::decode_async::<Ed25519, Claims>(&token, store).await.unwrap();
+    assert_eq!(decoded.payload, claims);
+    let tampered = format!("{}.{}", token.rsplit_once('.').unwrap().0, bs64::from_bytes([0; 64]));
+    assert_eq!(jws::decode_async::<Ed25519, Claims>(&tampered, store).await.unwrap_err(), Error::InvalidSignature);
+
+    let token = jws::encode::<Ed25519>(Header::default().with_kid("key-2"), &claims, &key).unwrap();
+    assert_eq!(jws::decode_async::<Ed25519, Claims>(&token, store).await.unwrap_err(), Error::InvalidKey("no matching key"));
+    let failing = |_: &Header, _: &Claims| async { Err::<Vec<u8>, _>(Error::fetch("unavailable")) };
+    assert_eq!(jws::decode_async::<Ed25519, Claims>(&token, failing).await.unwrap_err(), Error::fetch("unavailable"));
+}
+
+#[tokio::test]
+async fn test_verifier_config_jwks_url() {
+    let http = resources();
+    let config = VerifierConfig {
+        algorithms: vec![Alg::EdDSA],
+        issuer: Some("https://example.com".to_owned()),
+        jwks_url: Some("https://example.com/jwks.json".to_owned()),
+        ..Default::default()
+    };
+    let verifier = Verifier::from_config_with_http(&config, &http).await.unwrap();
+    let key = Ed25519KeyPair::from_pkcs8(include_bytes!("eddsa-pri-rfc5958.pk8")).unwrap();
+    let claims = Claims { iss: Some("https://example.com".to_owned()), ..Default::default() }.expired_in(Duration::from_secs(60));
+    let token = jws::encode::<Ed25519>(Header::default().with_kid("key-1"), &claims, &key).unwrap();
+    assert_eq!(verifier.verify::<Claims>(&token).unwrap().payload, claims);
+    let token = jws::encode::<Ed25519>(Header::default().with_kid("key-2"), &claims, &key).unwrap();
+    assert_eq!(verifier.verify::<Claims>(&token).unwrap_err(), VerifyError::Token(Error::InvalidKey("no matching key")));
+
+    let config = VerifierConfig { jwks_url: Some("https://example.com/missing.json".to_owned()), ..config };
+    assert!(Verifier::from_config_with_http(&config, &http).await.is_err());
+}
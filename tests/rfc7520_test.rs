@@ -0,0 +1,29 @@
+//! Tests of the `rfc7520` feature.
+
+#![cfg(feature = "rfc7520")]
+
+use jwts::Error;
+use jwts::jwk::Jwk;
+use jwts::jws::alg::HS256;
+use jwts::jws::stream;
+use jwts::rfc7520::{self, EXAMPLES, Example};
+
+#[test]
+fn test_rfc7520() {
+    rfc7520::run_all().unwrap();
+
+    let detached = &EXAMPLES[3];
+    let payload = jwts::bs64::to_bytes(detached.payload).unwrap();
+    let key = serde_json::from_str::<Jwk>(rfc7520::HMAC_KEY).unwrap().to_verify_key().unwrap();
+    stream::verify_detached::<HS256>(&detached.token(), payload.as_slice(), &key).unwrap();
+    assert!(detached.token().contains(".."));
+
+    let tampered = Example { signature: "t0h6KThzkfBBBkLspW1h84VsJZFTsPPqMDA7g1Md7p0", ..EXAMPLES[2] };
+    assert_eq!(tampered.run(), Err(Error::InvalidSignature));
+
+    let sections: Vec<_> = EXAMPLES.iter().map(|x| x.section).collect();
+    assert_eq!(sections, ["4.1", "4.2", "4.4", "4.5"]);
+    let pss = &EXAMPLES[1];
+    let tampered = Example { payload: &pss.payload[1..], ..*pss };
+    assert_eq!(tampered.run(), Err(Error::InvalidSignature));
+}
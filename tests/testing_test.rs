@@ -0,0 +1,46 @@
+//! Tests of the `test-utils` feature.
+
+#![cfg(all(feature = "test-utils", feature = "alg-hs", feature = "alg-rs", feature = "alg-es", feature = "alg-eddsa"))]
+
+use std::time::Duration;
+
+use jwts::{Claims, jws, Verifier};
+use jwts::jws::VerifyWith;
+use jwts::jws::alg::{Ed25519, ES256, ES384, HS256, RS256};
+use jwts::testing::{self, FixedClock};
+use jwts::validate::{Validate, ValidateError};
+
+#[test]
+fn test_keys() {
+    let claims = Claims::default();
+    let token = testing::mint::<HS256>(testing::HS_SECRET, &claims);
+    jws::decode::<Claims>(&token, VerifyWith::<HS256>(testing::HS_SECRET)).unwrap();
+    let token = testing::mint::<RS256>(&testing::rsa_key_pair(), &claims);
+    jws::decode::<Claims>(&token, VerifyWith::<RS256>(testing::RSA_PUBLIC_KEY)).unwrap();
+    let token = testing::mint::<ES256>(testing::EC_P256_PRIVATE_KEY, &claims);
+    jws::decode::<Claims>(&token, VerifyWith::<ES256>(testing::ec_p256_public_key())).unwrap();
+    let token = testing::mint::<ES384>(testing::EC_P384_PRIVATE_KEY, &claims);
+    jws::decode::<Claims>(&token, VerifyWith::<ES384>(testing::ec_p384_public_key())).unwrap();
+    let token = testing::mint::<Ed25519>(&testing::ed25519_key_pair(), &claims);
+    jws::decode::<Claims>(&token, VerifyWith::<Ed25519>(testing::ED25519_PUBLIC_KEY)).unwrap();
+}
+
+#[test]
+fn test_fixed_clock() {
+    let clock = FixedClock(1_000_000);
+    let claims = clock.claims_with_nbf(60, 10);
+    assert_eq!((claims.iat, claims.nbf, claims.exp), (Some(1_000_000), Some(1_000_010), Some(1_000_060)));
+    assert_eq!(clock.offset(-2_000_000), 0);
+
+    claims.validate(clock.issued_at_time()).unwrap();
+    claims.validate(clock.expired_time()).unwrap();
+    assert_eq!(claims.validate(clock.not_before_time()), Err(ValidateError::NotBefore { now: 1_000_000, actual: Some(1_000_010) }));
+
+    let later = clock.advance(Duration::from_secs(60));
+    claims.validate(later.not_before_time()).unwrap();
+    assert_eq!(claims.validate(later.expired_time()), Err(ValidateError::TokenExpiredAt { now: 1_000_060, actual: Some(1_000_060) }));
+
+    let verifier = Verifier::new(VerifyWith::<HS256>(testing::HS_SECRET)).with_validation(clock.expired_time());
+    assert!(verifier.verify::<Claims>(&testing::mint::<HS256>(testing::HS_SECRET, &clock.claims(1))).is_ok());
+    assert!(verifier.verify::<Claims>(&testing::mint::<HS256>(testing::HS_SECRET, &clock.claims(-1))).is_err());
+}
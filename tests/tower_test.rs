@@ -0,0 +1,116 @@
+//! Tests of the `tower` feature.
+
+#![cfg(all(feature = "tower", feature = "alg-hs"))]
+
+use std::convert::Infallible;
+use std::future::{ready, Future, Ready};
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+use http::{header, Request, Response, StatusCode};
+use tower_layer::Layer;
+use tower_service::Service;
+
+use jwts::{Claims, Error, jws, Verifier};
+use jwts::extract::TokenExtractor;
+use jwts::jws::{Header, Verify, VerifyWith};
+use jwts::jws::alg::HS256;
+use jwts::tower::JwtLayer;
+use jwts::validate::ExpiredTime;
+
+/// Responds with the subject of the claims in the extensions.
+struct Whoami;
+
+impl Service<Request<()>> for Whoami {
+    type Response = Response<String>;
+    type Error = Infallible;
+    type Future = Ready<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<()>) -> Self::Future {
+        let sub = req.extensions().get::<Claims>().and_then(|x| x.sub.clone());
+        ready(Ok(Response::new(sub.unwrap_or_default())))
+    }
+}
+
+/// Fails as if the keys could not be fetched.
+struct Unreachable;
+
+impl<P> Verify<P> for Unreachable {
+    fn verify(&self, _f2s: &str, _signature: &[u8], _header: &Header, _payload: &P) -> Result<(), Error> {
+        Err(Error::fetch("connection refused"))
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    match future.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("pending"),
+    }
+}
+
+#[test]
+fn test_jwt_layer() {
+    let verifier = Verifier::new(VerifyWith::<HS256>(b"secret")).with_validation(ExpiredTime);
+    let mut service = JwtLayer::<_, Claims>::new(verifier).skip("/public").layer(Whoami);
+
+    let claims = Claims {
+        sub: Some("sea".to_owned()),
+        ..Default::default()
+    };
+    let valid = jws::encode::<HS256>(Header::default(), &claims.clone().expired_in(Duration::from_secs(60)), b"secret").unwrap();
+    let forged = jws::encode::<HS256>(Header::default(), &claims.expired_in(Duration::from_secs(60)), b"another").unwrap();
+
+    let mut get = |path: &str, auth: Option<String>| {
+        let req = Request::get(path);
+        let req = match auth {
+            Some(auth) => req.header(header::AUTHORIZATION, auth),
+            None => req,
+        }.body(()).unwrap();
+        block_on(service.call(req)).unwrap()
+    };
+
+    let res = get("/public/page", None);
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.body(), "");
+
+    let res = get("/whoami", None);
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(res.headers().get(header::WWW_AUTHENTICATE).unwrap(), "Bearer");
+
+    let res = get("/whoami", Some(format!("Bearer {}", valid)));
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.body(), "sea");
+
+    let res = get("/whoami", Some(format!("Bearer {}", forged)));
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    let res = get("/whoami", Some("Bearer malformed".to_owned()));
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let verifier = Verifier::new(VerifyWith::<HS256>(b"secret")).with_validation(ExpiredTime);
+    let mut service = JwtLayer::<_, Claims>::new(verifier).skip("/").layer(Whoami);
+    let res = block_on(service.call(Request::get("/").body(()).unwrap())).unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let res = block_on(service.call(Request::get("/whoami").body(()).unwrap())).unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    let mut service = JwtLayer::<_, Claims>::new(Verifier::new(Unreachable)).layer(Whoami);
+    let req = Request::get("/whoami").header(header::AUTHORIZATION, format!("Bearer {}", valid)).body(()).unwrap();
+    assert_eq!(block_on(service.call(req)).unwrap().status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let verifier = Verifier::new(VerifyWith::<HS256>(b"secret")).with_validation(ExpiredTime);
+    let extractor = TokenExtractor::new().with_cookie("session").with_query("token");
+    let mut service = JwtLayer::<_, Claims>::new(verifier).with_extractor(extractor).layer(Whoami);
+
+    let req = Request::get("/whoami").header(header::COOKIE, format!("theme=dark; session={}", valid)).body(()).unwrap();
+    let res = block_on(service.call(req)).unwrap();
+    assert_eq!(res.body(), "sea");
+    let req = Request::get(format!("/whoami?token={}", valid)).body(()).unwrap();
+    let res = block_on(service.call(req)).unwrap();
+    assert_eq!(res.body(), "sea");
+}
@@ -0,0 +1,36 @@
+//! Tests of the `uuid` feature.
+
+#![cfg(all(feature = "uuid", feature = "alg-hs"))]
+
+use std::time::Duration;
+
+use jwts::{Claims, Issuer, JtiUuid, jws};
+use jwts::jws::VerifyWith;
+use jwts::jws::alg::HS256;
+
+#[test]
+fn test_random_jti() {
+    let a = Claims::new().with_random_jti().jti.unwrap();
+    let b = Claims::new().with_random_jti().jti.unwrap();
+    assert_ne!(a, b);
+    assert_eq!(a.len(), 36);
+    assert_eq!(&a[14..15], "4");
+
+    let v7 = Claims::new().with_jti_uuid(JtiUuid::V7).jti.unwrap();
+    assert_eq!(&v7[14..15], "7");
+    assert!(v7 < JtiUuid::V7.generate());
+}
+
+#[test]
+fn test_issuer_random_jti() {
+    let issuer = Issuer::<HS256>::new(b"secret", Duration::from_secs(60)).with_random_jti(JtiUuid::V4);
+    let jti = |token: &str| jws::decode::<Claims>(token, VerifyWith::<HS256>(b"secret")).unwrap().payload.jti;
+    let a = jti(&issuer.issue(Claims::new()).unwrap()).unwrap();
+    let b = jti(&issuer.issue(Claims::new()).unwrap()).unwrap();
+    assert_ne!(a, b);
+
+    let claims = Claims { jti: Some("fixed".to_owned()), ..Default::default() };
+    assert_eq!(jti(&issuer.issue(claims).unwrap()).as_deref(), Some("fixed"));
+    let issuer = Issuer::<HS256>::new(b"secret", Duration::from_secs(60));
+    assert_eq!(jti(&issuer.issue(Claims::new()).unwrap()), None);
+}
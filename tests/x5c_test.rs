@@ -0,0 +1,57 @@
+//! Tests of the `x5c` feature.
+
+#![cfg(all(feature = "x5c", feature = "alg-es", feature = "alg-hs"))]
+
+use jwts::{Claims, Error, jws};
+use jwts::jws::{Header, Token};
+use jwts::jws::alg::{ES256, ES384, HS256};
+use jwts::x509;
+use jwts::x509::{KeyUsage, TrustAnchors, VerifyWithX5c};
+
+#[test]
+fn test_verify_x5c() {
+    let claims = Claims {
+        iss: Some("sea".to_owned()),
+        ..Default::default()
+    };
+    let header = Header::default().with_x5c_der(&[include_bytes!("x5c-leaf.der")]);
+    let token = jws::encode::<ES256>(header, &claims, include_bytes!("x5c-leaf.pk8")).unwrap();
+
+    let anchors = TrustAnchors::from_der(&[include_bytes!("x5c-root.der")]).unwrap();
+    let Token { payload, .. } = jws::decode::<Claims>(&token, VerifyWithX5c::<ES256>::new(&anchors, KeyUsage::client_auth())).unwrap();
+    assert_eq!(payload, claims);
+
+    let result = jws::decode::<Claims>(&token, VerifyWithX5c::<ES384>::new(&anchors, KeyUsage::client_auth()));
+    assert_eq!(result, Err(Error::InvalidKey("certificate key type mismatch")));
+
+    let another = TrustAnchors::from_der(&[include_bytes!("x5c-root-another.der")]).unwrap();
+    let result = jws::decode::<Claims>(&token, VerifyWithX5c::<ES256>::new(&another, KeyUsage::client_auth()));
+    assert_eq!(result, Err(Error::Rejected("untrusted certificate chain")));
+
+    let token = jws::encode::<ES256>(Header::default(), &claims, include_bytes!("x5c-leaf.pk8")).unwrap();
+    let result = jws::decode::<Claims>(&token, VerifyWithX5c::<ES256>::new(&anchors, KeyUsage::client_auth()));
+    assert_eq!(result, Err(Error::InvalidKey("missing x5c")));
+
+    let header = Header::default()
+        .with_x5c_der(&[include_bytes!("x5c-leaf.der")])
+        .with_x5t_s256(x509::thumbprint_sha256(include_bytes!("x5c-root.der")));
+    let token = jws::encode::<ES256>(header, &claims, include_bytes!("x5c-leaf.pk8")).unwrap();
+    let result = jws::decode::<Claims>(&token, VerifyWithX5c::<ES256>::new(&anchors, KeyUsage::client_auth()));
+    assert_eq!(result, Err(Error::InvalidKey("certificate thumbprint mismatch")));
+}
+
+#[test]
+fn test_verify_x5c_key_usage() {
+    let header = Header::default().with_x5c_der(&[include_bytes!("x5c-eku-leaf.der")]);
+    let token = jws::encode::<ES256>(header, &Claims::default(), include_bytes!("x5c-eku-leaf.pk8")).unwrap();
+    let anchors = TrustAnchors::from_der(&[include_bytes!("x5c-eku-root.der")]).unwrap();
+
+    // the leaf is for server authentication only
+    assert!(jws::decode::<Claims>(&token, VerifyWithX5c::<ES256>::new(&anchors, KeyUsage::server_auth())).is_ok());
+    let result = jws::decode::<Claims>(&token, VerifyWithX5c::<ES256>::new(&anchors, KeyUsage::client_auth()));
+    assert_eq!(result, Err(Error::Rejected("untrusted certificate chain")));
+
+    let token = jws::encode::<HS256>(Header::default().with_x5c_der(&[include_bytes!("x5c-eku-leaf.der")]), &Claims::default(), b"secret").unwrap();
+    let result = jws::decode::<Claims>(&token, VerifyWithX5c::<HS256>::new(&anchors, KeyUsage::server_auth()));
+    assert_eq!(result, Err(Error::InvalidKey("certificate key type mismatch")));
+}
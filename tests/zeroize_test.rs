@@ -0,0 +1,31 @@
+//! Tests of the `zeroize` feature.
+
+#![cfg(all(feature = "zeroize", feature = "alg-hs", feature = "alg-rs"))]
+
+use jwts::{Claims, Error, jws};
+use jwts::crypto::RsaKeyPair;
+use jwts::jws::{Header, VerifyWith};
+use jwts::jws::alg::{HS256, RS256};
+use jwts::secret::SecretKey;
+
+#[test]
+fn test_secret_key() {
+    let key = SecretKey::from(b"secret".to_vec());
+    let token = jws::encode::<HS256>(Header::default(), &Claims::default(), &key).unwrap();
+    jws::decode::<Claims>(&token, VerifyWith::<HS256>(&key)).unwrap();
+    jws::decode::<Claims>(&token, VerifyWith::<HS256>(b"secret")).unwrap();
+    assert_eq!(&*key.clone(), b"secret");
+    assert!(!format!("{:?}", key).contains("secret"));
+}
+
+#[test]
+fn test_secret_key_from_pem() {
+    let key = SecretKey::from_pem(include_str!("rsa-pri.pem"), "RSA PRIVATE KEY").unwrap();
+    assert_eq!(&*key, include_bytes!("rsa-pri.der"));
+    let key_pair = RsaKeyPair::from_der(&key).unwrap();
+    let token = jws::encode::<RS256>(Header::default(), &Claims::default(), &key_pair).unwrap();
+    jws::decode::<Claims>(&token, VerifyWith::<RS256>(include_bytes!("rsa-pub.der"))).unwrap();
+
+    let missing = SecretKey::from_pem(include_str!("rsa-pri.pem"), "PRIVATE KEY");
+    assert_eq!(missing.unwrap_err(), Error::InvalidKey("no PEM block of the label"));
+}